@@ -2,7 +2,8 @@ use together::{
     constants::*,
     db::{get_db_pool, DatabaseConfig},
     utils::{init_logging, config::Config},
-    db::{attestations, users},
+    db::{attestations, pairs, signatures, signers, users},
+    services::{events::{self, PairingEvent}, merkle, webhooks::{self, WebhookEvent}},
 };
 use alloy::{
     primitives::{B256, U256, Address},
@@ -12,7 +13,7 @@ use alloy::{
 use anyhow::Result;
 use sqlx::PgPool;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time;
 use tracing::{error, info, warn};
 
@@ -23,6 +24,8 @@ struct TogetherEvent {
     timestamp: u64,
     tx_hash: String,
     block_number: u64,
+    block_hash: Option<String>,
+    log_index: Option<u64>,
 }
 
 #[derive(Debug)]
@@ -65,6 +68,14 @@ async fn run_attestation_watcher(
     let mut interval = time::interval(Duration::from_secs(ATTESTATION_WATCHER_FETCH_INTERVAL_SECS));
     let mut iter_count: usize = 0;
     let mut latest_known_block = get_latest_block(&provider).await?;
+    let mut last_snapshot_at: Option<Instant> = None;
+    
+    // Pairs attested before collectible token ids existed
+    match pairs::backfill_pairs(&pool).await {
+        Ok(0) => {}
+        Ok(added) => info!("🎴 Registered {} pairs missing a token id", added),
+        Err(e) => warn!("⚠️ Failed to backfill pair token ids: {}", e),
+    }
     
     loop {
         interval.tick().await;
@@ -100,6 +111,19 @@ async fn run_attestation_watcher(
             latest_known_block
         };
         
+        // Periodically publish a Merkle root over everything processed so far
+        if last_snapshot_at.is_none_or(|at| at.elapsed() >= Duration::from_secs(MERKLE_SNAPSHOT_INTERVAL_SECS)) {
+            last_snapshot_at = Some(Instant::now());
+            match merkle::take_snapshot(&pool, watcher_state.last_processed_block as i64).await {
+                Ok(Some(snapshot)) => info!(
+                    "🌳 Merkle snapshot {}: root {} over {} attestations at block {}",
+                    snapshot.id, snapshot.root, snapshot.leaf_count, snapshot.block_height
+                ),
+                Ok(None) => info!("🌳 Attestations unchanged since last Merkle snapshot"),
+                Err(e) => error!("❌ Failed to take Merkle snapshot: {}", e),
+            }
+        }
+        
         // Skip if we're caught up
         if watcher_state.last_processed_block >= current_latest {
             continue;
@@ -117,7 +141,7 @@ async fn run_attestation_watcher(
         // Process the block range
         match process_block_range(&provider, &pool, contract_address, from_block, to_block).await {
             Ok(events_processed) => {
-                info!("✅ Successfully processed {} Together events in range {} to {}", events_processed, from_block, to_block);
+                info!("✅ Successfully processed {} contract events in range {} to {}", events_processed, from_block, to_block);
                 
                 // Update watcher state
                 attestations::update_watcher_state(&pool, ATTESTATION_WATCHER_ID, to_block as i64, None).await?;
@@ -157,10 +181,12 @@ async fn process_block_range(
     to_block: u64,
 ) -> Result<usize> {
     let together_topic: B256 = TOGETHER_EVENT_TOPIC.parse()?;
+    let signer_allowed_topic: B256 = SIGNER_ALLOWED_TOPIC.parse()?;
+    let signer_denied_topic: B256 = SIGNER_DENIED_TOPIC.parse()?;
     
     let filter = Filter::new()
         .address(contract_address)
-        .event_signature(together_topic)
+        .event_signature(vec![together_topic, signer_allowed_topic, signer_denied_topic])
         .from_block(from_block)
         .to_block(to_block);
     
@@ -170,7 +196,12 @@ async fn process_block_range(
     let mut events_processed = 0;
     
     for log in logs {
-        if let Err(e) = process_together_log(pool, &log).await {
+        let result = match log.topic0() {
+            Some(topic) if *topic == signer_allowed_topic => process_signer_log(pool, &log, true).await,
+            Some(topic) if *topic == signer_denied_topic => process_signer_log(pool, &log, false).await,
+            _ => process_together_log(pool, &log).await,
+        };
+        if let Err(e) = result {
             error!("Failed to process log in tx {}: {}", log.transaction_hash.unwrap_or_default(), e);
         } else {
             events_processed += 1;
@@ -204,6 +235,39 @@ async fn process_together_log(pool: &PgPool, log: &Log) -> Result<()> {
         Ok(attestation) => {
             info!("✅ Successfully inserted attestation with ID: {}", attestation.id);
             
+            // Keep the event's exact position for proof certificates
+            if let (Some(block_hash), Some(log_index)) = (&event.block_hash, event.log_index)
+                && let Err(e) = attestations::set_attestation_log_position(pool, attestation.id, block_hash, log_index as i64).await
+            {
+                warn!("Failed to record log position for attestation {}: {}", attestation.id, e);
+            }
+            
+            // Link the on-chain event back to the signature we issued for it
+            match signatures::mark_consumed_by_event(
+                pool,
+                &event.address_1,
+                &event.address_2,
+                event.timestamp as i64,
+                &event.tx_hash,
+            ).await {
+                Ok(Some(issuance)) => info!("🧾 Marked signature nonce {} as consumed", issuance.nonce),
+                Ok(None) => warn!("🧾 No issued signature found for this Together event"),
+                Err(e) => warn!("Failed to mark signature issuance consumed: {}", e),
+            }
+            
+            // Partners hear about every confirmed attestation, not just ones we relayed
+            let confirmed_webhook = WebhookEvent::confirmed(
+                &event.address_1,
+                &event.address_2,
+                &event.tx_hash,
+                event.block_number as i64,
+            );
+            match webhooks::enqueue(pool, &confirmed_webhook).await {
+                Ok(0) => {}
+                Ok(queued) => info!("🪝 Queued {} confirmed webhooks", queued),
+                Err(e) => warn!("Failed to queue confirmed webhooks: {}", e),
+            }
+            
             // Try to mark the oldest unprocessed optimistic connection as processed
             // First get users by wallet addresses
             if let (Ok(Some(user1)), Ok(Some(user2))) = (
@@ -217,6 +281,16 @@ async fn process_together_log(pool: &PgPool, log: &Log) -> Result<()> {
                 } else {
                     info!("🔗 Marked oldest optimistic connection as processed for users {} & {}", user1.id, user2.id);
                 }
+                
+                let confirmed = PairingEvent::Confirmed {
+                    user_id_1: user1.id,
+                    user_id_2: user2.id,
+                    tx_hash: event.tx_hash.clone(),
+                    block_number: event.block_number as i64,
+                };
+                if let Err(e) = events::publish(pool, &confirmed).await {
+                    warn!("Failed to publish confirmed event: {}", e);
+                }
             }
         }
         Err(e) => {
@@ -228,6 +302,29 @@ async fn process_together_log(pool: &PgPool, log: &Log) -> Result<()> {
     Ok(())
 }
 
+// SignerAllowed/SignerDenied(address indexed account): the server and connection checker
+// stop picking a key as soon as it's recorded as denied
+async fn process_signer_log(pool: &PgPool, log: &Log, allowed: bool) -> Result<()> {
+    let account = log.topics().get(1)
+        .ok_or_else(|| anyhow::anyhow!("Invalid signer event: missing account topic"))?;
+    let address = format!("0x{}", hex::encode(&account.as_slice()[12..32]));
+    
+    let tx_hash = log.transaction_hash
+        .ok_or_else(|| anyhow::anyhow!("Missing transaction hash"))?
+        .to_string();
+    let block_number = log.block_number
+        .ok_or_else(|| anyhow::anyhow!("Missing block number"))?;
+    
+    let status = signers::upsert_signer_status(pool, &address, allowed, &tx_hash, block_number as i64).await?;
+    if status.allowed {
+        info!("🔑 Signer {} allowed (tx: {})", address, tx_hash);
+    } else {
+        warn!("🔒 Signer {} denied (tx: {}), it will no longer be used", address, tx_hash);
+    }
+    
+    Ok(())
+}
+
 fn parse_together_event(log: &Log) -> Result<TogetherEvent> {
     // TogetherEvent(address indexed onBehalfOf, address indexed togetherWith, uint256 indexed timestamp)
     if log.topics().len() != 4 {
@@ -257,6 +354,8 @@ fn parse_together_event(log: &Log) -> Result<TogetherEvent> {
         timestamp,
         tx_hash,
         block_number,
+        block_hash: log.block_hash.map(|hash| hash.to_string()),
+        log_index: log.log_index,
     })
 }
 
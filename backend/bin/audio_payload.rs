@@ -0,0 +1,71 @@
+use anyhow::Result;
+use alloy::primitives::Address;
+use chrono::Utc;
+use clap::{value_parser, Arg, Command};
+use std::fs;
+use together::{audio::{self, AudioPayload}, constants::{AUDIO_MIN_SAMPLE_RATE, AUDIO_SAMPLE_RATE}};
+
+fn main() -> Result<()> {
+    let matches = Command::new("audio-payload")
+        .about("Encode or decode Together audio pairing payloads")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("encode")
+                .about("Write a pairing payload to a WAV file")
+                .arg(Arg::new("address").long("address").short('a').required(true).help("Wallet address to broadcast"))
+                .arg(Arg::new("code").long("code").short('c').required(true).help("Current rolling pairing code"))
+                .arg(Arg::new("timestamp").long("timestamp").short('t').help("Unix timestamp (defaults to now)"))
+                .arg(
+                    Arg::new("sample-rate")
+                        .long("sample-rate")
+                        .value_parser(value_parser!(u32).range(AUDIO_MIN_SAMPLE_RATE as i64..))
+                        .help("Output sample rate in Hz (defaults to 48000)"),
+                )
+                .arg(Arg::new("out").long("out").short('o').required(true).help("Output WAV path")),
+        )
+        .subcommand(
+            Command::new("decode")
+                .about("Decode a pairing payload from a WAV recording")
+                .arg(Arg::new("input").required(true).help("WAV file recorded from a client"))
+                .arg(Arg::new("secret").long("secret").help("Broadcaster's pairing secret, to validate the rolling code")),
+        )
+        .get_matches();
+
+    match matches.subcommand() {
+        Some(("encode", args)) => {
+            let address: Address = args.get_one::<String>("address").unwrap().parse()?;
+            let code: u32 = args.get_one::<String>("code").unwrap().parse()?;
+            let timestamp: u32 = match args.get_one::<String>("timestamp") {
+                Some(t) => t.parse()?,
+                None => Utc::now().timestamp() as u32,
+            };
+            let sample_rate = args.get_one::<u32>("sample-rate")
+                .copied()
+                .unwrap_or(AUDIO_SAMPLE_RATE);
+            let out = args.get_one::<String>("out").unwrap();
+
+            let payload = AudioPayload::new(address, code, timestamp);
+            fs::write(out, audio::encode_wav(&payload, sample_rate))?;
+            println!("Wrote {} ({} payload bytes: 0x{})", out, audio::AUDIO_PAYLOAD_LEN, hex::encode(payload.to_bytes()));
+        }
+        Some(("decode", args)) => {
+            let input = args.get_one::<String>("input").unwrap();
+            let payload = audio::decode_wav(&fs::read(input)?)?;
+
+            println!("Version:      {}", payload.version);
+            println!("Address:      {}", payload.address);
+            println!("Rolling code: {:06}", payload.rolling_code);
+            println!("Timestamp:    {}", payload.timestamp);
+
+            if let Some(secret) = args.get_one::<String>("secret") {
+                match payload.validate_rolling_code(secret, Utc::now()) {
+                    Ok(()) => println!("Rolling code: valid"),
+                    Err(e) => println!("Rolling code: INVALID ({})", e),
+                }
+            }
+        }
+        _ => unreachable!("subcommand is required"),
+    }
+
+    Ok(())
+}
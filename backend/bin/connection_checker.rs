@@ -1,7 +1,9 @@
 use together::{
-    db::{get_db_pool, DatabaseConfig, users},
+    constants::{PAIRING_CODE_FAILURE_WINDOW_SECS, SIGNATURE_SOURCE_CONNECTION_CHECKER},
+    db::{get_db_pool, DatabaseConfig, pairing, proximity as proximity_db, sessions, users},
+    models::NewSignatureIssuance,
     utils::{init_logging, config::Config},
-    services::contract::ContractService,
+    services::{account_deletion, contract::{ContractService, ReceiptStatus}, events::{self, PairingEvent}, proximity, signatures::{self, QuotaSubject}, signers, webhooks::{self, WebhookEvent}},
 };
 use anyhow::Result;
 use sqlx::PgPool;
@@ -21,6 +23,9 @@ async fn main() -> Result<()> {
     let db_config = DatabaseConfig::from_env()?;
     let pool = get_db_pool(&db_config).await?;
     
+    // Load signers now so keystore password prompts happen at startup
+    config.signers.load_all()?;
+    
     // Setup contract service
     let contract_service = ContractService::new(
         config.rpc_url.clone(),
@@ -28,6 +33,9 @@ async fn main() -> Result<()> {
         config.alchemy_api_key.clone(),
     ).await?;
     
+    // Send partner webhooks queued by the checker and the attestation watcher
+    webhooks::spawn_dispatcher(pool.clone(), webhooks::http_client()?);
+    
     // Run the connection checker
     run_connection_checker(pool, contract_service, config).await?;
     
@@ -56,6 +64,28 @@ async fn run_connection_checker(pool: PgPool, contract_service: ContractService,
             }
         }
         
+        if iter_count % 120 == 1 { // Every 10 minutes, starting at startup
+            match sessions::delete_expired_auth_rows(&pool).await {
+                Ok(0) => {}
+                Ok(deleted) => info!("🧹 Cleaned up {} expired auth rows", deleted),
+                Err(e) => error!("❌ Failed to clean up expired auth nonces, tickets and sessions: {}", e),
+            }
+            if let Err(e) = pairing::delete_stale_pairing_code_failures(&pool, PAIRING_CODE_FAILURE_WINDOW_SECS).await {
+                error!("❌ Failed to clean up stale pairing code failures: {}", e);
+            }
+        }
+        
+        if let Err(e) = pairing::delete_expired_pairing_codes(&pool).await {
+            error!("❌ Failed to clean up expired pairing codes: {}", e);
+        }
+        
+        // Erase accounts whose deletion grace period has ended
+        match account_deletion::process_due_deletions(&pool).await {
+            Ok(0) => {}
+            Ok(erased) => info!("🗑️ Erased {} deleted accounts", erased),
+            Err(e) => error!("❌ Failed to process account deletions: {}", e),
+        }
+        
         // 2. Log unprocessed optimistic connections (for monitoring)
         if iter_count % 60 == 0 { // Log every 5 minutes
             match users::get_unprocessed_optimistic_connections(&pool).await {
@@ -114,10 +144,43 @@ async fn process_connection_match(
         return Ok(());
     }
     
+    // Require challenge-response proof that they were together; the pending
+    // connections stay in place so the match is retried once proof arrives
+    let Some(response_ids) = proximity::check_proximity(pool, config.proximity_policy, user_1.id, user_2.id).await? else {
+        info!("📡 Waiting for proximity proof between users {} & {}", user_1.id, user_2.id);
+        return Ok(());
+    };
+    
+    let exceeded = signatures::check_quota(&mut *pool.acquire().await?, QuotaSubject::Relayed, &user_1.wallet_address, &user_2.wallet_address).await?;
+    if let Some(exceeded) = exceeded {
+        info!("⏳ {}, skipping relay for users {} & {}", exceeded.message(), user_1.id, user_2.id);
+        return Ok(());
+    }
+    
+    let current_timestamp = Utc::now().timestamp() as u64;
+    
     // Create optimistic connection first (shows users they're connected while tx is pending)
     match users::create_optimistic_connection(pool, user_1.id, user_2.id).await {
         Ok(optimistic) => {
+            if !response_ids.is_empty()
+                && let Err(e) = proximity_db::record_evidence(
+                    pool,
+                    user_1.id,
+                    user_2.id,
+                    current_timestamp as i64,
+                    Some(optimistic.id),
+                    &response_ids,
+                ).await
+            {
+                error!("❌ Failed to record proximity evidence: {}", e);
+            }
             info!("🎯 Created optimistic connection with ID: {}", optimistic.id);
+            publish_event(pool, PairingEvent::Matched {
+                optimistic_id: optimistic.id,
+                user_id_1: user_1.id,
+                user_id_2: user_2.id,
+            }).await;
+            enqueue_webhook(pool, WebhookEvent::matched(&user_1.wallet_address, &user_2.wallet_address)).await;
         }
         Err(e) => {
             error!("❌ Failed to create optimistic connection: {}", e);
@@ -136,20 +199,99 @@ async fn process_connection_match(
     
     info!("🧹 Cleaned up matched pending connections");
     
-    // Now send transaction to contract
-    let current_timestamp = Utc::now().timestamp() as u64;
+    // Sign and record the issuance in the ledger before relaying
+    // The key that signs also sends the transaction and pays its gas, as before rotation
+    let signed = match signers::pick_signer(pool, &config.signers).await {
+        Ok(signer) => contract_service.sign_together_server_side(
+            signer.clone(),
+            &user_1.wallet_address,
+            &user_2.wallet_address,
+            current_timestamp,
+        ).await.map(|signed| (signed, signer)),
+        Err(e) => Err(e),
+    };
+    let ((signature_data, signer_address), relayer) = match signed {
+        Ok(signed) => signed,
+        Err(e) => {
+            error!("❌ Failed to sign attestation: {}", e);
+            publish_event(pool, PairingEvent::Failed {
+                user_id_1: user_1.id,
+                user_id_2: user_2.id,
+                reason: "Attestation could not be signed".to_string(),
+            }).await;
+            enqueue_webhook(pool, WebhookEvent::relay_failed(
+                &user_1.wallet_address,
+                &user_2.wallet_address,
+                "Attestation could not be signed",
+            )).await;
+            return Ok(());
+        }
+    };
+    
+    let issuance = NewSignatureIssuance {
+        nonce: signatures::format_nonce(signature_data.nonce),
+        on_behalf_of: user_1.wallet_address.clone(),
+        together_with: user_2.wallet_address.clone(),
+        attestation_timestamp: current_timestamp as i64,
+        deadline: signature_data.deadline as i64,
+        signer_address: signer_address.to_string(),
+        source: SIGNATURE_SOURCE_CONNECTION_CHECKER,
+        requester_ip: None,
+        session_id: None,
+        requester: None,
+    };
+    // A signature the ledger doesn't know about is never relayed
+    let refusal = match signatures::record_within_quota(pool, &issuance).await {
+        Ok(None) => None,
+        Ok(Some(exceeded)) => {
+            info!("⏳ {}, not relaying for users {} & {}", exceeded.message(), user_1.id, user_2.id);
+            Some("Signature quota reached")
+        }
+        Err(e) => {
+            error!("❌ Failed to record signature issuance: {}", e);
+            Some("Attestation could not be recorded")
+        }
+    };
+    if let Some(reason) = refusal {
+        publish_event(pool, PairingEvent::Failed {
+            user_id_1: user_1.id,
+            user_id_2: user_2.id,
+            reason: reason.to_string(),
+        }).await;
+        enqueue_webhook(pool, WebhookEvent::relay_failed(&user_1.wallet_address, &user_2.wallet_address, reason)).await;
+        return Ok(());
+    }
     
-    match contract_service.submit_together_transaction_server_signed(
-        &config.private_key_signer,
-        &user_1.wallet_address,
-        &user_2.wallet_address,
-        current_timestamp,
-    ).await {
-        Ok(tx_hash) => {
-            info!("✅ Successfully sent attestation transaction: {}", tx_hash);
+    // Now send transaction to contract
+    match contract_service.submit_signed_together(relayer, signature_data).await {
+        Ok(sent) => {
+            info!("✅ Successfully sent attestation transaction: {}", sent.tx_hash);
+            publish_event(pool, PairingEvent::TxSent {
+                user_id_1: user_1.id,
+                user_id_2: user_2.id,
+                tx_hash: sent.tx_hash.clone(),
+            }).await;
+            if sent.receipt_status == ReceiptStatus::Reverted {
+                publish_event(pool, PairingEvent::Failed {
+                    user_id_1: user_1.id,
+                    user_id_2: user_2.id,
+                    reason: "Attestation transaction reverted".to_string(),
+                }).await;
+                enqueue_webhook(pool, WebhookEvent::reverted(&user_1.wallet_address, &user_2.wallet_address, &sent.tx_hash)).await;
+            }
         }
         Err(e) => {
             error!("❌ Failed to send attestation transaction: {}", e);
+            publish_event(pool, PairingEvent::Failed {
+                user_id_1: user_1.id,
+                user_id_2: user_2.id,
+                reason: "Attestation transaction could not be sent".to_string(),
+            }).await;
+            enqueue_webhook(pool, WebhookEvent::relay_failed(
+                &user_1.wallet_address,
+                &user_2.wallet_address,
+                "Attestation transaction could not be sent",
+            )).await;
             
             // The optimistic connection will expire naturally if the tx never gets sent
             // This way users still see they're "connected" for a bit even if tx fails
@@ -158,3 +300,17 @@ async fn process_connection_match(
     
     Ok(())
 }
+
+async fn publish_event(pool: &PgPool, event: PairingEvent) {
+    if let Err(e) = events::publish(pool, &event).await {
+        error!("❌ Failed to publish {} event: {}", event.name(), e);
+    }
+}
+
+async fn enqueue_webhook(pool: &PgPool, event: WebhookEvent) {
+    match webhooks::enqueue(pool, &event).await {
+        Ok(0) => {}
+        Ok(queued) => info!("🪝 Queued {} {} webhooks", queued, event.event_type.as_str()),
+        Err(e) => error!("❌ Failed to queue {} webhooks: {}", event.event_type.as_str(), e),
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use alloy::primitives::Address;
+use clap::{Arg, Command};
+use std::{fs::File, io::{BufWriter, Write}};
+use together::{
+    db::{get_db_pool, DatabaseConfig},
+    services::export,
+    utils::init_logging,
+};
+use tracing::info;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    init_logging();
+
+    let matches = Command::new("export-user-data")
+        .about("Export everything held about a wallet, in the same archive users download themselves")
+        .arg(Arg::new("address").required(true).help("Wallet address to export"))
+        .arg(
+            Arg::new("output")
+                .long("output")
+                .short('o')
+                .help("Output ZIP file (defaults to together-export-<address>.zip)"),
+        )
+        .get_matches();
+
+    let address = matches.get_one::<String>("address").unwrap();
+    let _: Address = address.parse()?;
+    let output = matches.get_one::<String>("output")
+        .cloned()
+        .unwrap_or_else(|| format!("together-export-{}.zip", address.to_lowercase()));
+
+    let db_config = DatabaseConfig::from_env()?;
+    let pool = get_db_pool(&db_config).await?;
+
+    let data = export::collect_user_data(&pool, address).await?;
+    info!(
+        "📦 Exporting {} attestations, {} signature issuances for {}",
+        data.attestations.len(),
+        data.signature_issuances.len(),
+        address
+    );
+
+    let mut writer = export::write_archive(&data, BufWriter::new(File::create(&output)?))?;
+    writer.flush()?;
+    info!("✅ Wrote {}", output);
+
+    Ok(())
+}
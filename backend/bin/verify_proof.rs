@@ -0,0 +1,66 @@
+use anyhow::Result;
+use alloy::primitives::Address;
+use clap::{Arg, ArgAction, Command};
+use std::{fs, io::Read};
+use together::{
+    constants::{TOGETHER_CONTRACT_ADDRESS, WORLDCHAIN_MAINNET_CHAIN_ID},
+    proof::{self, ProofCertificate},
+};
+
+fn main() -> Result<()> {
+    let matches = Command::new("verify-proof")
+        .about("Verify a Together proof certificate offline")
+        .arg(Arg::new("certificate").required(true).help("Certificate JSON file, or - for stdin"))
+        .arg(
+            Arg::new("signer")
+                .long("signer")
+                .short('s')
+                .required(true)
+                .action(ArgAction::Append)
+                .help("Trusted attestation signer address (repeatable)"),
+        )
+        .arg(Arg::new("contract").long("contract").default_value(TOGETHER_CONTRACT_ADDRESS).help("Expected Together contract address"))
+        .arg(Arg::new("chain-id").long("chain-id").help("Expected chain ID (defaults to Worldchain mainnet)"))
+        .get_matches();
+
+    let path = matches.get_one::<String>("certificate").unwrap();
+    let json = if path == "-" {
+        let mut json = String::new();
+        std::io::stdin().read_to_string(&mut json)?;
+        json
+    } else {
+        fs::read_to_string(path)?
+    };
+    let certificate: ProofCertificate = serde_json::from_str(&json)?;
+
+    let trusted_signers = matches.get_many::<String>("signer").unwrap()
+        .map(|signer| signer.parse::<Address>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let contract: Address = matches.get_one::<String>("contract").unwrap().parse()?;
+    let chain_id: u64 = matches.get_one::<String>("chain-id")
+        .map(|id| id.parse())
+        .transpose()?
+        .unwrap_or(WORLDCHAIN_MAINNET_CHAIN_ID);
+
+    println!("Addresses:    {} & {}", certificate.address_1, certificate.address_2);
+    println!("Timestamp:    {}", certificate.timestamp);
+    println!("Transaction:  {} (block {}, log {})", certificate.tx_hash, certificate.block_number, certificate.log_index);
+    println!("Block hash:   {}", certificate.block_hash);
+    println!("Contract:     {} on chain {}", certificate.contract_address, certificate.chain_id);
+    println!("Signer:       {}", certificate.signer);
+
+    if certificate.contract_address != contract || certificate.chain_id != chain_id {
+        println!("Result:       INVALID (certificate is for a different contract or chain)");
+        std::process::exit(1);
+    }
+
+    match proof::verify_certificate(&certificate, &trusted_signers) {
+        Ok(()) => println!("Result:       valid"),
+        Err(e) => {
+            println!("Result:       INVALID ({})", e);
+            std::process::exit(1);
+        }
+    }
+
+    Ok(())
+}
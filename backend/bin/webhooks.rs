@@ -0,0 +1,177 @@
+use anyhow::Result;
+use alloy::primitives::Address;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use together::{
+    db::{get_db_pool, DatabaseConfig, webhooks as webhooks_db},
+    services::webhooks::{self, WebhookEvent, WebhookEventType},
+    utils::init_logging,
+    Uuid,
+};
+use tracing::{error, info};
+
+fn endpoint_id_arg() -> Arg {
+    Arg::new("id").required(true).help("Endpoint id")
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    init_logging();
+
+    let matches = Command::new("webhooks")
+        .about("Manage partner webhook endpoints and inspect their deliveries")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("add")
+                .about("Register an endpoint and print its signing secret")
+                .arg(Arg::new("url").required(true).help("HTTPS URL deliveries are POSTed to"))
+                .arg(Arg::new("description").long("description").short('d').help("Who the endpoint belongs to"))
+                .arg(
+                    Arg::new("event")
+                        .long("event")
+                        .short('e')
+                        .action(ArgAction::Append)
+                        .help("Only send this event type (matched, confirmed, relay_failed, reverted); repeatable, defaults to all"),
+                )
+                .arg(
+                    Arg::new("address")
+                        .long("address")
+                        .short('a')
+                        .action(ArgAction::Append)
+                        .help("Only send events involving this wallet; repeatable, defaults to all"),
+                ),
+        )
+        .subcommand(Command::new("list").about("List registered endpoints"))
+        .subcommand(Command::new("disable").about("Stop queueing events for an endpoint").arg(endpoint_id_arg()))
+        .subcommand(Command::new("enable").about("Resume queueing events for an endpoint").arg(endpoint_id_arg()))
+        .subcommand(Command::new("remove").about("Delete an endpoint and its delivery log").arg(endpoint_id_arg()))
+        .subcommand(
+            Command::new("deliveries")
+                .about("Show an endpoint's recent deliveries")
+                .arg(endpoint_id_arg())
+                .arg(
+                    Arg::new("limit")
+                        .long("limit")
+                        .short('n')
+                        .value_parser(clap::value_parser!(i64))
+                        .default_value("20"),
+                ),
+        )
+        .subcommand(
+            Command::new("retry")
+                .about("Queue a failed delivery again")
+                .arg(Arg::new("delivery").required(true).help("Delivery id")),
+        )
+        .subcommand(Command::new("ping").about("Send a signed ping to an endpoint right now").arg(endpoint_id_arg()))
+        .get_matches();
+
+    let db_config = DatabaseConfig::from_env()?;
+    let pool = get_db_pool(&db_config).await?;
+
+    let parse_id = |matches: &ArgMatches, name: &str| -> Result<Uuid> {
+        Ok(matches.get_one::<String>(name).unwrap().parse()?)
+    };
+
+    match matches.subcommand() {
+        Some(("add", sub)) => {
+            let url = sub.get_one::<String>("url").unwrap();
+            let _: reqwest::Url = url.parse()?;
+            let event_types = sub.get_many::<String>("event")
+                .unwrap_or_default()
+                .map(|event| event.parse::<WebhookEventType>().map(|event_type| event_type.as_str().to_string()))
+                .collect::<Result<Vec<_>>>()?;
+            let addresses = sub.get_many::<String>("address")
+                .unwrap_or_default()
+                .map(|address| address.parse::<Address>().map(|_| address.to_lowercase()))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let secret = webhooks::generate_secret();
+            let endpoint = webhooks_db::create_endpoint(
+                &pool,
+                url,
+                &secret,
+                sub.get_one::<String>("description").map(String::as_str),
+                &event_types,
+                &addresses,
+            ).await?;
+
+            info!("🪝 Registered endpoint {} for {}", endpoint.id, endpoint.url);
+            info!("🔑 Signing secret (shown once): {}", secret);
+        }
+        Some(("list", _)) => {
+            let endpoints = webhooks_db::list_endpoints(&pool).await?;
+            if endpoints.is_empty() {
+                info!("No webhook endpoints registered");
+            }
+            for endpoint in endpoints {
+                let describe = |filter: &[String]| if filter.is_empty() { "all".to_string() } else { filter.join(", ") };
+                info!(
+                    "{} {} {} | events: {} | addresses: {}{}",
+                    if endpoint.active { "🟢" } else { "⏸️" },
+                    endpoint.id,
+                    endpoint.url,
+                    describe(&endpoint.event_types),
+                    describe(&endpoint.addresses),
+                    endpoint.description.map(|d| format!(" | {}", d)).unwrap_or_default(),
+                );
+            }
+        }
+        Some((command @ ("disable" | "enable"), sub)) => {
+            let id = parse_id(sub, "id")?;
+            if webhooks_db::set_endpoint_active(&pool, id, command == "enable").await? {
+                info!("✅ Endpoint {} {}d", id, command);
+            } else {
+                error!("❌ No endpoint {}", id);
+            }
+        }
+        Some(("remove", sub)) => {
+            let id = parse_id(sub, "id")?;
+            if webhooks_db::delete_endpoint(&pool, id).await? {
+                info!("🗑️ Removed endpoint {}", id);
+            } else {
+                error!("❌ No endpoint {}", id);
+            }
+        }
+        Some(("deliveries", sub)) => {
+            let id = parse_id(sub, "id")?;
+            let limit = *sub.get_one::<i64>("limit").unwrap();
+            for delivery in webhooks_db::get_recent_deliveries(&pool, id, limit).await? {
+                info!(
+                    "{} {} {} | {} attempts | HTTP {} | {}",
+                    delivery.created_at.to_rfc3339(),
+                    delivery.id,
+                    delivery.event_type,
+                    delivery.attempts,
+                    delivery.last_response_status.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string()),
+                    match delivery.status.as_str() {
+                        "pending" => format!("pending, next attempt {}", delivery.next_attempt_at.to_rfc3339()),
+                        _ => delivery.last_error.map(|e| format!("{}: {}", delivery.status, e)).unwrap_or(delivery.status),
+                    },
+                );
+            }
+        }
+        Some(("retry", sub)) => {
+            let id = parse_id(sub, "delivery")?;
+            if webhooks_db::retry_delivery(&pool, id).await? {
+                info!("🔁 Delivery {} queued again", id);
+            } else {
+                error!("❌ No failed delivery {}", id);
+            }
+        }
+        Some(("ping", sub)) => {
+            let id = parse_id(sub, "id")?;
+            let endpoint = webhooks_db::get_endpoint(&pool, id).await?
+                .ok_or_else(|| anyhow::anyhow!("No endpoint {}", id))?;
+
+            let event = WebhookEvent::ping();
+            let payload = serde_json::to_string(&event)?;
+            let client = webhooks::http_client()?;
+            match webhooks::send_webhook(&client, &endpoint.url, &endpoint.secret, event.id, event.event_type.as_str(), &payload).await {
+                Ok(status) => info!("✅ {} answered HTTP {}", endpoint.url, status),
+                Err(e) => error!("❌ Ping to {} failed: {}", endpoint.url, e),
+            }
+        }
+        _ => unreachable!("subcommand_required"),
+    }
+
+    Ok(())
+}
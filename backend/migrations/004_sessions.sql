@@ -0,0 +1,22 @@
+-- Wallet sign-in nonces issued by the server (consumed once by a matching signature)
+CREATE TABLE auth_nonces (
+    nonce VARCHAR(64) PRIMARY KEY,
+    wallet_address VARCHAR(42) NOT NULL, -- address the nonce was issued to
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL DEFAULT (NOW() + INTERVAL '10 minutes'),
+    consumed_at TIMESTAMPTZ -- set when a session is created from this nonce
+);
+
+-- Bearer sessions for authenticated endpoints
+CREATE TABLE user_sessions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash VARCHAR(66) UNIQUE NOT NULL, -- keccak256 of the bearer token, never the token itself
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_auth_nonces_expires_at ON auth_nonces(expires_at);
+CREATE INDEX idx_user_sessions_user_id ON user_sessions(user_id);
+CREATE INDEX idx_user_sessions_expires_at ON user_sessions(expires_at);
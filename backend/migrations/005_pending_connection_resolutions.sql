@@ -0,0 +1,17 @@
+-- Record of pending connections removed before they matched or expired
+CREATE TABLE pending_connection_resolutions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    pending_connection_id UUID NOT NULL, -- id of the deleted pending_connections row
+    from_user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    to_user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    action VARCHAR(16) NOT NULL, -- 'cancelled' (by sender) or 'declined' (by recipient)
+    reason_code VARCHAR(32) NOT NULL, -- e.g. 'wrong_user', 'not_together', 'spam'
+    suppressed_until TIMESTAMPTZ, -- declines block new requests from the sender until this time
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE pending_connection_resolutions ADD CONSTRAINT chk_resolution_action CHECK (action IN ('cancelled', 'declined'));
+
+CREATE INDEX idx_pending_connection_resolutions_from_to ON pending_connection_resolutions(from_user_id, to_user_id);
+CREATE INDEX idx_pending_connection_resolutions_created_at ON pending_connection_resolutions(created_at);
+CREATE INDEX idx_pending_connection_resolutions_suppressed ON pending_connection_resolutions(suppressed_until) WHERE suppressed_until IS NOT NULL;
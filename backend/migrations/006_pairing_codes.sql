@@ -0,0 +1,19 @@
+-- Per-user secret that rotating pairing codes are derived from
+CREATE TABLE pairing_secrets (
+    user_id INTEGER PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret VARCHAR(64) NOT NULL, -- hex-encoded 32 byte secret, never sent to clients
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Codes that have been shown to a user, so a typed code resolves with an index lookup
+CREATE TABLE pairing_codes (
+    code VARCHAR(16) NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    window_index BIGINT NOT NULL, -- unix time / rotation period
+    expires_at TIMESTAMPTZ NOT NULL, -- end of the window plus a short grace period
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, window_index)
+);
+
+CREATE INDEX idx_pairing_codes_code ON pairing_codes(code);
+CREATE INDEX idx_pairing_codes_expires_at ON pairing_codes(expires_at);
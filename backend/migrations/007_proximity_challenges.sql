@@ -0,0 +1,38 @@
+-- Nonces issued to a user's device to broadcast to the person they're with
+CREATE TABLE proximity_challenges (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    issuer_user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    nonce VARCHAR(16) NOT NULL, -- 8 hex chars so it fits the audio payload's rolling code slot
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+-- Reports from the receiving device that it heard/saw a challenge nonce
+CREATE TABLE proximity_responses (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    challenge_id UUID NOT NULL REFERENCES proximity_challenges(id) ON DELETE CASCADE,
+    responder_user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    latency_ms BIGINT NOT NULL, -- time between issuing the challenge and receiving the report
+    consumed_at TIMESTAMPTZ -- set once the response has backed an attestation
+);
+
+-- Which challenge/response exchanges backed each attestation
+CREATE TABLE proximity_evidence (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id_1 INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_id_2 INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    attestation_timestamp BIGINT NOT NULL, -- timestamp signed into the on-chain attestation
+    optimistic_connection_id UUID, -- set when relayed through the pending connection flow
+    response_ids UUID[] NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE proximity_responses ADD CONSTRAINT uq_proximity_response UNIQUE (challenge_id, responder_user_id);
+
+CREATE INDEX idx_proximity_challenges_nonce ON proximity_challenges(nonce);
+CREATE INDEX idx_proximity_challenges_issuer ON proximity_challenges(issuer_user_id);
+CREATE INDEX idx_proximity_responses_responder ON proximity_responses(responder_user_id);
+CREATE INDEX idx_proximity_responses_received_at ON proximity_responses(received_at);
+CREATE INDEX idx_proximity_evidence_users ON proximity_evidence(user_id_1, user_id_2);
+CREATE INDEX idx_proximity_evidence_timestamp ON proximity_evidence(attestation_timestamp);
@@ -0,0 +1,21 @@
+-- Ledger of every EIP-712 together signature the backend hands out
+CREATE TABLE signature_issuances (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    nonce VARCHAR(66) UNIQUE NOT NULL, -- bytes32 AuthData nonce (0x-prefixed hex)
+    on_behalf_of VARCHAR(42) NOT NULL,
+    together_with VARCHAR(42) NOT NULL,
+    attestation_timestamp BIGINT NOT NULL,
+    deadline BIGINT NOT NULL,
+    signer_address VARCHAR(42) NOT NULL,
+    source VARCHAR(32) NOT NULL, -- 'attest' (client request) or 'connection_checker' (relayed match)
+    requester_ip VARCHAR(64),
+    session_id UUID, -- user_sessions.id when the request was authenticated
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    consumed_at TIMESTAMPTZ, -- when we observed the nonce used on chain
+    consumed_tx_hash VARCHAR(66)
+);
+
+CREATE INDEX idx_signature_issuances_on_behalf_of ON signature_issuances(LOWER(on_behalf_of), created_at);
+CREATE INDEX idx_signature_issuances_together_with ON signature_issuances(LOWER(together_with), created_at);
+CREATE INDEX idx_signature_issuances_timestamp ON signature_issuances(attestation_timestamp);
+CREATE INDEX idx_signature_issuances_unconsumed ON signature_issuances(created_at) WHERE consumed_at IS NULL;
@@ -0,0 +1,8 @@
+-- On-chain signer allow/deny state, as seen by the attestation watcher
+CREATE TABLE signer_status (
+    address VARCHAR(42) PRIMARY KEY, -- lowercase 0x address
+    allowed BOOLEAN NOT NULL,
+    tx_hash VARCHAR(66) NOT NULL,
+    block_number BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+-- Exact position of each Together event on chain, for proof certificates
+ALTER TABLE together_attestations ADD COLUMN block_hash VARCHAR(66);
+ALTER TABLE together_attestations ADD COLUMN log_index BIGINT;
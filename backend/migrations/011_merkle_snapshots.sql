@@ -0,0 +1,10 @@
+-- Merkle roots over every on-chain attestation up to a block height
+CREATE TABLE merkle_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    root VARCHAR(66) NOT NULL,
+    leaf_count BIGINT NOT NULL,
+    block_height BIGINT NOT NULL, -- leaves are attestations with block_number <= block_height
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_merkle_snapshots_block_height ON merkle_snapshots(block_height DESC);
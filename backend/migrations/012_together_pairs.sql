@@ -0,0 +1,9 @@
+-- One row per unique pair, keyed by its deterministic collectible token id
+CREATE TABLE together_pairs (
+    token_id VARCHAR(78) PRIMARY KEY, -- decimal uint256(keccak256(abi.encode(low, high)))
+    address_1 VARCHAR(42) NOT NULL,
+    address_2 VARCHAR(42) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_together_pairs_addresses ON together_pairs(LOWER(address_1), LOWER(address_2));
@@ -0,0 +1,18 @@
+-- Account deletion requests, kept as an audit trail after the user row is erased
+CREATE TABLE account_deletions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id INTEGER REFERENCES users(id) ON DELETE SET NULL, -- cleared when the user row is erased
+    wallet_address VARCHAR(42) NOT NULL, -- kept so the wallet's on-chain attestations stay hidden
+    status VARCHAR(16) NOT NULL DEFAULT 'pending', -- 'pending', 'cancelled' or 'completed'
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    scheduled_for TIMESTAMPTZ NOT NULL, -- end of the grace period
+    cancelled_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ
+);
+
+ALTER TABLE account_deletions ADD CONSTRAINT chk_account_deletion_status CHECK (status IN ('pending', 'cancelled', 'completed'));
+
+-- At most one open request per user
+CREATE UNIQUE INDEX idx_account_deletions_pending_user ON account_deletions(user_id) WHERE status = 'pending';
+CREATE INDEX idx_account_deletions_due ON account_deletions(scheduled_for) WHERE status = 'pending';
+CREATE INDEX idx_account_deletions_erased ON account_deletions(LOWER(wallet_address)) WHERE status = 'completed';
@@ -0,0 +1,33 @@
+-- Partner endpoints notified about attestation lifecycle events
+CREATE TABLE webhook_endpoints (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL, -- HMAC-SHA256 key shared with the partner
+    description TEXT,
+    event_types TEXT[] NOT NULL DEFAULT '{}', -- 'matched', 'confirmed', 'reverted'; empty means all
+    addresses TEXT[] NOT NULL DEFAULT '{}', -- lowercase wallet addresses; empty means all
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One row per event per endpoint, doubling as the delivery log
+CREATE TABLE webhook_deliveries (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    endpoint_id UUID NOT NULL REFERENCES webhook_endpoints(id) ON DELETE CASCADE,
+    event_id UUID NOT NULL,
+    event_type VARCHAR(16) NOT NULL,
+    payload TEXT NOT NULL, -- the exact body that gets signed
+    status VARCHAR(16) NOT NULL DEFAULT 'pending', -- 'pending', 'delivered' or 'failed'
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_response_status INTEGER,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ
+);
+
+ALTER TABLE webhook_deliveries ADD CONSTRAINT chk_webhook_delivery_status CHECK (status IN ('pending', 'delivered', 'failed'));
+
+CREATE UNIQUE INDEX idx_webhook_deliveries_event ON webhook_deliveries(endpoint_id, event_id);
+CREATE INDEX idx_webhook_deliveries_due ON webhook_deliveries(next_attempt_at) WHERE status = 'pending';
+CREATE INDEX idx_webhook_deliveries_endpoint ON webhook_deliveries(endpoint_id, created_at DESC);
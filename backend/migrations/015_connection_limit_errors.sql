@@ -0,0 +1,30 @@
+-- Tag the connection limit triggers' errors so the API can map them to a status code
+-- (check_violation plus a constraint name, like a real CHECK constraint) instead of a 500
+CREATE OR REPLACE FUNCTION check_pending_connection_limit()
+RETURNS TRIGGER AS $$
+BEGIN
+    -- Check if there are already 3+ pending connections between these users
+    IF (SELECT COUNT(*) FROM pending_connections 
+        WHERE (from_user_id = NEW.from_user_id AND to_user_id = NEW.to_user_id)
+           OR (from_user_id = NEW.to_user_id AND to_user_id = NEW.from_user_id)) >= 3 THEN
+        RAISE EXCEPTION 'Too many pending connections between these users'
+            USING ERRCODE = 'check_violation', CONSTRAINT = 'pending_connection_limit';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION check_optimistic_connection_limit()
+RETURNS TRIGGER AS $$
+BEGIN
+    -- Check if there are already 50+ unprocessed optimistic connections between these users
+    IF (SELECT COUNT(*) FROM optimistic_connections 
+        WHERE processed = FALSE
+        AND ((user_id_1 = NEW.user_id_1 AND user_id_2 = NEW.user_id_2)
+           OR (user_id_1 = NEW.user_id_2 AND user_id_2 = NEW.user_id_1))) >= 50 THEN
+        RAISE EXCEPTION 'Too many unprocessed optimistic connections between these users'
+            USING ERRCODE = 'check_violation', CONSTRAINT = 'optimistic_connection_limit';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,8 @@
+-- Rate limit state shared by server replicas when RATE_LIMIT_STORE=postgres
+-- Each key holds only its GCRA theoretical arrival time; rows in the past carry no state and are swept
+CREATE UNLOGGED TABLE rate_limits (
+    key TEXT PRIMARY KEY, -- '<policy>:<ip|wallet|route>:<value>'
+    tat TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_rate_limits_tat ON rate_limits(tat);
@@ -0,0 +1,11 @@
+-- Every node of each snapshot's tree, so proofs are served without rebuilding it
+-- layer 0 holds the sorted leaves; the last layer holds the root
+CREATE TABLE merkle_snapshot_nodes (
+    snapshot_id BIGINT NOT NULL REFERENCES merkle_snapshots(id) ON DELETE CASCADE,
+    layer SMALLINT NOT NULL,
+    position INTEGER NOT NULL,
+    hash VARCHAR(66) NOT NULL,
+    PRIMARY KEY (snapshot_id, layer, position)
+);
+
+CREATE INDEX idx_merkle_snapshot_nodes_leaf ON merkle_snapshot_nodes(snapshot_id, hash) WHERE layer = 0;
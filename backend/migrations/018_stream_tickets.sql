@@ -0,0 +1,10 @@
+-- Single-use tickets that let EventSource clients open an event stream without putting
+-- their session token in a URL (where proxies and access logs would keep it)
+CREATE TABLE stream_tickets (
+    ticket_hash VARCHAR(66) PRIMARY KEY, -- keccak256 of the ticket, never the ticket itself
+    session_id UUID NOT NULL REFERENCES user_sessions(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_stream_tickets_expires_at ON stream_tickets(expires_at);
@@ -0,0 +1,9 @@
+-- Pairing codes a session typed that matched nobody, so guessing codes can be cut off
+CREATE TABLE pairing_code_failures (
+    id BIGSERIAL PRIMARY KEY,
+    session_id UUID NOT NULL REFERENCES user_sessions(id) ON DELETE CASCADE,
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_pairing_code_failures_session ON pairing_code_failures(session_id, attempted_at);
+CREATE INDEX idx_pairing_code_failures_attempted_at ON pairing_code_failures(attempted_at);
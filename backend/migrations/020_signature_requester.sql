@@ -0,0 +1,5 @@
+-- Who asked for a client-requested signature, so quotas are charged to the caller
+-- rather than to whichever addresses they named
+ALTER TABLE signature_issuances ADD COLUMN requester VARCHAR(80); -- 'wallet:<address>' when signed in, else 'ip:<ip>'; NULL for relayed matches
+
+CREATE INDEX idx_signature_issuances_requester ON signature_issuances(requester, created_at) WHERE requester IS NOT NULL;
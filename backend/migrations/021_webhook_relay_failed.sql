@@ -0,0 +1,6 @@
+-- 'reverted' used to cover relay failures too; those are now 'relay_failed' and
+-- 'reverted' only means the transaction was mined and reverted. Endpoints that
+-- subscribed to 'reverted' keep receiving both.
+UPDATE webhook_endpoints
+SET event_types = array_append(event_types, 'relay_failed')
+WHERE 'reverted' = ANY(event_types) AND NOT 'relay_failed' = ANY(event_types);
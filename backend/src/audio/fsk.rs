@@ -0,0 +1,179 @@
+use std::f64::consts::PI;
+use crate::constants::*;
+use super::payload::{AudioPayload, AudioCodecError, AUDIO_PAYLOAD_LEN};
+
+// Alternating bits let the receiver settle before the sync word marks the frame start
+const PREAMBLE: [u8; 4] = [0xAA; 4];
+const SYNC_WORD: [u8; 2] = [0x2D, 0xD4];
+
+// What the decoder searches for: last preamble byte followed by the sync word
+const FRAME_MARKER: [u8; 3] = [0xAA, SYNC_WORD[0], SYNC_WORD[1]];
+
+const AMPLITUDE: f64 = 0.6 * i16::MAX as f64;
+const SILENCE_SECS: f64 = 0.05;
+const FADE_SECS: f64 = 0.005;
+
+// Bit-window phases tried when aligning to a recording that starts mid-symbol
+const ALIGNMENT_STEPS: usize = 8;
+
+/// Modulate a payload to mono 16-bit PCM using continuous-phase binary FSK
+pub fn encode_pcm(payload: &AudioPayload, sample_rate: u32) -> Vec<i16> {
+    let frame: Vec<u8> = PREAMBLE.iter()
+        .chain(SYNC_WORD.iter())
+        .chain(payload.to_bytes().iter())
+        .copied()
+        .collect();
+
+    let samples_per_bit = sample_rate as f64 / AUDIO_BAUD_RATE as f64;
+    let total_bits = frame.len() * 8;
+    let tone_samples = (total_bits as f64 * samples_per_bit).round() as usize;
+    let silence = vec![0i16; (SILENCE_SECS * sample_rate as f64) as usize];
+    let fade_samples = ((FADE_SECS * sample_rate as f64) as usize).max(1);
+
+    let mut samples = Vec::with_capacity(tone_samples + 2 * silence.len());
+    samples.extend_from_slice(&silence);
+
+    let mut phase = 0.0f64;
+    for n in 0..tone_samples {
+        let bit_index = ((n as f64 / samples_per_bit) as usize).min(total_bits - 1);
+        let bit = (frame[bit_index / 8] >> (7 - bit_index % 8)) & 1;
+        let frequency = if bit == 1 { AUDIO_MARK_FREQUENCY_HZ } else { AUDIO_SPACE_FREQUENCY_HZ };
+
+        let envelope = (n.min(tone_samples - 1 - n) as f64 / fade_samples as f64).min(1.0);
+        samples.push((AMPLITUDE * envelope * phase.sin()) as i16);
+
+        phase = (phase + 2.0 * PI * frequency / sample_rate as f64) % (2.0 * PI);
+    }
+
+    samples.extend_from_slice(&silence);
+    samples
+}
+
+/// Demodulate mono 16-bit PCM back into a payload
+///
+/// Tries several bit alignments, looks for the frame marker in each demodulated bit
+/// stream and returns the first frame whose checksum verifies.
+pub fn decode_pcm(samples: &[i16], sample_rate: u32) -> Result<AudioPayload, AudioCodecError> {
+    let samples_per_bit = sample_rate as f64 / AUDIO_BAUD_RATE as f64;
+    // Shorter bit windows would never advance through the samples
+    if samples_per_bit < 1.0 {
+        return Err(AudioCodecError::UnsupportedSampleRate(sample_rate));
+    }
+    let mut last_error = AudioCodecError::NoPayloadFound;
+
+    for step in 0..ALIGNMENT_STEPS {
+        let offset = step as f64 * samples_per_bit / ALIGNMENT_STEPS as f64;
+        let bits = demodulate_bits(samples, sample_rate, samples_per_bit, offset);
+        let marker_bits = bytes_to_bits(&FRAME_MARKER);
+        let payload_bits = AUDIO_PAYLOAD_LEN * 8;
+
+        let mut start = 0;
+        while start + marker_bits.len() + payload_bits <= bits.len() {
+            if bits[start..start + marker_bits.len()] == marker_bits[..] {
+                let body_start = start + marker_bits.len();
+                let bytes = bits_to_bytes(&bits[body_start..body_start + payload_bits]);
+                match AudioPayload::from_bytes(&bytes) {
+                    Ok(payload) => return Ok(payload),
+                    Err(e) => last_error = e,
+                }
+            }
+            start += 1;
+        }
+    }
+
+    Err(last_error)
+}
+
+fn demodulate_bits(samples: &[i16], sample_rate: u32, samples_per_bit: f64, offset: f64) -> Vec<u8> {
+    let mut bits = Vec::new();
+    let mut index = 0usize;
+
+    loop {
+        let start = (offset + index as f64 * samples_per_bit).round() as usize;
+        let end = (offset + (index + 1) as f64 * samples_per_bit).round() as usize;
+        if end > samples.len() {
+            break;
+        }
+
+        let window = &samples[start..end];
+        let mark = goertzel_power(window, sample_rate, AUDIO_MARK_FREQUENCY_HZ);
+        let space = goertzel_power(window, sample_rate, AUDIO_SPACE_FREQUENCY_HZ);
+        bits.push(u8::from(mark > space));
+        index += 1;
+    }
+
+    bits
+}
+
+/// Signal power at a single frequency over a window
+fn goertzel_power(window: &[i16], sample_rate: u32, frequency: f64) -> f64 {
+    let coefficient = 2.0 * (2.0 * PI * frequency / sample_rate as f64).cos();
+    let (mut s_prev, mut s_prev2) = (0.0f64, 0.0f64);
+
+    for &sample in window {
+        let s = sample as f64 + coefficient * s_prev - s_prev2;
+        s_prev2 = s_prev;
+        s_prev = s;
+    }
+
+    s_prev2 * s_prev2 + s_prev * s_prev - coefficient * s_prev * s_prev2
+}
+
+fn bytes_to_bits(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1))
+        .collect()
+}
+
+fn bits_to_bytes(bits: &[u8]) -> Vec<u8> {
+    bits.chunks(8)
+        .map(|chunk| chunk.iter().fold(0u8, |acc, bit| (acc << 1) | bit))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Address;
+
+    fn sample_payload() -> AudioPayload {
+        let address: Address = "0x59888BE579194C701F16a9425f57ECce3906AF4b".parse().unwrap();
+        AudioPayload::new(address, 654321, 1758989464)
+    }
+
+    #[test]
+    fn test_pcm_round_trip() {
+        let payload = sample_payload();
+        let samples = encode_pcm(&payload, AUDIO_SAMPLE_RATE);
+        assert_eq!(decode_pcm(&samples, AUDIO_SAMPLE_RATE), Ok(payload));
+    }
+
+    #[test]
+    fn test_pcm_round_trip_with_offset_and_noise() {
+        let payload = sample_payload();
+        let clean = encode_pcm(&payload, 44_100);
+
+        // Start mid-symbol, attenuate and add deterministic pseudo-noise
+        let mut seed = 0x1234_5678u32;
+        let recorded: Vec<i16> = std::iter::repeat_n(0i16, 1_337)
+            .chain(clean.iter().map(|&s| s / 3))
+            .map(|s| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                s.saturating_add(((seed >> 16) as i16) / 16)
+            })
+            .collect();
+
+        assert_eq!(decode_pcm(&recorded, 44_100), Ok(payload));
+    }
+
+    #[test]
+    fn test_zero_sample_rate_is_rejected() {
+        assert_eq!(decode_pcm(&[0i16; 64], 0), Err(AudioCodecError::UnsupportedSampleRate(0)));
+    }
+
+    #[test]
+    fn test_silence_has_no_payload() {
+        let silence = vec![0i16; AUDIO_SAMPLE_RATE as usize];
+        assert_eq!(decode_pcm(&silence, AUDIO_SAMPLE_RATE), Err(AudioCodecError::NoPayloadFound));
+    }
+}
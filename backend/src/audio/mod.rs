@@ -0,0 +1,7 @@
+pub mod payload;
+pub mod fsk;
+pub mod wav;
+
+pub use payload::{AudioPayload, AudioCodecError, AUDIO_PAYLOAD_LEN};
+pub use fsk::{encode_pcm, decode_pcm};
+pub use wav::{encode_wav, decode_wav, read_wav_samples};
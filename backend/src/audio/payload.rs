@@ -0,0 +1,162 @@
+use alloy::primitives::Address;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use crate::{constants::*, utils::pairing_code};
+
+/// Size of an encoded payload in bytes
+///
+/// Layout (big-endian): version (1) | address (20) | rolling code (4) | unix timestamp (4) | CRC-32 (4)
+pub const AUDIO_PAYLOAD_LEN: usize = 33;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AudioCodecError {
+    #[error("payload must be {AUDIO_PAYLOAD_LEN} bytes, got {0}")]
+    InvalidLength(usize),
+    #[error("unsupported payload version {0}")]
+    UnsupportedVersion(u8),
+    #[error("checksum mismatch")]
+    ChecksumMismatch,
+    #[error("no payload found in audio")]
+    NoPayloadFound,
+    #[error("invalid WAV data: {0}")]
+    InvalidWav(String),
+    #[error("sample rate {0} Hz is too low for the pairing tones")]
+    UnsupportedSampleRate(u32),
+    #[error("payload timestamp is outside the accepted window")]
+    StaleTimestamp,
+    #[error("rolling code is not current")]
+    InvalidRollingCode,
+}
+
+/// What one phone plays and the other decodes: who I am, my current code, and when
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioPayload {
+    pub version: u8,
+    pub address: Address,
+    pub rolling_code: u32,
+    pub timestamp: u32,
+}
+
+impl AudioPayload {
+    pub fn new(address: Address, rolling_code: u32, timestamp: u32) -> Self {
+        Self {
+            version: AUDIO_PAYLOAD_VERSION,
+            address,
+            rolling_code,
+            timestamp,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; AUDIO_PAYLOAD_LEN] {
+        let mut bytes = [0u8; AUDIO_PAYLOAD_LEN];
+        bytes[0] = self.version;
+        bytes[1..21].copy_from_slice(self.address.as_slice());
+        bytes[21..25].copy_from_slice(&self.rolling_code.to_be_bytes());
+        bytes[25..29].copy_from_slice(&self.timestamp.to_be_bytes());
+        let crc = crc32(&bytes[..29]);
+        bytes[29..33].copy_from_slice(&crc.to_be_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AudioCodecError> {
+        if bytes.len() != AUDIO_PAYLOAD_LEN {
+            return Err(AudioCodecError::InvalidLength(bytes.len()));
+        }
+
+        let expected_crc = u32::from_be_bytes([bytes[29], bytes[30], bytes[31], bytes[32]]);
+        if crc32(&bytes[..29]) != expected_crc {
+            return Err(AudioCodecError::ChecksumMismatch);
+        }
+
+        if bytes[0] != AUDIO_PAYLOAD_VERSION {
+            return Err(AudioCodecError::UnsupportedVersion(bytes[0]));
+        }
+
+        Ok(Self {
+            version: bytes[0],
+            address: Address::from_slice(&bytes[1..21]),
+            rolling_code: u32::from_be_bytes([bytes[21], bytes[22], bytes[23], bytes[24]]),
+            timestamp: u32::from_be_bytes([bytes[25], bytes[26], bytes[27], bytes[28]]),
+        })
+    }
+
+    /// Check the payload's rolling code against the broadcaster's pairing secret
+    ///
+    /// Accepts the code for the window containing the payload timestamp, provided
+    /// that timestamp is within `AUDIO_PAYLOAD_MAX_SKEW_SECS` of `now`.
+    pub fn validate_rolling_code(&self, secret: &str, now: DateTime<Utc>) -> Result<(), AudioCodecError> {
+        let skew = (now.timestamp() - self.timestamp as i64).abs();
+        if skew > AUDIO_PAYLOAD_MAX_SKEW_SECS {
+            return Err(AudioCodecError::StaleTimestamp);
+        }
+
+        let sent_at = DateTime::from_timestamp(self.timestamp as i64, 0).ok_or(AudioCodecError::StaleTimestamp)?;
+        let expected = pairing_code::derive_pairing_code(secret, pairing_code::window_index(sent_at));
+        if expected.parse::<u32>().ok() != Some(self.rolling_code) {
+            return Err(AudioCodecError::InvalidRollingCode);
+        }
+
+        Ok(())
+    }
+}
+
+/// CRC-32 (IEEE 802.3, reflected), same as zlib/PNG
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_payload() -> AudioPayload {
+        let address: Address = "0xAefC770D8515C552C952a30e597d9fbEa99aA756".parse().unwrap();
+        AudioPayload::new(address, 123456, 1758989464)
+    }
+
+    #[test]
+    fn test_crc32_known_vector() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_payload_round_trip() {
+        let payload = sample_payload();
+        let bytes = payload.to_bytes();
+        assert_eq!(AudioPayload::from_bytes(&bytes), Ok(payload));
+    }
+
+    #[test]
+    fn test_payload_rejects_corruption() {
+        let mut bytes = sample_payload().to_bytes();
+        bytes[5] ^= 0x01;
+        assert_eq!(AudioPayload::from_bytes(&bytes), Err(AudioCodecError::ChecksumMismatch));
+        assert_eq!(AudioPayload::from_bytes(&bytes[..10]), Err(AudioCodecError::InvalidLength(10)));
+    }
+
+    #[test]
+    fn test_validate_rolling_code() {
+        let secret = pairing_code::generate_pairing_secret();
+        let now = Utc::now();
+        let code = pairing_code::derive_pairing_code(&secret, pairing_code::window_index(now));
+        let payload = AudioPayload::new(Address::ZERO, code.parse().unwrap(), now.timestamp() as u32);
+
+        assert_eq!(payload.validate_rolling_code(&secret, now), Ok(()));
+        assert_eq!(
+            payload.validate_rolling_code(&pairing_code::generate_pairing_secret(), now),
+            Err(AudioCodecError::InvalidRollingCode)
+        );
+        assert_eq!(
+            payload.validate_rolling_code(&secret, now + chrono::Duration::seconds(AUDIO_PAYLOAD_MAX_SKEW_SECS + 1)),
+            Err(AudioCodecError::StaleTimestamp)
+        );
+    }
+}
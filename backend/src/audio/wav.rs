@@ -0,0 +1,124 @@
+use crate::constants::AUDIO_MIN_SAMPLE_RATE;
+use super::payload::{AudioPayload, AudioCodecError};
+use super::fsk::{encode_pcm, decode_pcm};
+
+/// Encode a payload as a mono 16-bit PCM WAV file
+pub fn encode_wav(payload: &AudioPayload, sample_rate: u32) -> Vec<u8> {
+    let samples = encode_pcm(payload, sample_rate);
+    let data_len = (samples.len() * 2) as u32;
+
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes()); // chunk size
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    wav
+}
+
+/// Decode a payload from a 16-bit PCM WAV recording (mono or multi-channel)
+pub fn decode_wav(bytes: &[u8]) -> Result<AudioPayload, AudioCodecError> {
+    let (samples, sample_rate) = read_wav_samples(bytes)?;
+    decode_pcm(&samples, sample_rate)
+}
+
+/// Read the first channel of a 16-bit PCM WAV file, returning samples and sample rate
+pub fn read_wav_samples(bytes: &[u8]) -> Result<(Vec<i16>, u32), AudioCodecError> {
+    let invalid = |reason: &str| AudioCodecError::InvalidWav(reason.to_string());
+
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(invalid("missing RIFF/WAVE header"));
+    }
+
+    let mut format: Option<(u16, u16, u32, u16)> = None; // (format, channels, sample rate, bits)
+    let mut position = 12;
+
+    while position + 8 <= bytes.len() {
+        let id = &bytes[position..position + 4];
+        let size = u32::from_le_bytes([bytes[position + 4], bytes[position + 5], bytes[position + 6], bytes[position + 7]]) as usize;
+        let body_start = position + 8;
+        let body_end = body_start.checked_add(size).filter(|end| *end <= bytes.len())
+            .ok_or_else(|| invalid("truncated chunk"))?;
+        let body = &bytes[body_start..body_end];
+
+        match id {
+            b"fmt " => {
+                if body.len() < 16 {
+                    return Err(invalid("fmt chunk too short"));
+                }
+                format = Some((
+                    u16::from_le_bytes([body[0], body[1]]),
+                    u16::from_le_bytes([body[2], body[3]]),
+                    u32::from_le_bytes([body[4], body[5], body[6], body[7]]),
+                    u16::from_le_bytes([body[14], body[15]]),
+                ));
+            }
+            b"data" => {
+                let (audio_format, channels, sample_rate, bits) = format.ok_or_else(|| invalid("data chunk before fmt chunk"))?;
+                // 0xFFFE is WAVE_FORMAT_EXTENSIBLE, which phones commonly write for plain PCM
+                if (audio_format != 1 && audio_format != 0xFFFE) || bits != 16 || channels == 0 {
+                    return Err(invalid("only 16-bit PCM is supported"));
+                }
+                if sample_rate < AUDIO_MIN_SAMPLE_RATE {
+                    return Err(AudioCodecError::UnsupportedSampleRate(sample_rate));
+                }
+
+                let frame_size = 2 * channels as usize;
+                let samples = body.chunks_exact(frame_size)
+                    .map(|frame| i16::from_le_bytes([frame[0], frame[1]]))
+                    .collect();
+                return Ok((samples, sample_rate));
+            }
+            _ => {}
+        }
+
+        // Chunks are word aligned
+        position = body_end + (size % 2);
+    }
+
+    Err(invalid("missing data chunk"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Address;
+    use crate::constants::AUDIO_SAMPLE_RATE;
+
+    #[test]
+    fn test_wav_round_trip() {
+        let payload = AudioPayload::new(Address::repeat_byte(0x42), 42, 1_700_000_000);
+        let wav = encode_wav(&payload, AUDIO_SAMPLE_RATE);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(decode_wav(&wav), Ok(payload));
+    }
+
+    #[test]
+    fn test_rejects_non_wav() {
+        assert!(matches!(decode_wav(b"not a wav file"), Err(AudioCodecError::InvalidWav(_))));
+    }
+
+    #[test]
+    fn test_rejects_unusable_sample_rates() {
+        let payload = AudioPayload::new(Address::repeat_byte(0x42), 42, 1_700_000_000);
+        for rate in [0, 8_000] {
+            let mut wav = encode_wav(&payload, AUDIO_SAMPLE_RATE);
+            wav[24..28].copy_from_slice(&u32::to_le_bytes(rate));
+            assert_eq!(decode_wav(&wav), Err(AudioCodecError::UnsupportedSampleRate(rate)));
+        }
+    }
+}
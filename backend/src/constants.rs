@@ -11,6 +11,9 @@
 /// Together contract address on Worldchain mainnet
 pub const TOGETHER_CONTRACT_ADDRESS: &str = "0x0053E5F890d5cE67048C86eCCf6051A92Ab34b4b";
 
+/// Ethereum Attestation Service predeploy on Worldchain (OP Stack)
+pub const EAS_CONTRACT_ADDRESS: &str = "0x4200000000000000000000000000000000000021";
+
 // =============================================================================
 // BLOCKCHAIN CONFIGURATION
 // =============================================================================
@@ -18,6 +21,9 @@ pub const TOGETHER_CONTRACT_ADDRESS: &str = "0x0053E5F890d5cE67048C86eCCf6051A92
 /// Worldchain mainnet chain ID
 pub const WORLDCHAIN_MAINNET_CHAIN_ID: u64 = 480;
 
+/// Block explorer prefix for Worldchain transaction links
+pub const WORLDCHAIN_EXPLORER_TX_URL: &str = "https://worldscan.org/tx/";
+
 
 // =============================================================================
 // EVENT TOPICS (for blockchain event watching)
@@ -68,6 +74,9 @@ pub const MAX_CHUNK_SIZE: u64 = 4000;
 /// How often to refresh latest block number
 pub const REFRESH_LATEST_BLOCK_EVERY_N_ITERS: usize = 2;
 
+/// How often the attestation watcher publishes a new Merkle snapshot
+pub const MERKLE_SNAPSHOT_INTERVAL_SECS: u64 = 3600;
+
 /// Watcher ID for auction watcher
 pub const ATTESTATION_WATCHER_ID: &str = "attestation_watcher";
 
@@ -81,19 +90,332 @@ pub const TOGETHER_DOMAIN_NAME: &str = "Together";
 /// EIP712 domain version for together signatures
 pub const TOGETHER_DOMAIN_VERSION: &str = "1";
 
+/// EIP712 domain name for proof-of-together certificates
+pub const PROOF_DOMAIN_NAME: &str = "Together Proof";
+
+/// EIP712 domain version for proof-of-together certificates
+pub const PROOF_DOMAIN_VERSION: &str = "1";
+
+/// EIP712 domain name for verifiable credential proofs
+pub const CREDENTIAL_DOMAIN_NAME: &str = "Together Credentials";
+
+/// EIP712 domain version for verifiable credential proofs
+pub const CREDENTIAL_DOMAIN_VERSION: &str = "1";
+
+/// EIP712 domain name used by EAS for offchain attestations
+pub const EAS_DOMAIN_NAME: &str = "EAS Attestation";
+
+/// Offchain attestation version produced by our EAS export
+pub const EAS_OFFCHAIN_ATTESTATION_VERSION: u16 = 2;
+
+/// EAS schema together attestations are exported under
+pub const EAS_ATTESTATION_SCHEMA: &str = "address a,address b,uint256 timestamp,bytes32 txHash";
+
+/// Current proof certificate format version
+pub const PROOF_CERTIFICATE_VERSION: u8 = 1;
+
 /// Signature deadline duration in minutes
 pub const SIGNATURE_DEADLINE_MINUTES: i64 = 3;
 
+/// Maximum signatures requested by one wallet (or one IP when signed out) per rolling 24 hours
+pub const SIGNATURE_QUOTA_PER_REQUESTER_PER_DAY: i64 = 50;
+
+/// Maximum signatures involving one address per rolling 24 hours, counted separately for
+/// client requests (whoever asked) and relays
+pub const SIGNATURE_QUOTA_PER_ADDRESS_PER_DAY: i64 = 50;
+
+/// Maximum signatures for one pair per rolling hour, counted separately for client requests
+/// (whoever asked) and relays
+pub const SIGNATURE_QUOTA_PER_PAIR_PER_HOUR: i64 = 5;
+
+/// Issuance source for signatures requested through `/api/attest`
+pub const SIGNATURE_SOURCE_ATTEST: &str = "attest";
+
+/// Issuance source for signatures created when relaying a matched pending connection
+pub const SIGNATURE_SOURCE_CONNECTION_CHECKER: &str = "connection_checker";
+
+/// How long the server reuses its copy of the on-chain signer deny list before re-reading it
+pub const SIGNER_DENYLIST_CACHE_SECS: u64 = 15;
+
 // =============================================================================
 // RATE LIMITING
 // =============================================================================
 
-/// Maximum Alchemy API requests per minute per address
-pub const ALCHEMY_RATE_LIMIT_PER_MINUTE: u32 = 10;
-
 /// Rate limit window duration in seconds
 pub const RATE_LIMIT_WINDOW_SECONDS: u64 = 60;
 
+/// RPC proxy requests per IP per window
+pub const RPC_RATE_LIMIT_PER_MINUTE: u32 = 300;
+
+/// Attestation signature requests per wallet (per IP when signed out) per window
+pub const ATTEST_RATE_LIMIT_PER_MINUTE: u32 = 20;
+
+/// Sign-in nonce and session requests per IP per window
+pub const AUTH_RATE_LIMIT_PER_MINUTE: u32 = 10;
+
+/// Pending connection requests per signed-in wallet per window
+pub const PENDING_CONNECTION_RATE_LIMIT_PER_MINUTE: u32 = 30;
+
+/// Pending connection requests per IP per window, across every wallet behind it
+pub const PENDING_CONNECTION_IP_RATE_LIMIT_PER_MINUTE: u32 = 120;
+
+/// Credential and EAS export requests per IP per window, shared by both; each one signs up to a page of attestations
+pub const PROOF_EXPORT_RATE_LIMIT_PER_MINUTE: u32 = 30;
+
+/// How often stores drop keys whose allowance has fully recovered
+pub const RATE_LIMIT_SWEEP_INTERVAL_SECS: u64 = 60;
+
+/// Keys the in-memory store tracks before evicting those closest to recovering
+pub const RATE_LIMIT_MAX_KEYS: usize = 100_000;
+
+// =============================================================================
+// RPC PROXY
+// =============================================================================
+
+/// Network used when a proxy request doesn't name one
+pub const RPC_DEFAULT_NETWORK: &str = "worldchain";
+
+/// Read-only methods every proxied network accepts
+pub const RPC_READ_METHODS: &[&str] = &[
+    "eth_chainId", "net_version", "eth_blockNumber", "eth_call", "eth_estimateGas",
+    "eth_gasPrice", "eth_maxPriorityFeePerGas", "eth_feeHistory", "eth_getBalance",
+    "eth_getCode", "eth_getStorageAt", "eth_getTransactionCount", "eth_getTransactionByHash",
+    "eth_getTransactionReceipt", "eth_getBlockByNumber", "eth_getBlockByHash",
+];
+
+/// Extra methods accepted on Worldchain, where the app actually transacts
+///
+/// `eth_getLogs` is left out: a wide block range is an expensive scan on our Alchemy key.
+pub const RPC_WORLDCHAIN_METHODS: &[&str] = &["eth_sendRawTransaction"];
+
+/// Most calls a single JSON-RPC batch may carry; each one counts against the proxy rate limit
+pub const RPC_MAX_BATCH_CALLS: usize = 20;
+
+/// Upstream request timeout for proxied calls
+pub const RPC_PROXY_TIMEOUT_SECS: u64 = 30;
+
+/// How long results that can never change (`eth_chainId`, `net_version`) are reused
+pub const RPC_CACHE_STATIC_TTL_SECS: u64 = 3600;
+
+/// How long results pinned to a block are reused
+pub const RPC_CACHE_TTL_SECS: u64 = 10;
+
+/// Cached responses kept before new ones are skipped until entries expire
+pub const RPC_CACHE_MAX_ENTRIES: usize = 10_000;
+
+// =============================================================================
+// CLIENT IP RESOLUTION
+// =============================================================================
+
+/// Cloudflare's published edge ranges (https://www.cloudflare.com/ips/), the `cloudflare` preset
+pub const CLOUDFLARE_PROXY_RANGES: &[&str] = &[
+    "173.245.48.0/20", "103.21.244.0/22", "103.22.200.0/22", "103.31.4.0/22",
+    "141.101.64.0/18", "108.162.192.0/18", "190.93.240.0/20", "188.114.96.0/20",
+    "197.234.240.0/22", "198.41.128.0/17", "162.158.0.0/15", "104.16.0.0/13",
+    "104.24.0.0/14", "172.64.0.0/13", "131.0.72.0/22",
+    "2400:cb00::/32", "2606:4700::/32", "2803:f800::/32", "2405:b500::/32",
+    "2405:8100::/32", "2a06:98c0::/29", "2c0f:f248::/32",
+];
+
+/// Railway's edge proxy reaches services from the RFC 6598 shared address space, the `railway` preset
+pub const RAILWAY_PROXY_RANGES: &[&str] = &["100.64.0.0/10"];
+
+/// Loopback and private networks (a local nginx or Docker bridge), the `private` preset
+pub const PRIVATE_PROXY_RANGES: &[&str] = &[
+    "127.0.0.0/8", "10.0.0.0/8", "172.16.0.0/12", "192.168.0.0/16", "::1/128", "fc00::/7",
+];
+
+// =============================================================================
+// PENDING CONNECTIONS
+// =============================================================================
+
+/// How long a decline blocks new pending connections from the same sender
+pub const DECLINE_COOLDOWN_MINUTES: i64 = 60;
+
+// =============================================================================
+// PAIRING CODES
+// =============================================================================
+
+/// How often a user's pairing code rotates
+pub const PAIRING_CODE_ROTATION_SECS: i64 = 60;
+
+/// How long a code keeps resolving after it has rotated out
+pub const PAIRING_CODE_GRACE_SECS: i64 = 10;
+
+/// Number of digits in a pairing code
+pub const PAIRING_CODE_DIGITS: u32 = 6;
+
+/// Codes a session may type that match nobody within [`PAIRING_CODE_FAILURE_WINDOW_SECS`]
+pub const PAIRING_CODE_MAX_FAILURES: i64 = 10;
+
+/// How long a pairing code that matched nobody counts against the session
+pub const PAIRING_CODE_FAILURE_WINDOW_SECS: i64 = 600;
+
+// =============================================================================
+// AUDIO PAIRING
+// =============================================================================
+
+/// Current version byte of the audio pairing payload
+pub const AUDIO_PAYLOAD_VERSION: u8 = 1;
+
+/// Default sample rate for generated pairing audio
+pub const AUDIO_SAMPLE_RATE: u32 = 48_000;
+
+/// FSK symbol rate (bits per second)
+pub const AUDIO_BAUD_RATE: u32 = 200;
+
+/// FSK tone for a 0 bit (18 whole cycles per bit at 200 baud)
+pub const AUDIO_SPACE_FREQUENCY_HZ: f64 = 3_600.0;
+
+/// FSK tone for a 1 bit (24 whole cycles per bit at 200 baud)
+pub const AUDIO_MARK_FREQUENCY_HZ: f64 = 4_800.0;
+
+/// Lowest sample rate that can carry the mark tone (its Nyquist rate)
+pub const AUDIO_MIN_SAMPLE_RATE: u32 = 2 * AUDIO_MARK_FREQUENCY_HZ as u32;
+
+/// Maximum clock skew accepted between a payload timestamp and the server
+pub const AUDIO_PAYLOAD_MAX_SKEW_SECS: i64 = 120;
+
+// =============================================================================
+// PROXIMITY CHALLENGES
+// =============================================================================
+
+/// How long a broadcast challenge nonce can be reported back
+pub const PROXIMITY_CHALLENGE_TTL_SECS: i64 = 30;
+
+/// How recent a challenge response must be to back a match
+pub const PROXIMITY_PROOF_VALIDITY_SECS: i64 = 600;
+
+// =============================================================================
+// AUTHENTICATION
+// =============================================================================
+
+/// How long an authenticated session stays valid
+pub const SESSION_TTL_HOURS: i64 = 24 * 7;
+
+/// How long a stream ticket can wait before it is used to open an event stream
+pub const STREAM_TICKET_TTL_SECS: i64 = 60;
+
+/// How old a SIWE message's `Issued At` may be; matches the sign-in nonce lifetime
+pub const SIWE_MAX_AGE_SECS: i64 = 600;
+
+/// Allowance for client clocks running ahead when checking SIWE timestamps
+pub const SIWE_CLOCK_SKEW_SECS: i64 = 60;
+
+/// Domain SIWE messages must be addressed to unless `SIWE_DOMAIN` is set
+pub const DEFAULT_SIWE_DOMAIN: &str = "togetherapp.app";
+
+/// ERC-1271 `isValidSignature` magic value returned by smart contract wallets
+pub const ERC1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+
+// =============================================================================
+// PAIRING EVENTS
+// =============================================================================
+
+/// Postgres NOTIFY channel used to fan pairing events out across processes
+pub const PAIRING_EVENTS_CHANNEL: &str = "pairing_events";
+
+/// Capacity of the in-process broadcast buffer for pairing events
+pub const PAIRING_EVENTS_BUFFER: usize = 256;
+
+/// Seconds to wait before re-establishing a dropped LISTEN connection
+pub const PAIRING_EVENTS_RECONNECT_SECS: u64 = 5;
+
+/// Interval between SSE keep-alive comments
+pub const SSE_KEEPALIVE_INTERVAL_SECS: u64 = 15;
+
+// =============================================================================
+// PROFILE CARDS
+// =============================================================================
+
+/// Partners listed on a profile card
+pub const PROFILE_CARD_TOP_PARTNERS: usize = 3;
+
+/// Most recent partners considered when picking the top ones for a card
+pub const PROFILE_CARD_CONNECTIONS_SCANNED: i64 = 200;
+
+/// Rendered profile cards kept in memory
+pub const PROFILE_CARD_CACHE_CAPACITY: usize = 1024;
+
+/// Seconds clients and link unfurlers may reuse a card before revalidating
+pub const PROFILE_CARD_MAX_AGE_SECS: u64 = 300;
+
+// =============================================================================
+// DATA EXPORT
+// =============================================================================
+
+/// Version of the personal data export layout, bumped when files or columns change
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Bytes buffered before a chunk of a streamed export is sent
+pub const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Chunks a streamed export may queue ahead of a slow client
+pub const EXPORT_CHANNEL_CAPACITY: usize = 8;
+
+/// Days between an account deletion request and the data being erased
+pub const ACCOUNT_DELETION_GRACE_DAYS: i64 = 14;
+
+// =============================================================================
+// PROOF EXPORTS
+// =============================================================================
+
+/// Attestations a credential or EAS export signs when no limit is given
+pub const PROOF_EXPORT_DEFAULT_LIMIT: i64 = 50;
+
+/// Most attestations a single credential or EAS export will sign
+pub const PROOF_EXPORT_MAX_LIMIT: i64 = 100;
+
+// =============================================================================
+// BADGES
+// =============================================================================
+
+/// Left-hand text of a badge when no label is given
+pub const BADGE_DEFAULT_LABEL: &str = "together";
+
+/// Longest label a badge will render
+pub const BADGE_MAX_LABEL_CHARS: usize = 40;
+
+/// Seconds a badge may be served from cache without revalidating
+pub const BADGE_MAX_AGE_SECS: u64 = 300;
+
+/// Seconds a stale badge may still be served while a fresh one is fetched
+pub const BADGE_STALE_WHILE_REVALIDATE_SECS: u64 = 86400;
+
+// =============================================================================
+// WEBHOOKS
+// =============================================================================
+
+/// Header carrying `t=<unix seconds>,v1=<hex HMAC-SHA256 of "<t>.<body>">`
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Together-Signature";
+
+/// Header naming the event type of a webhook delivery
+pub const WEBHOOK_EVENT_HEADER: &str = "X-Together-Event";
+
+/// Header carrying the delivery id, stable across retries so receivers can dedupe
+pub const WEBHOOK_DELIVERY_HEADER: &str = "X-Together-Delivery";
+
+/// How far a signature timestamp may drift from the receiver's clock
+pub const WEBHOOK_SIGNATURE_TOLERANCE_SECS: i64 = 300;
+
+/// Seconds to wait for a partner endpoint to respond
+pub const WEBHOOK_REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Attempts before a delivery is given up on
+pub const WEBHOOK_MAX_ATTEMPTS: i32 = 8;
+
+/// Delay before the first retry, doubled for each attempt after that
+pub const WEBHOOK_RETRY_BASE_SECS: i64 = 30;
+
+/// Longest delay between retries
+pub const WEBHOOK_RETRY_MAX_SECS: i64 = 6 * 60 * 60;
+
+/// How often the dispatcher looks for due deliveries
+pub const WEBHOOK_DISPATCH_INTERVAL_SECS: u64 = 5;
+
+/// Deliveries sent per dispatcher pass
+pub const WEBHOOK_DISPATCH_BATCH_SIZE: i64 = 20;
+
 // =============================================================================
 // DATABASE CONFIGURATION
 // =============================================================================
@@ -121,6 +443,21 @@ pub const CAST_HASH_LENGTH: usize = 66;
 /// Default server port if not specified in environment
 pub const DEFAULT_SERVER_PORT: u16 = 3000;
 
+/// Frontend URL that shared profile links point at, if PUBLIC_APP_URL isn't set
+pub const DEFAULT_PUBLIC_APP_URL: &str = "https://togetherapp.app";
+
+/// Generated OpenAPI 3.1 document
+pub const OPENAPI_JSON_PATH: &str = "/api/openapi.json";
+
+/// Bundled Swagger UI for browsing the OpenAPI document
+pub const API_DOCS_PATH: &str = "/api/docs";
+
+/// Header carrying the request ID, echoed on every response and in error bodies
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest caller-supplied request ID that is kept instead of generating a new one
+pub const MAX_REQUEST_ID_LENGTH: usize = 64;
+
 // =============================================================================
 // HELPER FUNCTIONS FOR VALIDATION
 // =============================================================================
@@ -0,0 +1,294 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use crate::models::AccountDeletion;
+
+/// Open a deletion request, or return the one already pending for this user
+pub async fn request_deletion(
+    pool: &PgPool,
+    user_id: i32,
+    wallet_address: &str,
+    scheduled_for: DateTime<Utc>,
+) -> Result<AccountDeletion> {
+    let created = sqlx::query_as::<_, AccountDeletion>(
+        r#"
+        INSERT INTO account_deletions (user_id, wallet_address, scheduled_for)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id) WHERE status = 'pending' DO NOTHING
+        RETURNING *
+        "#
+    )
+    .bind(user_id)
+    .bind(wallet_address)
+    .bind(scheduled_for)
+    .fetch_optional(pool)
+    .await?;
+
+    match created {
+        Some(deletion) => Ok(deletion),
+        None => get_latest_deletion(pool, user_id).await?
+            .ok_or_else(|| anyhow::anyhow!("Pending deletion for user {} disappeared", user_id)),
+    }
+}
+
+/// The user's most recent deletion request, whatever its status
+pub async fn get_latest_deletion(pool: &PgPool, user_id: i32) -> Result<Option<AccountDeletion>> {
+    let deletion = sqlx::query_as::<_, AccountDeletion>(
+        "SELECT * FROM account_deletions WHERE user_id = $1 ORDER BY requested_at DESC LIMIT 1"
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(deletion)
+}
+
+/// Cancel the user's pending request during its grace period
+pub async fn cancel_deletion(pool: &PgPool, user_id: i32) -> Result<Option<AccountDeletion>> {
+    let deletion = sqlx::query_as::<_, AccountDeletion>(
+        r#"
+        UPDATE account_deletions
+        SET status = 'cancelled', cancelled_at = NOW()
+        WHERE user_id = $1 AND status = 'pending'
+        RETURNING *
+        "#
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(deletion)
+}
+
+/// Pending requests whose grace period has ended
+pub async fn get_due_deletions(pool: &PgPool, now: DateTime<Utc>) -> Result<Vec<AccountDeletion>> {
+    let deletions = sqlx::query_as::<_, AccountDeletion>(
+        "SELECT * FROM account_deletions WHERE status = 'pending' AND scheduled_for <= $1 ORDER BY scheduled_for"
+    )
+    .bind(now)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(deletions)
+}
+
+/// Whether a wallet's account has been erased, so its attestations must stay hidden and
+/// it can't sign up again
+pub async fn is_address_erased(pool: &PgPool, address: &str) -> Result<bool> {
+    let erased = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM account_deletions WHERE status = 'completed' AND LOWER(wallet_address) = LOWER($1))"
+    )
+    .bind(address)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(erased)
+}
+
+/// Whether any of the wallets has been erased, e.g. either side of a pair
+pub async fn any_address_erased(pool: &PgPool, addresses: &[&str]) -> Result<bool> {
+    let addresses: Vec<String> = addresses.iter().map(|address| address.to_lowercase()).collect();
+    let erased = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM account_deletions WHERE status = 'completed' AND LOWER(wallet_address) = ANY($1))"
+    )
+    .bind(&addresses)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(erased)
+}
+
+/// Erase a user's off-chain data and close the request, atomically
+///
+/// Pending and optimistic connections are deleted explicitly, and everything else keyed
+/// on the user (sessions, pairing codes, proximity challenges, resolutions) goes with
+/// the `users` row through its ON DELETE CASCADE keys. The request row survives with
+/// `user_id` set to NULL. Signature issuances are kept for nonce accounting, minus who
+/// requested them (IP, session and quota key).
+pub async fn erase_account(pool: &PgPool, deletion: &AccountDeletion) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    if let Some(user_id) = deletion.user_id {
+        sqlx::query("DELETE FROM pending_connections WHERE from_user_id = $1 OR to_user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM optimistic_connections WHERE user_id_1 = $1 OR user_id_2 = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    sqlx::query("DELETE FROM username_cache WHERE LOWER(address) = LOWER($1)")
+        .bind(&deletion.wallet_address)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM auth_nonces WHERE LOWER(wallet_address) = LOWER($1)")
+        .bind(&deletion.wallet_address)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE signature_issuances SET requester_ip = NULL, session_id = NULL, requester = NULL
+        WHERE LOWER(on_behalf_of) = LOWER($1) OR LOWER(together_with) = LOWER($1)
+        "#
+    )
+    .bind(&deletion.wallet_address)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE account_deletions SET status = 'completed', completed_at = NOW() WHERE id = $1")
+        .bind(deletion.id)
+        .execute(&mut *tx)
+        .await?;
+
+    if let Some(user_id) = deletion.user_id {
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use crate::{
+        db::{attestations, pairing, sessions, signatures, testing::{random_wallet, test_pool}, users, webhooks},
+        models::NewSignatureIssuance,
+        services::webhooks::{enqueue, WebhookEvent},
+    };
+
+    async fn insert_attestation(pool: &PgPool, address_1: &str, address_2: &str) {
+        sqlx::query("INSERT INTO together_attestations (address_1, address_2, attestation_timestamp) VALUES ($1, $2, 1700000000)")
+            .bind(address_1)
+            .bind(address_2)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_deletion_waits_out_the_grace_period() {
+        let Some(pool) = test_pool().await else { return };
+        let user = users::create_user(&pool, &random_wallet()).await.unwrap();
+        let scheduled_for = Utc::now() + Duration::days(30);
+
+        let deletion = request_deletion(&pool, user.id, &user.wallet_address, scheduled_for).await.unwrap();
+        assert_eq!(deletion.status, "pending");
+        // Asking again returns the open request rather than pushing the date back
+        let again = request_deletion(&pool, user.id, &user.wallet_address, scheduled_for + Duration::days(1)).await.unwrap();
+        assert_eq!(again.id, deletion.id);
+
+        let due_now = get_due_deletions(&pool, Utc::now()).await.unwrap();
+        assert!(due_now.iter().all(|due| due.id != deletion.id));
+        let due_later = get_due_deletions(&pool, scheduled_for + Duration::seconds(1)).await.unwrap();
+        assert!(due_later.iter().any(|due| due.id == deletion.id));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_deletion_never_comes_due() {
+        let Some(pool) = test_pool().await else { return };
+        let user = users::create_user(&pool, &random_wallet()).await.unwrap();
+        let scheduled_for = Utc::now() + Duration::days(30);
+        let deletion = request_deletion(&pool, user.id, &user.wallet_address, scheduled_for).await.unwrap();
+
+        let cancelled = cancel_deletion(&pool, user.id).await.unwrap().unwrap();
+        assert_eq!(cancelled.id, deletion.id);
+        assert_eq!(cancelled.status, "cancelled");
+        assert!(cancel_deletion(&pool, user.id).await.unwrap().is_none());
+
+        let due = get_due_deletions(&pool, scheduled_for + Duration::days(1)).await.unwrap();
+        assert!(due.iter().all(|due| due.id != deletion.id));
+        assert!(!is_address_erased(&pool, &user.wallet_address).await.unwrap());
+
+        // A new request can be opened after cancelling
+        let reopened = request_deletion(&pool, user.id, &user.wallet_address, scheduled_for).await.unwrap();
+        assert_ne!(reopened.id, deletion.id);
+        assert_eq!(get_latest_deletion(&pool, user.id).await.unwrap().unwrap().id, reopened.id);
+    }
+
+    #[tokio::test]
+    async fn test_erase_account_removes_only_the_erased_user() {
+        let Some(pool) = test_pool().await else { return };
+        let erased = users::create_user(&pool, &random_wallet()).await.unwrap();
+        let partner = users::create_user(&pool, &random_wallet()).await.unwrap();
+        let bystander = users::create_user(&pool, &random_wallet()).await.unwrap();
+        let session_expiry = Utc::now() + Duration::hours(1);
+
+        // The erased user's own rows, and rows shared with the partner
+        let erased_token = random_wallet();
+        let erased_session = sessions::create_session(&pool, erased.id, &erased_token, session_expiry).await.unwrap();
+        pairing::get_or_create_pairing_secret(&pool, erased.id, "ab").await.unwrap();
+        users::create_pending_connection(&pool, erased.id, partner.id).await.unwrap();
+        users::create_pending_connection(&pool, partner.id, erased.id).await.unwrap();
+        users::create_optimistic_connection(&pool, erased.id, partner.id).await.unwrap();
+        attestations::upsert_username_cache(&pool, &erased.wallet_address, Some("erased"), None).await.unwrap();
+        insert_attestation(&pool, &erased.wallet_address, &partner.wallet_address).await;
+        let issuance = NewSignatureIssuance {
+            nonce: format!("0x{}", hex::encode(rand::random::<[u8; 32]>())),
+            on_behalf_of: partner.wallet_address.clone(),
+            together_with: erased.wallet_address.clone(),
+            attestation_timestamp: 1_700_000_000,
+            deadline: 1_700_000_600,
+            signer_address: random_wallet(),
+            source: crate::constants::SIGNATURE_SOURCE_ATTEST,
+            requester_ip: Some("203.0.113.7".to_string()),
+            session_id: Some(erased_session.id),
+            requester: Some(format!("wallet:{}", erased.wallet_address.to_lowercase())),
+        };
+        signatures::record_issuance(&mut pool.acquire().await.unwrap(), &issuance).await.unwrap();
+
+        // The partner's and a bystander's rows that must survive
+        let partner_token = random_wallet();
+        sessions::create_session(&pool, partner.id, &partner_token, session_expiry).await.unwrap();
+        users::create_pending_connection(&pool, partner.id, bystander.id).await.unwrap();
+        insert_attestation(&pool, &partner.wallet_address, &bystander.wallet_address).await;
+
+        let endpoint = webhooks::create_endpoint(
+            &pool,
+            "https://partner.example/hook",
+            "secret",
+            None,
+            &[],
+            &[erased.wallet_address.clone(), partner.wallet_address.clone()],
+        ).await.unwrap();
+        assert_eq!(enqueue(&pool, &WebhookEvent::matched(&erased.wallet_address, &partner.wallet_address)).await.unwrap(), 1);
+
+        let deletion = request_deletion(&pool, erased.id, &erased.wallet_address, Utc::now()).await.unwrap();
+        erase_account(&pool, &deletion).await.unwrap();
+
+        assert!(users::get_user_by_id(&pool, erased.id).await.unwrap().is_none());
+        assert!(sessions::get_session_user(&pool, &erased_token).await.unwrap().is_none());
+        assert!(attestations::get_username_cache(&pool, &erased.wallet_address).await.unwrap().is_none());
+        assert!(users::get_pending_connection(&pool, partner.id, erased.id).await.unwrap().is_none());
+        assert!(users::get_optimistic_connections_involving(&pool, partner.id).await.unwrap().is_empty());
+        let closed = get_latest_deletion(&pool, erased.id).await.unwrap();
+        assert!(closed.is_none(), "user_id is cleared on the audit row");
+        assert!(is_address_erased(&pool, &erased.wallet_address).await.unwrap());
+
+        // The partner keeps everything that doesn't involve the erased user
+        assert!(users::get_user_by_id(&pool, partner.id).await.unwrap().is_some());
+        assert!(sessions::get_session_user(&pool, &partner_token).await.unwrap().is_some());
+        assert!(users::get_pending_connection(&pool, partner.id, bystander.id).await.unwrap().is_some());
+        let served = attestations::get_attestations_for_address(&pool, &partner.wallet_address, None, None).await.unwrap();
+        assert_eq!(served.len(), 1);
+        assert!(served[0].address_1.eq_ignore_ascii_case(&bystander.wallet_address)
+            || served[0].address_2.eq_ignore_ascii_case(&bystander.wallet_address));
+        assert!(attestations::check_together(&pool, &partner.wallet_address, &erased.wallet_address).await.unwrap().is_none());
+
+        // The issuance stays for nonce accounting, without who asked for it
+        let kept = signatures::get_issuance_by_nonce(&pool, &issuance.nonce).await.unwrap().unwrap();
+        assert!(kept.requester_ip.is_none() && kept.session_id.is_none() && kept.requester.is_none());
+
+        assert_eq!(enqueue(&pool, &WebhookEvent::matched(&erased.wallet_address, &partner.wallet_address)).await.unwrap(), 0);
+        webhooks::delete_endpoint(&pool, endpoint.id).await.unwrap();
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 use sqlx::{PgPool, Row};
+use uuid::Uuid;
 use crate::models::attestations::{TogetherAttestation, UserProfile, ConnectionInfo, UsernameCache};
 
 /// Insert a new together attestation
@@ -37,10 +38,30 @@ pub async fn insert_attestation(
     // Update counts for both addresses
     update_address_count(pool, addr1).await?;
     update_address_count(pool, addr2).await?;
+    crate::db::pairs::upsert_pair(pool, addr1, addr2).await?;
 
     Ok(attestation)
 }
 
+/// Record where an attestation's Together event sits on chain
+pub async fn set_attestation_log_position(
+    pool: &PgPool,
+    attestation_id: Uuid,
+    block_hash: &str,
+    log_index: i64,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE together_attestations SET block_hash = $2, log_index = $3 WHERE id = $1"
+    )
+    .bind(attestation_id)
+    .bind(block_hash)
+    .bind(log_index)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
 /// Update the count for a specific address
 async fn update_address_count(pool: &PgPool, address: &str) -> Result<()> {
     sqlx::query(
@@ -77,8 +98,57 @@ pub async fn get_together_count(pool: &PgPool, address: &str) -> Result<i64> {
     Ok(count.unwrap_or(0))
 }
 
+/// Number of distinct wallets an address has been together with
+pub async fn count_distinct_partners(pool: &PgPool, address: &str) -> Result<i64> {
+    let count = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(DISTINCT CASE
+            WHEN LOWER(address_1) = LOWER($1) THEN LOWER(address_2)
+            ELSE LOWER(address_1)
+        END)
+        FROM together_attestations
+        WHERE LOWER(address_1) = LOWER($1) OR LOWER(address_2) = LOWER($1)
+        "#
+    )
+    .bind(address)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// Number of attestations for an address at or after `since` (unix seconds)
+pub async fn count_attestations_since(pool: &PgPool, address: &str, since: i64) -> Result<i64> {
+    let count = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*) FROM together_attestations
+        WHERE (LOWER(address_1) = LOWER($1) OR LOWER(address_2) = LOWER($1))
+        AND attestation_timestamp >= $2
+        "#
+    )
+    .bind(address)
+    .bind(since)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
 /// Get user profile with connections data
+///
+/// Erased accounts get an empty profile and are left out of everyone else's partners:
+/// their attestations are on chain and can't be deleted, only hidden.
 pub async fn get_user_profile(pool: &PgPool, address: &str, limit: Option<i64>) -> Result<UserProfile> {
+    if crate::db::account_deletions::is_address_erased(pool, address).await? {
+        return Ok(UserProfile {
+            address: address.to_string(),
+            username: None,
+            profile_picture_url: None,
+            total_connections: 0,
+            recent_connections: Vec::new(),
+        });
+    }
+
     let total_connections = get_together_count(pool, address).await?;
     
     // Get user's own username
@@ -132,6 +202,10 @@ pub async fn get_user_profile(pool: &PgPool, address: &str, limit: Option<i64>)
         FROM connection_stats cs
         LEFT JOIN username_cache uc ON LOWER(uc.address) = LOWER(cs.partner_address)
         LEFT JOIN optimistic_stats os ON LOWER(os.partner_address) = LOWER(cs.partner_address)
+        WHERE NOT EXISTS (
+            SELECT 1 FROM account_deletions ad
+            WHERE ad.status = 'completed' AND LOWER(ad.wallet_address) = LOWER(cs.partner_address)
+        )
         ORDER BY cs.latest_timestamp DESC
         LIMIT $2
         "#
@@ -162,7 +236,7 @@ pub async fn get_user_profile(pool: &PgPool, address: &str, limit: Option<i64>)
     })
 }
 
-/// Check if two addresses have been together
+/// Check if two addresses have been together; never true once either wallet is erased
 pub async fn check_together(pool: &PgPool, address_1: &str, address_2: &str) -> Result<Option<TogetherAttestation>> {
     // Ensure consistent ordering
     let (addr1, addr2) = if address_1.to_lowercase() <= address_2.to_lowercase() {
@@ -175,6 +249,10 @@ pub async fn check_together(pool: &PgPool, address_1: &str, address_2: &str) ->
         r#"
         SELECT * FROM together_attestations
         WHERE LOWER(address_1) = LOWER($1) AND LOWER(address_2) = LOWER($2)
+        AND NOT EXISTS (
+            SELECT 1 FROM account_deletions ad
+            WHERE ad.status = 'completed' AND LOWER(ad.wallet_address) IN (LOWER(address_1), LOWER(address_2))
+        )
         ORDER BY attestation_timestamp DESC
         LIMIT 1
         "#
@@ -187,7 +265,7 @@ pub async fn check_together(pool: &PgPool, address_1: &str, address_2: &str) ->
     Ok(attestation)
 }
 
-/// Get all attestations for an address with pagination
+/// Get all attestations for an address with pagination, leaving out erased partners
 pub async fn get_attestations_for_address(
     pool: &PgPool, 
     address: &str, 
@@ -200,7 +278,11 @@ pub async fn get_attestations_for_address(
     let attestations = sqlx::query_as::<_, TogetherAttestation>(
         r#"
         SELECT * FROM together_attestations
-        WHERE LOWER(address_1) = LOWER($1) OR LOWER(address_2) = LOWER($1)
+        WHERE (LOWER(address_1) = LOWER($1) OR LOWER(address_2) = LOWER($1))
+        AND NOT EXISTS (
+            SELECT 1 FROM account_deletions ad
+            WHERE ad.status = 'completed' AND LOWER(ad.wallet_address) IN (LOWER(address_1), LOWER(address_2))
+        )
         ORDER BY attestation_timestamp DESC
         OFFSET $2 LIMIT $3
         "#
@@ -214,6 +296,26 @@ pub async fn get_attestations_for_address(
     Ok(attestations)
 }
 
+/// Every attestation involving an address, oldest first, leaving out erased partners
+pub async fn get_all_attestations_for_address(pool: &PgPool, address: &str) -> Result<Vec<TogetherAttestation>> {
+    let attestations = sqlx::query_as::<_, TogetherAttestation>(
+        r#"
+        SELECT * FROM together_attestations
+        WHERE (LOWER(address_1) = LOWER($1) OR LOWER(address_2) = LOWER($1))
+        AND NOT EXISTS (
+            SELECT 1 FROM account_deletions ad
+            WHERE ad.status = 'completed' AND LOWER(ad.wallet_address) IN (LOWER(address_1), LOWER(address_2))
+        )
+        ORDER BY attestation_timestamp
+        "#
+    )
+    .bind(address)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(attestations)
+}
+
 /// Get watcher state for resuming blockchain watching
 pub async fn get_watcher_state(pool: &PgPool, watcher_id: &str) -> Result<Option<crate::models::attestations::WatcherState>> {
     let state = sqlx::query_as::<_, crate::models::attestations::WatcherState>(
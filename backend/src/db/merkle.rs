@@ -0,0 +1,136 @@
+use anyhow::Result;
+use sqlx::{PgConnection, PgPool};
+use crate::models::MerkleSnapshot;
+
+/// Store a snapshot together with every node of its tree, leaves first
+pub async fn insert_snapshot(pool: &PgPool, root: &str, block_height: i64, layers: &[Vec<String>]) -> Result<MerkleSnapshot> {
+    let mut tx = pool.begin().await?;
+
+    let snapshot = sqlx::query_as::<_, MerkleSnapshot>(
+        r#"
+        INSERT INTO merkle_snapshots (root, leaf_count, block_height)
+        VALUES ($1, $2, $3)
+        RETURNING *
+        "#
+    )
+    .bind(root)
+    .bind(layers.first().map_or(0, Vec::len) as i64)
+    .bind(block_height)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    insert_nodes(&mut tx, snapshot.id, layers).await?;
+
+    tx.commit().await?;
+    Ok(snapshot)
+}
+
+/// Store the tree of a snapshot taken before nodes were kept
+pub async fn backfill_snapshot_nodes(pool: &PgPool, snapshot_id: i64, layers: &[Vec<String>]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    insert_nodes(&mut tx, snapshot_id, layers).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn insert_nodes(conn: &mut PgConnection, snapshot_id: i64, layers: &[Vec<String>]) -> Result<()> {
+    for (layer, hashes) in layers.iter().enumerate() {
+        let positions: Vec<i32> = (0..hashes.len() as i32).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO merkle_snapshot_nodes (snapshot_id, layer, position, hash)
+            SELECT $1, $2, position, hash FROM UNNEST($3::int[], $4::text[]) AS nodes(position, hash)
+            "#
+        )
+        .bind(snapshot_id)
+        .bind(layer as i16)
+        .bind(&positions)
+        .bind(hashes)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn has_snapshot_nodes(pool: &PgPool, snapshot_id: i64) -> Result<bool> {
+    let exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM merkle_snapshot_nodes WHERE snapshot_id = $1)"
+    )
+    .bind(snapshot_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(exists)
+}
+
+/// Position of a leaf among a snapshot's sorted leaves
+pub async fn get_leaf_position(pool: &PgPool, snapshot_id: i64, leaf: &str) -> Result<Option<i32>> {
+    let position = sqlx::query_scalar::<_, i32>(
+        "SELECT position FROM merkle_snapshot_nodes WHERE snapshot_id = $1 AND layer = 0 AND hash = $2"
+    )
+    .bind(snapshot_id)
+    .bind(leaf)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(position)
+}
+
+/// Hashes of the given `(layer, position)` nodes of a snapshot, lowest layer first
+pub async fn get_snapshot_nodes(pool: &PgPool, snapshot_id: i64, positions: &[(usize, usize)]) -> Result<Vec<String>> {
+    let layers: Vec<i16> = positions.iter().map(|(layer, _)| *layer as i16).collect();
+    let indexes: Vec<i32> = positions.iter().map(|(_, position)| *position as i32).collect();
+    let hashes = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT n.hash FROM merkle_snapshot_nodes n
+        JOIN UNNEST($2::smallint[], $3::int[]) AS wanted(layer, position)
+            ON n.layer = wanted.layer AND n.position = wanted.position
+        WHERE n.snapshot_id = $1
+        ORDER BY n.layer, n.position
+        "#
+    )
+    .bind(snapshot_id)
+    .bind(&layers)
+    .bind(&indexes)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(hashes)
+}
+
+pub async fn get_latest_snapshot(pool: &PgPool) -> Result<Option<MerkleSnapshot>> {
+    let snapshot = sqlx::query_as::<_, MerkleSnapshot>(
+        "SELECT * FROM merkle_snapshots ORDER BY id DESC LIMIT 1"
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(snapshot)
+}
+
+pub async fn get_snapshot(pool: &PgPool, snapshot_id: i64) -> Result<Option<MerkleSnapshot>> {
+    let snapshot = sqlx::query_as::<_, MerkleSnapshot>(
+        "SELECT * FROM merkle_snapshots WHERE id = $1"
+    )
+    .bind(snapshot_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(snapshot)
+}
+
+/// (address_1, address_2, timestamp) of every on-chain attestation up to a block height
+pub async fn get_attestation_leaves(pool: &PgPool, block_height: i64) -> Result<Vec<(String, String, i64)>> {
+    let rows = sqlx::query_as::<_, (String, String, i64)>(
+        r#"
+        SELECT address_1, address_2, attestation_timestamp FROM together_attestations
+        WHERE block_number IS NOT NULL AND block_number <= $1
+        "#
+    )
+    .bind(block_height)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
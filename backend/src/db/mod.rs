@@ -2,5 +2,17 @@ pub mod connection;
 pub mod migrations;
 pub mod attestations;
 pub mod users;
+pub mod sessions;
+pub mod pairing;
+pub mod proximity;
+pub mod signatures;
+pub mod signers;
+pub mod merkle;
+pub mod pairs;
+pub mod account_deletions;
+pub mod webhooks;
+pub mod rate_limits;
+#[cfg(test)]
+pub mod testing;
 
 pub use connection::{get_db_pool, DatabaseConfig};
@@ -0,0 +1,121 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use crate::models::PairingCode;
+
+/// Get a user's pairing secret, creating it with `new_secret` on first use
+pub async fn get_or_create_pairing_secret(pool: &PgPool, user_id: i32, new_secret: &str) -> Result<String> {
+    let secret = sqlx::query_scalar::<_, String>(
+        r#"
+        INSERT INTO pairing_secrets (user_id, secret)
+        VALUES ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE SET user_id = pairing_secrets.user_id
+        RETURNING secret
+        "#
+    )
+    .bind(user_id)
+    .bind(new_secret)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(secret)
+}
+
+/// Record the code shown to a user for a rotation window
+pub async fn upsert_pairing_code(
+    pool: &PgPool,
+    user_id: i32,
+    code: &str,
+    window_index: i64,
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> Result<PairingCode> {
+    let pairing_code = sqlx::query_as::<_, PairingCode>(
+        r#"
+        INSERT INTO pairing_codes (code, user_id, window_index, expires_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id, window_index) DO UPDATE SET code = EXCLUDED.code
+        RETURNING *
+        "#
+    )
+    .bind(code)
+    .bind(user_id)
+    .bind(window_index)
+    .bind(expires_at)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(pairing_code)
+}
+
+/// Users whose currently valid code equals `code` (more than one means a collision)
+pub async fn find_users_by_pairing_code(pool: &PgPool, code: &str) -> Result<Vec<i32>> {
+    let user_ids = sqlx::query_scalar::<_, i32>(
+        r#"
+        SELECT DISTINCT user_id FROM pairing_codes
+        WHERE code = $1 AND expires_at > NOW()
+        "#
+    )
+    .bind(code)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(user_ids)
+}
+
+/// Count a pairing code the session typed that matched nobody
+pub async fn record_pairing_code_failure(pool: &PgPool, session_id: Uuid) -> Result<()> {
+    sqlx::query("INSERT INTO pairing_code_failures (session_id) VALUES ($1)")
+        .bind(session_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// When the session has `max_failures` failed codes within the window, the time the oldest of them ages out
+pub async fn get_pairing_code_lockout(
+    pool: &PgPool,
+    session_id: Uuid,
+    max_failures: i64,
+    window_secs: i64,
+) -> Result<Option<DateTime<Utc>>> {
+    let locked_until = sqlx::query_scalar::<_, DateTime<Utc>>(
+        r#"
+        SELECT MIN(attempted_at) + make_interval(secs => $3)
+        FROM (
+            SELECT attempted_at FROM pairing_code_failures
+            WHERE session_id = $1 AND attempted_at > NOW() - make_interval(secs => $3)
+            ORDER BY attempted_at DESC
+            LIMIT $2
+        ) recent
+        HAVING COUNT(*) >= $2
+        "#
+    )
+    .bind(session_id)
+    .bind(max_failures)
+    .bind(window_secs as f64)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(locked_until)
+}
+
+/// Remove failed codes that no longer count against their session
+pub async fn delete_stale_pairing_code_failures(pool: &PgPool, window_secs: i64) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM pairing_code_failures WHERE attempted_at <= NOW() - make_interval(secs => $1)")
+        .bind(window_secs as f64)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Remove codes that can no longer resolve
+pub async fn delete_expired_pairing_codes(pool: &PgPool) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM pairing_codes WHERE expires_at <= NOW()")
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,85 @@
+use anyhow::Result;
+use alloy::primitives::Address;
+use sqlx::PgPool;
+use crate::{models::{PairStats, TogetherPair}, utils::pair_token::pair_token_id};
+
+/// Register a pair under its token id, if it isn't already
+pub async fn upsert_pair(pool: &PgPool, address_1: &str, address_2: &str) -> Result<()> {
+    let token_id = pair_token_id(address_1.parse::<Address>()?, address_2.parse::<Address>()?);
+
+    sqlx::query(
+        r#"
+        INSERT INTO together_pairs (token_id, address_1, address_2)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (token_id) DO NOTHING
+        "#
+    )
+    .bind(token_id.to_string())
+    .bind(address_1)
+    .bind(address_2)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_pair(pool: &PgPool, token_id: &str) -> Result<Option<TogetherPair>> {
+    let pair = sqlx::query_as::<_, TogetherPair>(
+        "SELECT * FROM together_pairs WHERE token_id = $1"
+    )
+    .bind(token_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(pair)
+}
+
+/// First and last meeting and number of attestations for an ordered pair
+pub async fn get_pair_stats(pool: &PgPool, address_1: &str, address_2: &str) -> Result<Option<PairStats>> {
+    let stats = sqlx::query_as::<_, PairStats>(
+        r#"
+        SELECT
+            MIN(attestation_timestamp) as first_met,
+            MAX(attestation_timestamp) as last_met,
+            COUNT(*) as strength
+        FROM together_attestations
+        WHERE LOWER(address_1) = LOWER($1) AND LOWER(address_2) = LOWER($2)
+        HAVING COUNT(*) > 0
+        "#
+    )
+    .bind(address_1)
+    .bind(address_2)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(stats)
+}
+
+/// Register every attested pair missing from `together_pairs`, returning how many were added
+///
+/// Token ids are computed here rather than in SQL, so pairs attested before the table
+/// existed are filled in by this instead of a migration.
+pub async fn backfill_pairs(pool: &PgPool) -> Result<usize> {
+    let missing = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT DISTINCT ta.address_1, ta.address_2
+        FROM together_attestations ta
+        WHERE NOT EXISTS (
+            SELECT 1 FROM together_pairs tp
+            WHERE LOWER(tp.address_1) = LOWER(ta.address_1) AND LOWER(tp.address_2) = LOWER(ta.address_2)
+        )
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut added = 0;
+    for (address_1, address_2) in missing {
+        match upsert_pair(pool, &address_1, &address_2).await {
+            Ok(()) => added += 1,
+            Err(e) => tracing::warn!("Skipping pair {} / {}: {}", address_1, address_2, e),
+        }
+    }
+
+    Ok(added)
+}
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use crate::models::{ProximityChallenge, ProximityExchange, ProximityEvidence, ProximityResponse};
+
+/// Issue a challenge nonce to a user
+pub async fn create_challenge(
+    pool: &PgPool,
+    issuer_user_id: i32,
+    nonce: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<ProximityChallenge> {
+    let challenge = sqlx::query_as::<_, ProximityChallenge>(
+        r#"
+        INSERT INTO proximity_challenges (issuer_user_id, nonce, expires_at)
+        VALUES ($1, $2, $3)
+        RETURNING *
+        "#
+    )
+    .bind(issuer_user_id)
+    .bind(nonce)
+    .bind(expires_at)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(challenge)
+}
+
+/// Whether an unexpired challenge already uses this nonce
+pub async fn active_challenge_exists(pool: &PgPool, nonce: &str) -> Result<bool> {
+    let exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM proximity_challenges WHERE nonce = $1 AND expires_at > NOW())"
+    )
+    .bind(nonce)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(exists)
+}
+
+/// Record that `responder_user_id` reported `nonce`, if it matches an active challenge from someone else
+pub async fn record_response(
+    pool: &PgPool,
+    nonce: &str,
+    responder_user_id: i32,
+) -> Result<Option<(ProximityResponse, ProximityChallenge)>> {
+    let challenge = sqlx::query_as::<_, ProximityChallenge>(
+        r#"
+        SELECT * FROM proximity_challenges
+        WHERE nonce = $1 AND expires_at > NOW() AND issuer_user_id != $2
+        ORDER BY created_at DESC
+        LIMIT 1
+        "#
+    )
+    .bind(nonce)
+    .bind(responder_user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(challenge) = challenge else {
+        return Ok(None);
+    };
+
+    let response = sqlx::query_as::<_, ProximityResponse>(
+        r#"
+        INSERT INTO proximity_responses (challenge_id, responder_user_id, latency_ms)
+        VALUES ($1, $2, (EXTRACT(EPOCH FROM (NOW() - $3)) * 1000)::BIGINT)
+        ON CONFLICT (challenge_id, responder_user_id) DO UPDATE SET challenge_id = EXCLUDED.challenge_id
+        RETURNING *
+        "#
+    )
+    .bind(challenge.id)
+    .bind(responder_user_id)
+    .bind(challenge.created_at)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(Some((response, challenge)))
+}
+
+/// Unconsumed exchanges between two users (either direction) received since `since`
+pub async fn get_recent_exchanges(
+    pool: &PgPool,
+    user_id_1: i32,
+    user_id_2: i32,
+    since: DateTime<Utc>,
+) -> Result<Vec<ProximityExchange>> {
+    let exchanges = sqlx::query_as::<_, ProximityExchange>(
+        r#"
+        SELECT pr.id as response_id, pc.id as challenge_id, pc.issuer_user_id, pr.responder_user_id,
+               pr.latency_ms, pr.received_at
+        FROM proximity_responses pr
+        JOIN proximity_challenges pc ON pc.id = pr.challenge_id
+        WHERE pr.consumed_at IS NULL AND pr.received_at > $3
+        AND ((pc.issuer_user_id = $1 AND pr.responder_user_id = $2)
+          OR (pc.issuer_user_id = $2 AND pr.responder_user_id = $1))
+        ORDER BY pr.received_at DESC
+        "#
+    )
+    .bind(user_id_1)
+    .bind(user_id_2)
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(exchanges)
+}
+
+/// Store the evidence behind an attestation and mark its responses as used
+pub async fn record_evidence(
+    pool: &PgPool,
+    user_id_1: i32,
+    user_id_2: i32,
+    attestation_timestamp: i64,
+    optimistic_connection_id: Option<Uuid>,
+    response_ids: &[Uuid],
+) -> Result<ProximityEvidence> {
+    let (smaller_id, larger_id) = if user_id_1 < user_id_2 {
+        (user_id_1, user_id_2)
+    } else {
+        (user_id_2, user_id_1)
+    };
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("UPDATE proximity_responses SET consumed_at = NOW() WHERE id = ANY($1)")
+        .bind(response_ids)
+        .execute(&mut *tx)
+        .await?;
+
+    let evidence = sqlx::query_as::<_, ProximityEvidence>(
+        r#"
+        INSERT INTO proximity_evidence (user_id_1, user_id_2, attestation_timestamp, optimistic_connection_id, response_ids)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#
+    )
+    .bind(smaller_id)
+    .bind(larger_id)
+    .bind(attestation_timestamp)
+    .bind(optimistic_connection_id)
+    .bind(response_ids)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(evidence)
+}
@@ -0,0 +1,53 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+/// Take requests from `key`'s allowance, returning false (and changing nothing) if they don't fit
+///
+/// A single GCRA step: the TAT advances by `advance_ms` (one emission interval per request) as
+/// long as it is within `tolerance_ms` of now. Atomic, so replicas sharing the table can't
+/// overshoot the limit.
+pub async fn try_acquire(pool: &PgPool, key: &str, advance_ms: i64, tolerance_ms: i64) -> Result<bool> {
+    let acquired = sqlx::query_scalar::<_, bool>(
+        r#"
+        INSERT INTO rate_limits (key, tat)
+        VALUES ($1, NOW() + $2::BIGINT * INTERVAL '1 millisecond')
+        ON CONFLICT (key) DO UPDATE
+        SET tat = GREATEST(rate_limits.tat, NOW()) + $2::BIGINT * INTERVAL '1 millisecond'
+        WHERE GREATEST(rate_limits.tat, NOW()) - NOW() <= $3::BIGINT * INTERVAL '1 millisecond'
+        RETURNING TRUE
+        "#
+    )
+    .bind(key)
+    .bind(advance_ms)
+    .bind(tolerance_ms)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(acquired.is_some())
+}
+
+/// Milliseconds until `key` may make another request
+pub async fn retry_after_ms(pool: &PgPool, key: &str, tolerance_ms: i64) -> Result<i64> {
+    let wait = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT GREATEST(CEIL(EXTRACT(EPOCH FROM tat - NOW()) * 1000) - $2, 0)::BIGINT
+        FROM rate_limits
+        WHERE key = $1
+        "#
+    )
+    .bind(key)
+    .bind(tolerance_ms)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(wait.unwrap_or(0))
+}
+
+/// Drop keys whose allowance has fully recovered
+pub async fn delete_expired(pool: &PgPool) -> Result<u64> {
+    let result = sqlx::query("DELETE FROM rate_limits WHERE tat < NOW()")
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
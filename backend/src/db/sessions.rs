@@ -0,0 +1,164 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use crate::models::{AuthNonce, User, UserSession};
+
+/// Store a freshly issued sign-in nonce for a wallet
+pub async fn create_auth_nonce(pool: &PgPool, nonce: &str, wallet_address: &str) -> Result<AuthNonce> {
+    let auth_nonce = sqlx::query_as::<_, AuthNonce>(
+        r#"
+        INSERT INTO auth_nonces (nonce, wallet_address)
+        VALUES ($1, $2)
+        RETURNING *
+        "#
+    )
+    .bind(nonce)
+    .bind(wallet_address)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(auth_nonce)
+}
+
+/// Mark a nonce as consumed, returning it only if it was still unused and unexpired
+pub async fn consume_auth_nonce(pool: &PgPool, nonce: &str) -> Result<Option<AuthNonce>> {
+    let auth_nonce = sqlx::query_as::<_, AuthNonce>(
+        r#"
+        UPDATE auth_nonces
+        SET consumed_at = NOW()
+        WHERE nonce = $1 AND consumed_at IS NULL AND expires_at > NOW()
+        RETURNING *
+        "#
+    )
+    .bind(nonce)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(auth_nonce)
+}
+
+/// Create a session for a user from an already hashed bearer token
+pub async fn create_session(
+    pool: &PgPool,
+    user_id: i32,
+    token_hash: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<UserSession> {
+    let session = sqlx::query_as::<_, UserSession>(
+        r#"
+        INSERT INTO user_sessions (user_id, token_hash, expires_at)
+        VALUES ($1, $2, $3)
+        RETURNING *
+        "#
+    )
+    .bind(user_id)
+    .bind(token_hash)
+    .bind(expires_at)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(session)
+}
+
+/// Resolve an unexpired session and its user by token hash, touching `last_seen_at`
+pub async fn get_session_user(pool: &PgPool, token_hash: &str) -> Result<Option<(UserSession, User)>> {
+    let session = sqlx::query_as::<_, UserSession>(
+        r#"
+        UPDATE user_sessions
+        SET last_seen_at = NOW()
+        WHERE token_hash = $1 AND expires_at > NOW()
+        RETURNING *
+        "#
+    )
+    .bind(token_hash)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(session) = session else {
+        return Ok(None);
+    };
+
+    let user = crate::db::users::get_user_by_id(pool, session.user_id).await?;
+    Ok(user.map(|user| (session, user)))
+}
+
+/// Store a stream ticket for a session from an already hashed ticket
+pub async fn create_stream_ticket(
+    pool: &PgPool,
+    session_id: Uuid,
+    ticket_hash: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query("INSERT INTO stream_tickets (ticket_hash, session_id, expires_at) VALUES ($1, $2, $3)")
+        .bind(ticket_hash)
+        .bind(session_id)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Consume a stream ticket, resolving its session and user only if both ticket and session are unexpired
+pub async fn consume_stream_ticket(pool: &PgPool, ticket_hash: &str) -> Result<Option<(UserSession, User)>> {
+    let session = sqlx::query_as::<_, UserSession>(
+        r#"
+        WITH ticket AS (
+            DELETE FROM stream_tickets
+            WHERE ticket_hash = $1
+            RETURNING session_id, expires_at
+        )
+        UPDATE user_sessions s
+        SET last_seen_at = NOW()
+        FROM ticket
+        WHERE s.id = ticket.session_id AND ticket.expires_at > NOW() AND s.expires_at > NOW()
+        RETURNING s.*
+        "#
+    )
+    .bind(ticket_hash)
+    .fetch_optional(pool)
+    .await?;
+
+    let Some(session) = session else {
+        return Ok(None);
+    };
+
+    let user = crate::db::users::get_user_by_id(pool, session.user_id).await?;
+    Ok(user.map(|user| (session, user)))
+}
+
+/// Ids of the sessions a user holds, until they are swept after expiring
+pub async fn get_session_ids_for_user(pool: &PgPool, user_id: i32) -> Result<Vec<Uuid>> {
+    let ids = sqlx::query_scalar::<_, Uuid>("SELECT id FROM user_sessions WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(ids)
+}
+
+/// Delete a session (sign out)
+pub async fn delete_session(pool: &PgPool, token_hash: &str) -> Result<()> {
+    sqlx::query("DELETE FROM user_sessions WHERE token_hash = $1")
+        .bind(token_hash)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Remove expired nonces, stream tickets and sessions
+pub async fn delete_expired_auth_rows(pool: &PgPool) -> Result<u64> {
+    let nonces = sqlx::query("DELETE FROM auth_nonces WHERE expires_at <= NOW()")
+        .execute(pool)
+        .await?;
+    let tickets = sqlx::query("DELETE FROM stream_tickets WHERE expires_at <= NOW()")
+        .execute(pool)
+        .await?;
+    let sessions = sqlx::query("DELETE FROM user_sessions WHERE expires_at <= NOW()")
+        .execute(pool)
+        .await?;
+
+    Ok(nonces.rows_affected() + tickets.rows_affected() + sessions.rows_affected())
+}
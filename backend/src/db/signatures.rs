@@ -0,0 +1,224 @@
+use anyhow::Result;
+use sqlx::{PgConnection, PgPool};
+use chrono::{DateTime, Utc};
+use crate::{
+    constants::SIGNATURE_SOURCE_CONNECTION_CHECKER,
+    models::{NewSignatureIssuance, SignatureIssuance},
+};
+
+/// Record a signature before it is handed out or relayed; see `services::signatures::record_within_quota`
+pub async fn record_issuance(conn: &mut PgConnection, issuance: &NewSignatureIssuance<'_>) -> Result<SignatureIssuance> {
+    let record = sqlx::query_as::<_, SignatureIssuance>(
+        r#"
+        INSERT INTO signature_issuances (
+            nonce, on_behalf_of, together_with, attestation_timestamp, deadline,
+            signer_address, source, requester_ip, session_id, requester
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING *
+        "#
+    )
+    .bind(&issuance.nonce)
+    .bind(&issuance.on_behalf_of)
+    .bind(&issuance.together_with)
+    .bind(issuance.attestation_timestamp)
+    .bind(issuance.deadline)
+    .bind(&issuance.signer_address)
+    .bind(issuance.source)
+    .bind(&issuance.requester_ip)
+    .bind(issuance.session_id)
+    .bind(&issuance.requester)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(record)
+}
+
+/// Look up an issuance by its AuthData nonce
+pub async fn get_issuance_by_nonce(pool: &PgPool, nonce: &str) -> Result<Option<SignatureIssuance>> {
+    let record = sqlx::query_as::<_, SignatureIssuance>(
+        "SELECT * FROM signature_issuances WHERE nonce = $1"
+    )
+    .bind(nonce)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record)
+}
+
+/// Every issuance involving an address (on either side), oldest first
+pub async fn get_issuances_for_address(pool: &PgPool, address: &str) -> Result<Vec<SignatureIssuance>> {
+    let records = sqlx::query_as::<_, SignatureIssuance>(
+        r#"
+        SELECT * FROM signature_issuances
+        WHERE LOWER(on_behalf_of) = LOWER($1) OR LOWER(together_with) = LOWER($1)
+        ORDER BY created_at
+        "#
+    )
+    .bind(address)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(records)
+}
+
+/// Hold `key`'s quota lock until the transaction ends, so checks and inserts for it don't interleave
+pub async fn lock_quota(conn: &mut PgConnection, key: &str) -> Result<()> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+        .bind(key)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Count signatures requested by `requester` since `since`
+pub async fn count_requested_issuances(conn: &mut PgConnection, requester: &str, since: DateTime<Utc>) -> Result<i64> {
+    let count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM signature_issuances WHERE requester = $1 AND created_at > $2"
+    )
+    .bind(requester)
+    .bind(since)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(count)
+}
+
+/// Count client-requested signatures for a pair (in either order) since `since`, whoever asked
+pub async fn count_requested_pair_issuances(
+    conn: &mut PgConnection,
+    address_1: &str,
+    address_2: &str,
+    since: DateTime<Utc>,
+) -> Result<i64> {
+    let count = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*) FROM signature_issuances
+        WHERE ((LOWER(on_behalf_of) = LOWER($1) AND LOWER(together_with) = LOWER($2))
+            OR (LOWER(on_behalf_of) = LOWER($2) AND LOWER(together_with) = LOWER($1)))
+        AND requester IS NOT NULL AND created_at > $3
+        "#
+    )
+    .bind(address_1)
+    .bind(address_2)
+    .bind(since)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(count)
+}
+
+/// Count client-requested signatures involving an address (on either side) since `since`, whoever asked
+pub async fn count_requested_issuances_for_address(conn: &mut PgConnection, address: &str, since: DateTime<Utc>) -> Result<i64> {
+    let count = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*) FROM signature_issuances
+        WHERE (LOWER(on_behalf_of) = LOWER($1) OR LOWER(together_with) = LOWER($1))
+        AND requester IS NOT NULL AND created_at > $2
+        "#
+    )
+    .bind(address)
+    .bind(since)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(count)
+}
+
+/// Count relayed signatures involving an address (on either side) since `since`
+pub async fn count_relayed_issuances_for_address(conn: &mut PgConnection, address: &str, since: DateTime<Utc>) -> Result<i64> {
+    let count = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*) FROM signature_issuances
+        WHERE (LOWER(on_behalf_of) = LOWER($1) OR LOWER(together_with) = LOWER($1))
+        AND source = $2 AND created_at > $3
+        "#
+    )
+    .bind(address)
+    .bind(SIGNATURE_SOURCE_CONNECTION_CHECKER)
+    .bind(since)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(count)
+}
+
+/// Count relayed signatures for a pair (in either order) since `since`
+pub async fn count_relayed_pair_issuances(
+    conn: &mut PgConnection,
+    address_1: &str,
+    address_2: &str,
+    since: DateTime<Utc>,
+) -> Result<i64> {
+    let count = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*) FROM signature_issuances
+        WHERE ((LOWER(on_behalf_of) = LOWER($1) AND LOWER(together_with) = LOWER($2))
+            OR (LOWER(on_behalf_of) = LOWER($2) AND LOWER(together_with) = LOWER($1)))
+        AND source = $3 AND created_at > $4
+        "#
+    )
+    .bind(address_1)
+    .bind(address_2)
+    .bind(SIGNATURE_SOURCE_CONNECTION_CHECKER)
+    .bind(since)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(count)
+}
+
+/// Mark the issuance matching an on-chain Together event as consumed
+///
+/// The event doesn't carry the nonce, so match on the pair and attestation timestamp
+/// and take the oldest unconsumed issuance.
+pub async fn mark_consumed_by_event(
+    pool: &PgPool,
+    on_behalf_of: &str,
+    together_with: &str,
+    attestation_timestamp: i64,
+    tx_hash: &str,
+) -> Result<Option<SignatureIssuance>> {
+    let record = sqlx::query_as::<_, SignatureIssuance>(
+        r#"
+        UPDATE signature_issuances
+        SET consumed_at = NOW(), consumed_tx_hash = $4
+        WHERE id = (
+            SELECT id FROM signature_issuances
+            WHERE LOWER(on_behalf_of) = LOWER($1)
+            AND LOWER(together_with) = LOWER($2)
+            AND attestation_timestamp = $3
+            AND consumed_at IS NULL
+            ORDER BY created_at ASC
+            LIMIT 1
+        )
+        RETURNING *
+        "#
+    )
+    .bind(on_behalf_of)
+    .bind(together_with)
+    .bind(attestation_timestamp)
+    .bind(tx_hash)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record)
+}
+
+/// Mark an issuance consumed after confirming the nonce is used on chain
+pub async fn mark_consumed_by_nonce(pool: &PgPool, nonce: &str) -> Result<Option<SignatureIssuance>> {
+    let record = sqlx::query_as::<_, SignatureIssuance>(
+        r#"
+        UPDATE signature_issuances
+        SET consumed_at = COALESCE(consumed_at, NOW())
+        WHERE nonce = $1
+        RETURNING *
+        "#
+    )
+    .bind(nonce)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(record)
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use crate::models::SignerStatus;
+
+/// Record a SignerAllowed/SignerDenied event, ignoring events older than what we have
+pub async fn upsert_signer_status(
+    pool: &PgPool,
+    address: &str,
+    allowed: bool,
+    tx_hash: &str,
+    block_number: i64,
+) -> Result<SignerStatus> {
+    let status = sqlx::query_as::<_, SignerStatus>(
+        r#"
+        INSERT INTO signer_status (address, allowed, tx_hash, block_number)
+        VALUES (LOWER($1), $2, $3, $4)
+        ON CONFLICT (address) DO UPDATE
+        SET allowed = EXCLUDED.allowed,
+            tx_hash = EXCLUDED.tx_hash,
+            block_number = EXCLUDED.block_number,
+            updated_at = NOW()
+        WHERE signer_status.block_number <= EXCLUDED.block_number
+        RETURNING *
+        "#
+    )
+    .bind(address)
+    .bind(allowed)
+    .bind(tx_hash)
+    .bind(block_number)
+    .fetch_optional(pool)
+    .await?;
+
+    match status {
+        Some(status) => Ok(status),
+        None => get_signer_status(pool, address).await?
+            .ok_or_else(|| anyhow::anyhow!("Signer status for {} disappeared", address)),
+    }
+}
+
+pub async fn get_signer_status(pool: &PgPool, address: &str) -> Result<Option<SignerStatus>> {
+    let status = sqlx::query_as::<_, SignerStatus>(
+        "SELECT * FROM signer_status WHERE address = LOWER($1)"
+    )
+    .bind(address)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(status)
+}
+
+/// Addresses the contract currently refuses signatures from
+pub async fn get_denied_signers(pool: &PgPool) -> Result<Vec<String>> {
+    let addresses = sqlx::query_scalar::<_, String>(
+        "SELECT address FROM signer_status WHERE allowed = FALSE"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(addresses)
+}
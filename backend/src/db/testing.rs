@@ -0,0 +1,25 @@
+//! Helpers for tests that need a real database
+//!
+//! Set `TEST_DATABASE_URL` to a scratch Postgres database to run them; tests skip
+//! themselves when it is unset. Tests share the database, so each one works on
+//! fresh random wallets instead of assuming empty tables.
+
+use alloy::primitives::Address;
+use sqlx::PgPool;
+use crate::db::migrations::run_migrations;
+
+/// A migrated pool on `TEST_DATABASE_URL`, or `None` to skip the test
+pub async fn test_pool() -> Option<PgPool> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL not set, skipping database test");
+        return None;
+    };
+    let pool = PgPool::connect(&url).await.expect("connect to TEST_DATABASE_URL");
+    run_migrations(&pool).await.expect("run migrations");
+    Some(pool)
+}
+
+/// A wallet address no other test will use
+pub fn random_wallet() -> String {
+    Address::from(rand::random::<[u8; 20]>()).to_string()
+}
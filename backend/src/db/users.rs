@@ -1,4 +1,4 @@
-use crate::models::{User, PendingConnection, OptimisticConnection, PendingConnectionMatch};
+use crate::models::{User, PendingConnection, OptimisticConnection, PendingConnectionMatch, PendingConnectionResolution, ResolutionAction, ResolutionReason};
 use anyhow::Result;
 use sqlx::PgPool;
 use chrono::{DateTime, Utc};
@@ -190,6 +190,98 @@ pub async fn delete_expired_pending_connections(pool: &PgPool) -> Result<u64> {
     Ok(result.rows_affected())
 }
 
+/// Cancel an outgoing pending connection owned by `from_user_id`, recording the reason
+pub async fn cancel_pending_connection(
+    pool: &PgPool,
+    connection_id: uuid::Uuid,
+    from_user_id: i32,
+    reason: ResolutionReason,
+) -> Result<Option<PendingConnection>> {
+    resolve_pending_connection(pool, connection_id, from_user_id, ResolutionAction::Cancelled, reason).await
+}
+
+/// Decline an incoming pending connection addressed to `to_user_id`, suppressing the sender until `suppressed_until`
+pub async fn decline_pending_connection(
+    pool: &PgPool,
+    connection_id: uuid::Uuid,
+    to_user_id: i32,
+    reason: ResolutionReason,
+    suppressed_until: DateTime<Utc>,
+) -> Result<Option<PendingConnection>> {
+    resolve_pending_connection(pool, connection_id, to_user_id, ResolutionAction::Declined { suppressed_until }, reason).await
+}
+
+// Deletes the pending connection and records the resolution atomically. `owner_user_id`
+// must be the sender for cancels and the recipient for declines.
+async fn resolve_pending_connection(
+    pool: &PgPool,
+    connection_id: uuid::Uuid,
+    owner_user_id: i32,
+    action: ResolutionAction,
+    reason: ResolutionReason,
+) -> Result<Option<PendingConnection>> {
+    let delete = match action {
+        ResolutionAction::Cancelled => r#"
+            DELETE FROM pending_connections
+            WHERE id = $1 AND from_user_id = $2
+            RETURNING id, from_user_id, to_user_id, created_at, expires_at
+            "#,
+        ResolutionAction::Declined { .. } => r#"
+            DELETE FROM pending_connections
+            WHERE id = $1 AND to_user_id = $2
+            RETURNING id, from_user_id, to_user_id, created_at, expires_at
+            "#,
+    };
+    let mut tx = pool.begin().await?;
+
+    let pending = sqlx::query_as::<_, PendingConnection>(delete)
+        .bind(connection_id)
+        .bind(owner_user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    let Some(pending) = pending else {
+        return Ok(None);
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO pending_connection_resolutions
+            (pending_connection_id, from_user_id, to_user_id, action, reason_code, suppressed_until)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#
+    )
+    .bind(pending.id)
+    .bind(pending.from_user_id)
+    .bind(pending.to_user_id)
+    .bind(action.as_str())
+    .bind(reason.as_str())
+    .bind(action.suppressed_until())
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(pending))
+}
+
+/// When `to_user_id` has declined `from_user_id` recently, the time the cooldown ends
+pub async fn get_decline_suppression(pool: &PgPool, from_user_id: i32, to_user_id: i32) -> Result<Option<DateTime<Utc>>> {
+    let suppressed_until = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+        r#"
+        SELECT MAX(suppressed_until)
+        FROM pending_connection_resolutions
+        WHERE from_user_id = $1 AND to_user_id = $2
+        AND action = 'declined' AND suppressed_until > NOW()
+        "#
+    )
+    .bind(from_user_id)
+    .bind(to_user_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(suppressed_until)
+}
+
 // Optimistic connection operations
 pub async fn create_optimistic_connection(
     pool: &PgPool, 
@@ -341,3 +433,132 @@ pub async fn mark_oldest_optimistic_connection_processed(pool: &PgPool, user_id_
 
     Ok(())
 }
+
+/// Pending connections a user sent or received that are still open
+pub async fn get_pending_connections_involving(pool: &PgPool, user_id: i32) -> Result<Vec<PendingConnection>> {
+    let pending = sqlx::query_as::<_, PendingConnection>(
+        r#"
+        SELECT id, from_user_id, to_user_id, created_at, expires_at
+        FROM pending_connections
+        WHERE from_user_id = $1 OR to_user_id = $1
+        ORDER BY created_at
+        "#
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(pending)
+}
+
+/// Cancels and declines of pending connections a user sent or received
+pub async fn get_pending_connection_resolutions_involving(pool: &PgPool, user_id: i32) -> Result<Vec<PendingConnectionResolution>> {
+    let resolutions = sqlx::query_as::<_, PendingConnectionResolution>(
+        r#"
+        SELECT * FROM pending_connection_resolutions
+        WHERE from_user_id = $1 OR to_user_id = $1
+        ORDER BY created_at
+        "#
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(resolutions)
+}
+
+/// Every optimistic connection a user is part of, processed or not
+pub async fn get_optimistic_connections_involving(pool: &PgPool, user_id: i32) -> Result<Vec<OptimisticConnection>> {
+    let optimistic = sqlx::query_as::<_, OptimisticConnection>(
+        r#"
+        SELECT id, user_id_1, user_id_2, processed, created_at
+        FROM optimistic_connections
+        WHERE user_id_1 = $1 OR user_id_2 = $1
+        ORDER BY created_at
+        "#
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(optimistic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::testing::{random_wallet, test_pool};
+
+    async fn pending_pair(pool: &PgPool) -> (User, User, PendingConnection) {
+        let from = create_user(pool, &random_wallet()).await.unwrap();
+        let to = create_user(pool, &random_wallet()).await.unwrap();
+        let pending = create_pending_connection(pool, from.id, to.id).await.unwrap();
+        (from, to, pending)
+    }
+
+    #[test]
+    fn test_resolution_action_matches_schema() {
+        assert_eq!(ResolutionAction::Cancelled.as_str(), "cancelled");
+        assert_eq!(ResolutionAction::Cancelled.suppressed_until(), None);
+        let until = Utc::now();
+        let declined = ResolutionAction::Declined { suppressed_until: until };
+        assert_eq!(declined.as_str(), "declined");
+        assert_eq!(declined.suppressed_until(), Some(until));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_is_sender_only() {
+        let Some(pool) = test_pool().await else { return };
+        let (from, to, pending) = pending_pair(&pool).await;
+
+        let by_recipient = cancel_pending_connection(&pool, pending.id, to.id, ResolutionReason::Other).await.unwrap();
+        assert!(by_recipient.is_none());
+        assert!(get_pending_connection(&pool, from.id, to.id).await.unwrap().is_some());
+
+        let cancelled = cancel_pending_connection(&pool, pending.id, from.id, ResolutionReason::WrongUser).await.unwrap();
+        assert_eq!(cancelled.map(|p| p.id), Some(pending.id));
+        assert!(get_pending_connection(&pool, from.id, to.id).await.unwrap().is_none());
+
+        let resolutions = get_pending_connection_resolutions_involving(&pool, from.id).await.unwrap();
+        assert_eq!(resolutions.len(), 1);
+        assert_eq!(resolutions[0].action, "cancelled");
+        assert_eq!(resolutions[0].reason_code, "wrong_user");
+        assert!(resolutions[0].suppressed_until.is_none());
+
+        // A cancel never suppresses the sender
+        assert!(get_decline_suppression(&pool, from.id, to.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_decline_is_recipient_only_and_suppresses_sender() {
+        let Some(pool) = test_pool().await else { return };
+        let (from, to, pending) = pending_pair(&pool).await;
+        let until = Utc::now() + chrono::Duration::minutes(30);
+
+        let by_sender = decline_pending_connection(&pool, pending.id, from.id, ResolutionReason::Spam, until).await.unwrap();
+        assert!(by_sender.is_none());
+
+        let declined = decline_pending_connection(&pool, pending.id, to.id, ResolutionReason::Spam, until).await.unwrap();
+        assert_eq!(declined.map(|p| p.id), Some(pending.id));
+        assert!(get_pending_connection(&pool, from.id, to.id).await.unwrap().is_none());
+
+        let resolutions = get_pending_connection_resolutions_involving(&pool, to.id).await.unwrap();
+        assert_eq!(resolutions.len(), 1);
+        assert_eq!(resolutions[0].action, "declined");
+
+        let suppressed = get_decline_suppression(&pool, from.id, to.id).await.unwrap().unwrap();
+        assert!((suppressed - until).num_milliseconds().abs() < 1);
+        // Only the declined direction is suppressed
+        assert!(get_decline_suppression(&pool, to.id, from.id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_expired_decline_no_longer_suppresses() {
+        let Some(pool) = test_pool().await else { return };
+        let (from, to, pending) = pending_pair(&pool).await;
+        let until = Utc::now() - chrono::Duration::seconds(1);
+
+        decline_pending_connection(&pool, pending.id, to.id, ResolutionReason::Other, until).await.unwrap().unwrap();
+        assert!(get_decline_suppression(&pool, from.id, to.id).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,209 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::models::{WebhookEndpoint, WebhookDelivery, DueWebhookDelivery};
+
+/// Register a partner endpoint; empty filters match every event type or address
+pub async fn create_endpoint(
+    pool: &PgPool,
+    url: &str,
+    secret: &str,
+    description: Option<&str>,
+    event_types: &[String],
+    addresses: &[String],
+) -> Result<WebhookEndpoint> {
+    let addresses: Vec<String> = addresses.iter().map(|address| address.to_lowercase()).collect();
+
+    let endpoint = sqlx::query_as::<_, WebhookEndpoint>(
+        r#"
+        INSERT INTO webhook_endpoints (url, secret, description, event_types, addresses)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING *
+        "#
+    )
+    .bind(url)
+    .bind(secret)
+    .bind(description)
+    .bind(event_types)
+    .bind(&addresses)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(endpoint)
+}
+
+pub async fn get_endpoint(pool: &PgPool, id: Uuid) -> Result<Option<WebhookEndpoint>> {
+    let endpoint = sqlx::query_as::<_, WebhookEndpoint>("SELECT * FROM webhook_endpoints WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(endpoint)
+}
+
+pub async fn list_endpoints(pool: &PgPool) -> Result<Vec<WebhookEndpoint>> {
+    let endpoints = sqlx::query_as::<_, WebhookEndpoint>("SELECT * FROM webhook_endpoints ORDER BY created_at")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(endpoints)
+}
+
+/// Pause or resume an endpoint; returns false if it doesn't exist
+pub async fn set_endpoint_active(pool: &PgPool, id: Uuid, active: bool) -> Result<bool> {
+    let result = sqlx::query("UPDATE webhook_endpoints SET active = $2 WHERE id = $1")
+        .bind(id)
+        .bind(active)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Remove an endpoint along with its delivery log
+pub async fn delete_endpoint(pool: &PgPool, id: Uuid) -> Result<bool> {
+    let result = sqlx::query("DELETE FROM webhook_endpoints WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Queue an event for every active endpoint whose filters match, returning how many were queued
+pub async fn enqueue_deliveries(
+    pool: &PgPool,
+    event_id: Uuid,
+    event_type: &str,
+    addresses: &[String],
+    payload: &str,
+) -> Result<u64> {
+    let addresses: Vec<String> = addresses.iter().map(|address| address.to_lowercase()).collect();
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO webhook_deliveries (endpoint_id, event_id, event_type, payload)
+        SELECT id, $1, $2, $4
+        FROM webhook_endpoints
+        WHERE active
+          AND (cardinality(event_types) = 0 OR $2 = ANY(event_types))
+          AND (cardinality(addresses) = 0 OR addresses && $3)
+        ON CONFLICT (endpoint_id, event_id) DO NOTHING
+        "#
+    )
+    .bind(event_id)
+    .bind(event_type)
+    .bind(&addresses)
+    .bind(payload)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Claim up to `limit` due deliveries, counting the attempt and leasing them until `lease_until`
+///
+/// The lease means a dispatcher that dies mid-send doesn't strand a delivery, and
+/// `SKIP LOCKED` keeps concurrent dispatchers from sending the same one twice.
+pub async fn claim_due_deliveries(
+    pool: &PgPool,
+    limit: i64,
+    lease_until: DateTime<Utc>,
+) -> Result<Vec<DueWebhookDelivery>> {
+    let deliveries = sqlx::query_as::<_, DueWebhookDelivery>(
+        r#"
+        UPDATE webhook_deliveries d
+        SET attempts = d.attempts + 1, next_attempt_at = $2
+        FROM webhook_endpoints e
+        WHERE e.id = d.endpoint_id
+          AND d.id IN (
+              SELECT id FROM webhook_deliveries
+              WHERE status = 'pending' AND next_attempt_at <= NOW()
+              ORDER BY next_attempt_at
+              LIMIT $1
+              FOR UPDATE SKIP LOCKED
+          )
+        RETURNING d.id, d.endpoint_id, d.event_type, d.payload, d.attempts, e.url, e.secret
+        "#
+    )
+    .bind(limit)
+    .bind(lease_until)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(deliveries)
+}
+
+pub async fn mark_delivered(pool: &PgPool, id: Uuid, response_status: i32) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE webhook_deliveries
+        SET status = 'delivered', delivered_at = NOW(), last_response_status = $2, last_error = NULL
+        WHERE id = $1
+        "#
+    )
+    .bind(id)
+    .bind(response_status)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Record a failed attempt, scheduling a retry or giving up when `retry_at` is `None`
+pub async fn mark_attempt_failed(
+    pool: &PgPool,
+    id: Uuid,
+    response_status: Option<i32>,
+    error: &str,
+    retry_at: Option<DateTime<Utc>>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE webhook_deliveries
+        SET status = CASE WHEN $4::timestamptz IS NULL THEN 'failed' ELSE 'pending' END,
+            next_attempt_at = COALESCE($4, next_attempt_at),
+            last_response_status = $2,
+            last_error = $3
+        WHERE id = $1
+        "#
+    )
+    .bind(id)
+    .bind(response_status)
+    .bind(error)
+    .bind(retry_at)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Put a failed delivery back in the queue for an immediate retry
+pub async fn retry_delivery(pool: &PgPool, id: Uuid) -> Result<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE webhook_deliveries
+        SET status = 'pending', next_attempt_at = NOW(), attempts = 0
+        WHERE id = $1 AND status = 'failed'
+        "#
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// An endpoint's most recent deliveries, newest first
+pub async fn get_recent_deliveries(pool: &PgPool, endpoint_id: Uuid, limit: i64) -> Result<Vec<WebhookDelivery>> {
+    let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+        "SELECT * FROM webhook_deliveries WHERE endpoint_id = $1 ORDER BY created_at DESC LIMIT $2"
+    )
+    .bind(endpoint_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(deliveries)
+}
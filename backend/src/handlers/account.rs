@@ -0,0 +1,123 @@
+use axum::{extract::{State, Path}, response::Json};
+use chrono::Utc;
+use crate::{
+    AppState,
+    constants::ACCOUNT_DELETION_GRACE_DAYS,
+    db::{account_deletions, users},
+    models::AccountDeletion,
+    handlers::{auth::AuthSession, error::{ApiError, ApiErrorBody}},
+};
+pub use together_types::account::AccountDeletionResponse;
+
+impl From<AccountDeletion> for AccountDeletionResponse {
+    fn from(deletion: AccountDeletion) -> Self {
+        Self {
+            status: deletion.status,
+            requested_at: deletion.requested_at.to_rfc3339(),
+            scheduled_for: deletion.scheduled_for.to_rfc3339(),
+            cancelled_at: deletion.cancelled_at.map(|at| at.to_rfc3339()),
+        }
+    }
+}
+
+/// Ask for the account to be erased once the grace period ends
+///
+/// Until then the account keeps working and the request can be cancelled. Asking
+/// again while a request is pending returns that request unchanged.
+#[utoipa::path(
+    post,
+    path = "/api/v2/users/{user_id}/deletion",
+    tag = "account",
+    params(
+        ("user_id" = i32, Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "Deletion scheduled (or the already pending request)", body = AccountDeletionResponse),
+        (status = 404, description = "User not found", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid session token", body = ApiErrorBody),
+        (status = 403, description = "Session belongs to another user", body = ApiErrorBody),
+    ),
+    security(("session_token" = [])),
+)]
+pub async fn request_account_deletion(
+    State(AppState { pool, .. }): State<AppState>,
+    Path(user_id): Path<i32>,
+    session: AuthSession,
+) -> Result<Json<AccountDeletionResponse>, ApiError> {
+    session.require_user(user_id)?;
+
+    let user = users::get_user_by_id(&pool, user_id).await
+        .map_err(ApiError::internal("Failed to look up user"))?
+        .ok_or(ApiError::UserNotFound("User"))?;
+
+    let scheduled_for = Utc::now() + chrono::Duration::days(ACCOUNT_DELETION_GRACE_DAYS);
+    let deletion = account_deletions::request_deletion(&pool, user_id, &user.wallet_address, scheduled_for).await
+        .map_err(ApiError::internal("Failed to request account deletion"))?;
+
+    tracing::info!("User {} requested account deletion, scheduled for {}", user_id, deletion.scheduled_for);
+    Ok(Json(deletion.into()))
+}
+
+/// The user's latest account deletion request
+#[utoipa::path(
+    get,
+    path = "/api/v2/users/{user_id}/deletion",
+    tag = "account",
+    params(
+        ("user_id" = i32, Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "The latest deletion request", body = AccountDeletionResponse),
+        (status = 404, description = "No pending account deletion", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid session token", body = ApiErrorBody),
+        (status = 403, description = "Session belongs to another user", body = ApiErrorBody),
+    ),
+    security(("session_token" = [])),
+)]
+pub async fn get_account_deletion(
+    State(AppState { pool, .. }): State<AppState>,
+    Path(user_id): Path<i32>,
+    session: AuthSession,
+) -> Result<Json<AccountDeletionResponse>, ApiError> {
+    session.require_user(user_id)?;
+
+    let deletion = account_deletions::get_latest_deletion(&pool, user_id).await
+        .map_err(ApiError::internal("Failed to look up account deletion"))?
+        .ok_or(ApiError::NotFound("No pending account deletion"))?;
+
+    Ok(Json(deletion.into()))
+}
+
+/// Cancel a pending account deletion during its grace period
+#[utoipa::path(
+    delete,
+    path = "/api/v2/users/{user_id}/deletion",
+    tag = "account",
+    params(
+        ("user_id" = i32, Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "Deletion cancelled", body = AccountDeletionResponse),
+        (status = 404, description = "No pending account deletion", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid session token", body = ApiErrorBody),
+        (status = 403, description = "Session belongs to another user", body = ApiErrorBody),
+    ),
+    security(("session_token" = [])),
+)]
+pub async fn cancel_account_deletion(
+    State(AppState { pool, .. }): State<AppState>,
+    Path(user_id): Path<i32>,
+    session: AuthSession,
+) -> Result<Json<AccountDeletionResponse>, ApiError> {
+    session.require_user(user_id)?;
+
+    let deletion = account_deletions::cancel_deletion(&pool, user_id).await
+        .map_err(ApiError::internal("Failed to cancel account deletion"))?
+        .ok_or(ApiError::NotFound("No pending account deletion"))?;
+
+    tracing::info!("User {} cancelled their account deletion", user_id);
+    Ok(Json(deletion.into()))
+}
@@ -0,0 +1,321 @@
+use axum::{
+    extract::{FromRequestParts, OptionalFromRequestParts, Query, State},
+    http::{header::AUTHORIZATION, request::Parts, StatusCode},
+    response::Json,
+};
+use serde::Deserialize;
+use sqlx::PgPool;
+use alloy::primitives::Address;
+use chrono::Utc;
+use uuid::Uuid;
+use crate::{
+    AppState,
+    utils::auth::{self, SiweError, SiweMessage},
+    constants::*,
+    db::{account_deletions, sessions, users},
+    models::{User, UserSession},
+    handlers::error::{ApiError, ApiErrorBody},
+};
+pub use together_types::auth::{AuthNonceRequest, AuthNonceResponse, CreateSessionRequest, SessionResponse, StreamTicketResponse};
+
+/// An authenticated caller, resolved from `Authorization: Bearer <token>`
+#[derive(Debug, Clone)]
+pub struct AuthSession {
+    pub session_id: Uuid,
+    pub user_id: i32,
+    pub wallet_address: String,
+    token_hash: String,
+}
+
+impl AuthSession {
+    /// Reject the request unless the session belongs to `user_id`
+    pub fn require_user(&self, user_id: i32) -> Result<(), ApiError> {
+        if self.user_id != user_id {
+            return Err(ApiError::Forbidden("Session does not belong to this user"));
+        }
+        Ok(())
+    }
+}
+
+impl AuthSession {
+    fn token_from_parts(parts: &Parts) -> Option<String> {
+        parts.headers.get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(|token| token.trim().to_string())
+    }
+
+    /// The session for a request, if it carries a token; a bad token is an error
+    pub async fn from_parts(parts: &mut Parts, pool: &PgPool) -> Result<Option<Self>, ApiError> {
+        match Self::token_from_parts(parts) {
+            Some(token) => Self::resolve(parts, pool, &token).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Look the token up once per request: rate limit layers and the handler's extractor
+    /// all need the session, so the outcome is kept in the request's extensions
+    async fn resolve(parts: &mut Parts, pool: &PgPool, token: &str) -> Result<Self, ApiError> {
+        if let Some(ResolvedSession(resolved)) = parts.extensions.get::<ResolvedSession>() {
+            return resolved.clone().ok_or_else(unauthorized);
+        }
+
+        let resolved = sessions::get_session_user(pool, &auth::hash_session_token(token)).await
+            .map_err(ApiError::internal("Failed to validate session"))?
+            .map(|(session, user)| Self::from_row(session, user));
+        parts.extensions.insert(ResolvedSession(resolved.clone()));
+
+        resolved.ok_or_else(unauthorized)
+    }
+
+    fn from_row(session: UserSession, user: User) -> Self {
+        AuthSession {
+            session_id: session.id,
+            user_id: user.id,
+            wallet_address: user.wallet_address,
+            token_hash: session.token_hash,
+        }
+    }
+}
+
+// `None` when the request's token matched no live session
+#[derive(Debug, Clone)]
+struct ResolvedSession(Option<AuthSession>);
+
+fn unauthorized() -> ApiError {
+    ApiError::Unauthorized("Missing or invalid session token")
+}
+
+impl FromRequestParts<AppState> for AuthSession {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let token = Self::token_from_parts(parts).ok_or_else(unauthorized)?;
+        Self::resolve(parts, &state.pool, &token).await
+    }
+}
+
+// `Option<AuthSession>`: no token means anonymous, but a bad token is still rejected
+impl OptionalFromRequestParts<AppState> for AuthSession {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Option<Self>, Self::Rejection> {
+        Self::from_parts(parts, &state.pool).await
+    }
+}
+
+// EventSource can't set headers, so event streams also take a single-use ticket
+#[derive(Debug, Deserialize)]
+struct StreamTicketQuery {
+    ticket: Option<String>,
+}
+
+/// The caller of an event stream, resolved from `Authorization: Bearer <token>` or a
+/// `?ticket=` issued by [`create_stream_ticket`]
+#[derive(Debug, Clone)]
+pub struct StreamSession(pub AuthSession);
+
+impl FromRequestParts<AppState> for StreamSession {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        if let Some(token) = AuthSession::token_from_parts(parts) {
+            return AuthSession::resolve(parts, &state.pool, &token).await.map(StreamSession);
+        }
+
+        let ticket = Query::<StreamTicketQuery>::try_from_uri(&parts.uri)
+            .ok()
+            .and_then(|Query(q)| q.ticket)
+            .ok_or_else(unauthorized)?;
+        let (session, user) = sessions::consume_stream_ticket(&state.pool, &auth::hash_session_token(&ticket)).await
+            .map_err(ApiError::internal("Failed to validate stream ticket"))?
+            .ok_or(ApiError::Unauthorized("Stream ticket is unknown, expired or already used"))?;
+
+        Ok(StreamSession(AuthSession::from_row(session, user)))
+    }
+}
+
+/// Issue a sign-in nonce for the mini-app's wallet auth
+#[utoipa::path(
+    post,
+    path = "/api/v2/auth/nonces",
+    tag = "auth",
+    request_body = AuthNonceRequest,
+    responses(
+        (status = 200, description = "Nonce to embed in the SIWE message", body = AuthNonceResponse),
+        (status = 400, description = "Invalid wallet address", body = ApiErrorBody),
+        (status = 429, description = "Rate limited", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
+pub async fn create_auth_nonce(
+    State(AppState { pool, .. }): State<AppState>,
+    Json(req): Json<AuthNonceRequest>,
+) -> Result<Json<AuthNonceResponse>, ApiError> {
+    let _address: Address = req.wallet_address.parse()
+        .map_err(|_| ApiError::InvalidAddress("wallet address"))?;
+
+    let auth_nonce = sessions::create_auth_nonce(&pool, &auth::generate_auth_nonce(), &req.wallet_address).await
+        .map_err(ApiError::internal("Failed to create auth nonce"))?;
+
+    Ok(Json(AuthNonceResponse {
+        nonce: auth_nonce.nonce,
+        expires_at: auth_nonce.expires_at.to_rfc3339(),
+    }))
+}
+
+/// Exchange a signed SIWE message for a session token
+#[utoipa::path(
+    post,
+    path = "/api/v2/auth/sessions",
+    tag = "auth",
+    request_body = CreateSessionRequest,
+    responses(
+        (status = 200, description = "Session token for `Authorization: Bearer`", body = SessionResponse),
+        (status = 400, description = "Malformed message or signature", body = ApiErrorBody),
+        (status = 401, description = "Signature, nonce, domain, chain or message age rejected", body = ApiErrorBody),
+        (status = 410, description = "The wallet's account was deleted", body = ApiErrorBody),
+        (status = 429, description = "Rate limited", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
+pub async fn create_session(
+    State(AppState { pool, config, provider, .. }): State<AppState>,
+    Json(req): Json<CreateSessionRequest>,
+) -> Result<Json<SessionResponse>, ApiError> {
+    let message = SiweMessage::parse(&req.message)
+        .map_err(|e| ApiError::InvalidRequest(e.reason()))?;
+    message.validate(&config.siwe_domain, WORLDCHAIN_MAINNET_CHAIN_ID, Utc::now())
+        .map_err(|e| match e {
+            SiweError::Malformed(reason) => ApiError::InvalidRequest(reason),
+            e => ApiError::Unauthorized(e.reason()),
+        })?;
+    let message_address = message.address;
+    let address: Address = message_address.parse()
+        .map_err(|_| ApiError::InvalidAddress("wallet address"))?;
+    let nonce = message.nonce;
+
+    let valid = auth::verify_wallet_signature(&provider, address, &req.message, &req.signature).await
+        .map_err(|_| ApiError::InvalidRequest("Invalid signature format"))?;
+    if !valid {
+        return Err(ApiError::Unauthorized("Signature does not match wallet"));
+    }
+
+    // Consume only after the signature checks out so a bad attempt can't burn someone's nonce
+    let auth_nonce = sessions::consume_auth_nonce(&pool, nonce).await
+        .map_err(ApiError::internal("Failed to validate nonce"))?
+        .ok_or(ApiError::Unauthorized("Nonce is unknown, expired or already used"))?;
+
+    if !auth_nonce.wallet_address.eq_ignore_ascii_case(message_address) {
+        return Err(ApiError::Unauthorized("Nonce was issued to a different wallet"));
+    }
+
+    // Erased wallets stay erased rather than coming back as an account whose history is hidden
+    if account_deletions::is_address_erased(&pool, &auth_nonce.wallet_address).await
+        .map_err(ApiError::internal("Failed to check account status"))? {
+        return Err(ApiError::AccountErased);
+    }
+    let user = users::get_or_create_user(&pool, &auth_nonce.wallet_address).await
+        .map_err(ApiError::internal("Failed to get or create user"))?;
+
+    let token = auth::generate_session_token();
+    let expires_at = Utc::now() + chrono::Duration::hours(SESSION_TTL_HOURS);
+    sessions::create_session(&pool, user.id, &auth::hash_session_token(&token), expires_at).await
+        .map_err(ApiError::internal("Failed to create session"))?;
+
+    tracing::info!("Created session for user {}", user.id);
+
+    Ok(Json(SessionResponse {
+        token,
+        user_id: user.id,
+        wallet_address: user.wallet_address,
+        expires_at: expires_at.to_rfc3339(),
+    }))
+}
+
+/// Issue a short-lived, single-use ticket for opening an event stream
+///
+/// Pass it as `?ticket=` from clients like `EventSource` that can't set an
+/// `Authorization` header, so the session token never appears in a URL.
+#[utoipa::path(
+    post,
+    path = "/api/v2/auth/stream-tickets",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Ticket for `?ticket=` on an event stream", body = StreamTicketResponse),
+        (status = 401, description = "Missing or invalid session token", body = ApiErrorBody),
+        (status = 429, description = "Rate limited", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+    security(("session_token" = [])),
+)]
+pub async fn create_stream_ticket(
+    State(AppState { pool, .. }): State<AppState>,
+    session: AuthSession,
+) -> Result<Json<StreamTicketResponse>, ApiError> {
+    let ticket = auth::generate_session_token();
+    let expires_at = Utc::now() + chrono::Duration::seconds(STREAM_TICKET_TTL_SECS);
+    sessions::create_stream_ticket(&pool, session.session_id, &auth::hash_session_token(&ticket), expires_at).await
+        .map_err(ApiError::internal("Failed to create stream ticket"))?;
+
+    Ok(Json(StreamTicketResponse {
+        ticket,
+        expires_at: expires_at.to_rfc3339(),
+    }))
+}
+
+/// Revoke the caller's session
+#[utoipa::path(
+    delete,
+    path = "/api/v2/auth/sessions/current",
+    tag = "auth",
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 401, description = "Missing or invalid session token", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
+pub async fn delete_session(
+    State(AppState { pool, .. }): State<AppState>,
+    session: AuthSession,
+) -> Result<StatusCode, ApiError> {
+    sessions::delete_session(&pool, &session.token_hash).await
+        .map_err(ApiError::internal("Failed to delete session"))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+    use crate::db::testing::{random_wallet, test_pool};
+
+    fn parts_with_token(token: &str) -> Parts {
+        let request = Request::builder().header(AUTHORIZATION, format!("Bearer {}", token)).body(()).unwrap();
+        request.into_parts().0
+    }
+
+    #[tokio::test]
+    async fn test_session_is_looked_up_once_per_request() {
+        let Some(pool) = test_pool().await else { return };
+        let user = users::create_user(&pool, &random_wallet()).await.unwrap();
+        let token = auth::generate_session_token();
+        let token_hash = auth::hash_session_token(&token);
+        sessions::create_session(&pool, user.id, &token_hash, Utc::now() + chrono::Duration::hours(1)).await.unwrap();
+
+        let mut parts = parts_with_token(&token);
+        let first = AuthSession::from_parts(&mut parts, &pool).await.unwrap().unwrap();
+        assert_eq!(first.user_id, user.id);
+
+        // Later layers and the handler reuse the first lookup rather than querying again
+        sessions::delete_session(&pool, &token_hash).await.unwrap();
+        let again = AuthSession::from_parts(&mut parts, &pool).await.unwrap().unwrap();
+        assert_eq!(again.session_id, first.session_id);
+
+        // A new request looks it up afresh, and a miss is remembered for the rest of it too
+        let mut fresh = parts_with_token(&token);
+        assert!(AuthSession::from_parts(&mut fresh, &pool).await.is_err());
+        assert!(AuthSession::from_parts(&mut fresh, &pool).await.is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use axum::{
+    extract::{State, Path, Query},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use alloy::primitives::{Address, keccak256};
+use chrono::{Datelike, TimeZone, Utc};
+use crate::{
+    AppState,
+    constants::*,
+    db::{account_deletions, attestations},
+    render,
+    handlers::error::{ApiError, ApiErrorBody},
+};
+pub use together_types::badge::{BadgeMetric, BadgeQuery};
+
+/// Shields-style "together · N" badge for embedding
+///
+/// `metric` is `total` (attestations, from `together_counts`), `partners` (distinct
+/// wallets) or `month` (attestations this calendar month, UTC).
+#[utoipa::path(
+    get,
+    path = "/api/v2/profiles/{address}/badge.svg",
+    tag = "embeds",
+    params(
+        ("address" = String, Path, description = "Wallet address"),
+        BadgeQuery,
+    ),
+    responses(
+        (status = 200, description = "Count badge", content_type = "image/svg+xml", body = String),
+        (status = 304, description = "Unchanged since `If-None-Match`"),
+        (status = 400, description = "Invalid wallet address", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
+pub async fn get_badge(
+    State(AppState { pool, .. }): State<AppState>,
+    Path(address): Path<String>,
+    Query(query): Query<BadgeQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let _: Address = address.parse()
+        .map_err(|_| ApiError::InvalidAddress("wallet address"))?;
+
+    // Erased accounts' attestations are hidden, so their badges read zero
+    let erased = account_deletions::is_address_erased(&pool, &address).await
+        .map_err(ApiError::internal("Failed to render badge"))?;
+    let count = match query.metric {
+        _ if erased => Ok(0),
+        BadgeMetric::Total => attestations::get_together_count(&pool, &address).await,
+        BadgeMetric::Partners => attestations::count_distinct_partners(&pool, &address).await,
+        BadgeMetric::Month => {
+            let now = Utc::now();
+            let month_start = Utc.with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0).single().unwrap_or(now);
+            attestations::count_attestations_since(&pool, &address, month_start.timestamp()).await
+        }
+    }
+    .map_err(ApiError::internal("Failed to render badge"))?;
+
+    let label: String = query.label.as_deref()
+        .filter(|label| !label.trim().is_empty())
+        .unwrap_or(BADGE_DEFAULT_LABEL)
+        .chars()
+        .take(BADGE_MAX_LABEL_CHARS)
+        .collect();
+    let svg = render::badge_svg(&label, &count.to_string(), query.style);
+
+    let etag = format!("\"{}\"", hex::encode(&keccak256(svg.as_bytes())[..16]));
+    let cache_control = format!(
+        "public, max-age={}, stale-while-revalidate={}",
+        BADGE_MAX_AGE_SECS, BADGE_STALE_WHILE_REVALIDATE_SECS
+    );
+    let not_modified = headers.get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag));
+    if not_modified {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, etag), (header::CACHE_CONTROL, cache_control)],
+        ).into_response());
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/svg+xml".to_string()),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, cache_control),
+        ],
+        svg,
+    ).into_response())
+}
@@ -0,0 +1,87 @@
+use axum::{extract::{State, Path, Query}, response::Json};
+use alloy::primitives::Address;
+use chrono::Utc;
+use crate::{
+    AppState,
+    constants::{PROOF_EXPORT_DEFAULT_LIMIT, PROOF_EXPORT_MAX_LIMIT},
+    db::attestations,
+    proof::{self, VerifiableCredential},
+    handlers::error::{ApiError, ApiErrorBody},
+};
+pub use together_types::credentials::CredentialsQuery;
+
+/// The requested page, bounded so one request can't make the server sign every attestation
+pub fn export_page(query: &CredentialsQuery) -> (i64, i64) {
+    let offset = query.offset.unwrap_or(0).max(0);
+    let limit = query.limit.unwrap_or(PROOF_EXPORT_DEFAULT_LIMIT).clamp(1, PROOF_EXPORT_MAX_LIMIT);
+    (offset, limit)
+}
+
+/// Export an address's on-chain attestations as signed W3C Verifiable Credentials
+///
+/// Attestations that haven't been seen on chain yet are left out.
+#[utoipa::path(
+    get,
+    path = "/api/v2/profiles/{address}/credentials",
+    tag = "proofs",
+    params(
+        ("address" = String, Path, description = "Wallet address"),
+        CredentialsQuery,
+    ),
+    responses(
+        (status = 200, description = "Signed verifiable credentials", body = Vec<VerifiableCredential>),
+        (status = 400, description = "Invalid wallet address", body = ApiErrorBody),
+        (status = 429, description = "Rate limited", body = ApiErrorBody),
+        (status = 500, description = "Signing failed", body = ApiErrorBody),
+        (status = 503, description = "No signer available", body = ApiErrorBody),
+    ),
+)]
+pub async fn get_credentials(
+    State(AppState { pool, config, signers, .. }): State<AppState>,
+    Path(address): Path<String>,
+    Query(query): Query<CredentialsQuery>,
+) -> Result<Json<Vec<VerifiableCredential>>, ApiError> {
+    let _: Address = address.parse().map_err(|_| ApiError::InvalidAddress("address"))?;
+    let contract_address: Address = config.together_contract_address.parse()
+        .map_err(ApiError::internal("Invalid contract address in config"))?;
+
+    let (offset, limit) = export_page(&query);
+    let rows = attestations::get_attestations_for_address(&pool, &address, Some(offset), Some(limit)).await
+        .map_err(ApiError::internal("Failed to export credentials"))?;
+
+    let signer = signers.pick(&pool).await
+        .map_err(|e| {
+            tracing::error!("Failed to pick attestation signer: {}", e);
+            ApiError::SignerUnavailable
+        })?;
+
+    let issued_at = Utc::now();
+    let mut credentials = Vec::with_capacity(rows.len());
+    for attestation in &rows {
+        let Some(credential) = proof::credential_from_attestation(attestation, signer.address(), contract_address, issued_at)
+            .map_err(ApiError::internal("Failed to export credentials"))?
+        else {
+            continue;
+        };
+        credentials.push(
+            proof::sign_credential(credential, signer.as_ref()).await
+                .map_err(ApiError::internal("Failed to sign credential"))?
+        );
+    }
+
+    Ok(Json(credentials))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_is_bounded() {
+        assert_eq!(export_page(&CredentialsQuery::default()), (0, PROOF_EXPORT_DEFAULT_LIMIT));
+        let huge = CredentialsQuery { offset: Some(-5), limit: Some(1_000_000) };
+        assert_eq!(export_page(&huge), (0, PROOF_EXPORT_MAX_LIMIT));
+        let empty = CredentialsQuery { offset: Some(10), limit: Some(0) };
+        assert_eq!(export_page(&empty), (10, 1));
+    }
+}
@@ -0,0 +1,63 @@
+use axum::{extract::{State, Path, Query}, response::Json};
+use alloy::primitives::Address;
+use crate::{
+    AppState,
+    db::attestations,
+    proof::{self, OffchainAttestation},
+    handlers::{credentials::{self, CredentialsQuery}, error::{ApiError, ApiErrorBody}},
+};
+
+/// Export an address's on-chain attestations as signed offchain EAS attestations
+///
+/// The address is the recipient of each one; rows not yet seen on chain are left out.
+#[utoipa::path(
+    get,
+    path = "/api/v2/profiles/{address}/eas-attestations",
+    tag = "proofs",
+    params(
+        ("address" = String, Path, description = "Wallet address"),
+        CredentialsQuery,
+    ),
+    responses(
+        (status = 200, description = "Signed offchain EAS attestations", body = Vec<OffchainAttestation>),
+        (status = 400, description = "Invalid wallet address", body = ApiErrorBody),
+        (status = 429, description = "Rate limited", body = ApiErrorBody),
+        (status = 500, description = "Signing failed", body = ApiErrorBody),
+        (status = 503, description = "No signer available", body = ApiErrorBody),
+    ),
+)]
+pub async fn get_eas_attestations(
+    State(AppState { pool, contract, signers, .. }): State<AppState>,
+    Path(address): Path<String>,
+    Query(query): Query<CredentialsQuery>,
+) -> Result<Json<Vec<OffchainAttestation>>, ApiError> {
+    let recipient: Address = address.parse().map_err(|_| ApiError::InvalidAddress("address"))?;
+
+    let (offset, limit) = credentials::export_page(&query);
+    let rows = attestations::get_attestations_for_address(&pool, &address, Some(offset), Some(limit)).await
+        .map_err(ApiError::internal("Failed to export EAS attestations"))?;
+
+    let eas_version = contract.get_eas_version().await
+        .map_err(ApiError::internal("Failed to look up the EAS version"))?;
+
+    let signer = signers.pick(&pool).await
+        .map_err(|e| {
+            tracing::error!("Failed to pick attestation signer: {}", e);
+            ApiError::SignerUnavailable
+        })?;
+
+    let mut exported = Vec::with_capacity(rows.len());
+    for attestation in &rows {
+        let Some(message) = proof::attest_message(attestation, recipient)
+            .map_err(ApiError::internal("Failed to export EAS attestations"))?
+        else {
+            continue;
+        };
+        exported.push(
+            proof::sign_offchain_attestation(message, &eas_version, signer.as_ref()).await
+                .map_err(ApiError::internal("Failed to sign EAS attestation"))?
+        );
+    }
+
+    Ok(Json(exported))
+}
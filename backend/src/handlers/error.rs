@@ -0,0 +1,276 @@
+//! API errors with stable, machine-readable codes
+//!
+//! An [`ApiError`]'s `Display` is the message the client sees, so it never carries
+//! internal details: unexpected failures are logged under the request ID and answered
+//! with their static context only.
+
+use axum::{
+    extract::Request,
+    http::{header::RETRY_AFTER, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use uuid::Uuid;
+use crate::constants::{MAX_REQUEST_ID_LENGTH, REQUEST_ID_HEADER};
+pub use together_types::error::{ErrorCode, ApiErrorBody};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("Invalid {0} format")]
+    InvalidAddress(&'static str),
+    #[error("{0}")]
+    InvalidRequest(&'static str),
+    #[error("Invalid pairing code format")]
+    InvalidPairingCode,
+    #[error("{0}")]
+    Unauthorized(&'static str),
+    #[error("{0}")]
+    Forbidden(&'static str),
+    #[error("Proximity proof required before attesting")]
+    ProximityProofRequired,
+    #[error("{0} not found")]
+    UserNotFound(&'static str),
+    #[error("Pairing code is invalid or expired")]
+    PairingCodeNotFound,
+    #[error("{0} pending connection not found")]
+    PendingConnectionNotFound(&'static str),
+    #[error("{0}")]
+    NotFound(&'static str),
+    /// Two users' codes collided this window; the next rotation will differ
+    #[error("Pairing code is ambiguous, wait for the next code")]
+    PairingCodeAmbiguous,
+    #[error("Pending connection already exists")]
+    PendingConnectionExists,
+    #[error("Resource already exists")]
+    AlreadyExists,
+    /// The wallet's account was deleted; it can't sign up again
+    #[error("This wallet's account was deleted and can't be used again")]
+    AccountErased,
+    #[error("Cannot create connection with yourself")]
+    SelfConnection,
+    #[error("Too many pending connections between these users")]
+    PendingLimitReached,
+    #[error("Too many unprocessed connections between these users")]
+    OptimisticLimitReached,
+    #[error("This user declined a recent request, try again after {}", .0.to_rfc3339())]
+    DeclineCooldown(DateTime<Utc>),
+    #[error("{0}")]
+    SignatureQuotaExceeded(String),
+    /// Also sent as `Retry-After`
+    #[error("Rate limit exceeded, try again in {}s", retry_after_secs(.0))]
+    RateLimited(Duration),
+    #[error("No attestation signer available")]
+    SignerUnavailable,
+    #[error("{context}")]
+    Internal {
+        context: &'static str,
+        #[source]
+        source: anyhow::Error,
+    },
+}
+
+impl ApiError {
+    /// Map an unexpected failure to a 500 that only exposes `context`
+    ///
+    /// Database errors the schema raises on purpose (the connection limit triggers,
+    /// CHECK and unique constraints) become their client-facing variant instead.
+    pub fn internal<E: Into<anyhow::Error>>(context: &'static str) -> impl FnOnce(E) -> Self {
+        move |e| {
+            let source = e.into();
+            Self::from_database(&source).unwrap_or(Self::Internal { context, source })
+        }
+    }
+
+    fn from_database(error: &anyhow::Error) -> Option<Self> {
+        let db_error = error.chain()
+            .find_map(|cause| cause.downcast_ref::<sqlx::Error>())?
+            .as_database_error()?;
+        match db_error.constraint() {
+            Some("pending_connection_limit") => Some(Self::PendingLimitReached),
+            Some("optimistic_connection_limit") => Some(Self::OptimisticLimitReached),
+            Some("chk_no_self_connection" | "chk_no_self_optimistic_connection") => Some(Self::SelfConnection),
+            _ if db_error.is_unique_violation() => Some(Self::AlreadyExists),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidAddress(_) => ErrorCode::InvalidAddress,
+            Self::InvalidRequest(_) => ErrorCode::InvalidRequest,
+            Self::InvalidPairingCode => ErrorCode::InvalidPairingCode,
+            Self::Unauthorized(_) => ErrorCode::Unauthorized,
+            Self::Forbidden(_) => ErrorCode::Forbidden,
+            Self::ProximityProofRequired => ErrorCode::ProximityProofRequired,
+            Self::UserNotFound(_) => ErrorCode::UserNotFound,
+            Self::PairingCodeNotFound => ErrorCode::PairingCodeNotFound,
+            Self::PendingConnectionNotFound(_) => ErrorCode::PendingConnectionNotFound,
+            Self::NotFound(_) => ErrorCode::NotFound,
+            Self::PairingCodeAmbiguous => ErrorCode::PairingCodeAmbiguous,
+            Self::PendingConnectionExists => ErrorCode::PendingConnectionExists,
+            Self::AlreadyExists => ErrorCode::AlreadyExists,
+            Self::AccountErased => ErrorCode::AccountErased,
+            Self::SelfConnection => ErrorCode::SelfConnection,
+            Self::PendingLimitReached => ErrorCode::PendingLimitReached,
+            Self::OptimisticLimitReached => ErrorCode::OptimisticLimitReached,
+            Self::DeclineCooldown(_) => ErrorCode::DeclineCooldown,
+            Self::SignatureQuotaExceeded(_) => ErrorCode::SignatureQuotaExceeded,
+            Self::RateLimited(_) => ErrorCode::RateLimited,
+            Self::SignerUnavailable => ErrorCode::SignerUnavailable,
+            Self::Internal { .. } => ErrorCode::Internal,
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidAddress(_)
+            | Self::InvalidRequest(_)
+            | Self::InvalidPairingCode
+            | Self::SelfConnection => StatusCode::BAD_REQUEST,
+            Self::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Self::Forbidden(_) | Self::ProximityProofRequired => StatusCode::FORBIDDEN,
+            Self::UserNotFound(_)
+            | Self::PairingCodeNotFound
+            | Self::PendingConnectionNotFound(_)
+            | Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::PairingCodeAmbiguous
+            | Self::PendingConnectionExists
+            | Self::AlreadyExists => StatusCode::CONFLICT,
+            Self::AccountErased => StatusCode::GONE,
+            Self::PendingLimitReached
+            | Self::OptimisticLimitReached
+            | Self::DeclineCooldown(_)
+            | Self::SignatureQuotaExceeded(_)
+            | Self::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::SignerUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Internal { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    // The only place an internal error's cause is recorded
+    fn log(&self, request_id: Option<&str>) {
+        if let Self::Internal { context, source } = self {
+            tracing::error!("{}: {:#} (request {})", context, source, request_id.unwrap_or("-"));
+        }
+    }
+
+    fn body(&self) -> ApiErrorBody {
+        let request_id = current_request_id();
+        self.log(request_id.as_deref());
+        ApiErrorBody {
+            error: self.to_string(),
+            code: self.code(),
+            request_id,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let mut response = (self.status(), Json(self.body())).into_response();
+        if let Self::RateLimited(retry_after) = self {
+            response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(retry_after_secs(&retry_after)));
+        }
+        response
+    }
+}
+
+// Whole seconds, rounded up so a client that waits exactly this long gets through
+fn retry_after_secs(retry_after: &Duration) -> u64 {
+    retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)
+}
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// ID of the request being handled, if called from inside [`assign_request_id`]
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Middleware: adopt the caller's `x-request-id` (or mint one), expose it to handlers and echo it back
+pub async fn assign_request_id(request: Request, next: Next) -> Response {
+    let request_id = request.headers().get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_valid_request_id(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let mut response = REQUEST_ID.scope(request_id.clone(), next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+// Caller-supplied IDs end up in logs, so keep them short and plain
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LENGTH
+        && id.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn body_of(error: ApiError) -> (StatusCode, ApiErrorBody) {
+        let response = error.into_response();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_internal_errors_hide_their_cause() {
+        let error = ApiError::internal("Failed to create pending connection")(anyhow::anyhow!("connection refused at 10.0.0.3:5432"));
+        let (status, body) = REQUEST_ID.scope("req-1".to_string(), body_of(error)).await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body.error, "Failed to create pending connection");
+        assert_eq!(body.code, ErrorCode::Internal);
+        assert_eq!(body.request_id.as_deref(), Some("req-1"));
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_keep_code_and_status() {
+        let (status, body) = body_of(ApiError::InvalidAddress("wallet address")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body.error, "Invalid wallet address format");
+        assert_eq!(body.request_id, None);
+
+        let json = serde_json::to_value(ApiErrorBody {
+            error: String::new(),
+            code: ErrorCode::PendingLimitReached,
+            request_id: None,
+        }).unwrap();
+        assert_eq!(json["code"], "PENDING_LIMIT_REACHED");
+        assert!(json.get("request_id").is_none());
+
+        assert_eq!(ApiError::PendingLimitReached.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(ApiError::PendingConnectionExists.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_sends_retry_after() {
+        let response = ApiError::RateLimited(Duration::from_millis(1500)).into_response();
+        assert_eq!(response.headers()[RETRY_AFTER], "2");
+
+        let (status, body) = body_of(ApiError::RateLimited(Duration::from_secs(3))).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body.code, ErrorCode::RateLimited);
+        assert_eq!(body.error, "Rate limit exceeded, try again in 3s");
+    }
+
+    #[test]
+    fn test_is_valid_request_id() {
+        assert!(is_valid_request_id("67e55044-10b1-426f-9247-bb680e5fe0c8"));
+        assert!(is_valid_request_id("edge_1.abc"));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("has space"));
+        assert!(!is_valid_request_id(&"a".repeat(MAX_REQUEST_ID_LENGTH + 1)));
+    }
+}
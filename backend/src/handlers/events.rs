@@ -0,0 +1,61 @@
+use axum::{
+    extract::{Path, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use std::convert::Infallible;
+use tokio::time::Duration;
+use tokio_stream::{wrappers::{errors::BroadcastStreamRecvError, BroadcastStream}, Stream, StreamExt};
+use crate::{
+    AppState,
+    constants::SSE_KEEPALIVE_INTERVAL_SECS,
+    handlers::{auth::StreamSession, error::{ApiError, ApiErrorBody}},
+    services::events,
+};
+
+/// Stream pairing lifecycle events for a user as Server-Sent Events
+///
+/// Event names are `pending_created`, `cancelled`, `declined`, `matched`, `tx_sent`,
+/// `confirmed` and `failed`; each payload is the JSON-serialized `PairingEvent`. A
+/// `lagged` event tells the client it missed events and should refetch
+/// pending/optimistic connections.
+#[utoipa::path(
+    get,
+    path = "/api/v2/users/{user_id}/events",
+    tag = "pairing",
+    params(
+        ("user_id" = i32, Path, description = "User id"),
+        ("ticket" = Option<String>, Query, description = "Single-use ticket from `POST /api/v2/auth/stream-tickets`, for EventSource clients that can't set headers"),
+    ),
+    responses(
+        (status = 200, description = "Server-Sent Events; each `data` is a JSON `PairingEvent`", content_type = "text/event-stream", body = events::PairingEvent),
+        (status = 401, description = "Missing or invalid session token or stream ticket", body = ApiErrorBody),
+        (status = 403, description = "Session belongs to another user", body = ApiErrorBody),
+    ),
+    security(("session_token" = [])),
+)]
+pub async fn stream_user_events(
+    State(_state): State<AppState>,
+    Path(user_id): Path<i32>,
+    StreamSession(session): StreamSession,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    session.require_user(user_id)?;
+
+    let stream = BroadcastStream::new(events::subscribe()).filter_map(move |message| match message {
+        Ok(event) if event.involves(user_id) => {
+            match Event::default().event(event.name()).json_data(&event) {
+                Ok(sse_event) => Some(Ok(sse_event)),
+                Err(e) => {
+                    tracing::warn!("Failed to serialize pairing event: {}", e);
+                    None
+                }
+            }
+        }
+        Ok(_) => None,
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+            tracing::warn!("Event stream for user {} lagged by {} events", user_id, skipped);
+            Some(Ok(Event::default().event("lagged").data(skipped.to_string())))
+        }
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(Duration::from_secs(SSE_KEEPALIVE_INTERVAL_SECS))))
+}
@@ -0,0 +1,102 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::{State, Path},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use std::io::{self, Write};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use crate::{
+    AppState,
+    constants::*,
+    db::users,
+    handlers::{auth::AuthSession, error::{ApiError, ApiErrorBody}},
+    services::export,
+};
+
+/// Blocking writer that hands the archive to the response body in chunks
+struct ChannelWriter {
+    sender: mpsc::Sender<io::Result<Bytes>>,
+    buffer: Vec<u8>,
+}
+
+impl ChannelWriter {
+    fn send_buffer(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = Bytes::from(std::mem::replace(&mut self.buffer, Vec::with_capacity(EXPORT_CHUNK_SIZE)));
+        self.sender.blocking_send(Ok(chunk))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "export download was closed"))
+    }
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(data);
+        if self.buffer.len() >= EXPORT_CHUNK_SIZE {
+            self.send_buffer()?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send_buffer()
+    }
+}
+
+/// Download everything held about the signed-in user as a ZIP of JSON and CSV files
+///
+/// The archive is written while it's sent, so it's never held in memory whole. Its
+/// layout matches the `export_user_data` CLI.
+#[utoipa::path(
+    get,
+    path = "/api/v2/users/{user_id}/export",
+    tag = "account",
+    params(
+        ("user_id" = i32, Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "ZIP archive of everything held about the user", content_type = "application/zip", body = Vec<u8>),
+        (status = 404, description = "User not found", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid session token", body = ApiErrorBody),
+        (status = 403, description = "Session belongs to another user", body = ApiErrorBody),
+    ),
+    security(("session_token" = [])),
+)]
+pub async fn export_user_data(
+    State(AppState { pool, .. }): State<AppState>,
+    Path(user_id): Path<i32>,
+    session: AuthSession,
+) -> Result<Response, ApiError> {
+    session.require_user(user_id)?;
+
+    let user = users::get_user_by_id(&pool, user_id).await
+        .map_err(ApiError::internal("Failed to export user data"))?
+        .ok_or(ApiError::UserNotFound("User"))?;
+    let data = export::collect_user_data(&pool, &user.wallet_address).await
+        .map_err(ApiError::internal("Failed to export user data"))?;
+
+    let (sender, receiver) = mpsc::channel(EXPORT_CHANNEL_CAPACITY);
+    tokio::task::spawn_blocking(move || {
+        let writer = ChannelWriter { sender: sender.clone(), buffer: Vec::with_capacity(EXPORT_CHUNK_SIZE) };
+        let result = export::write_archive(&data, writer).and_then(|mut writer| Ok(writer.flush()?));
+        if let Err(e) = result {
+            tracing::warn!("Data export for user {} stopped: {}", user_id, e);
+            // Fails the body stream so the client sees a broken download, not a short ZIP
+            let _ = sender.blocking_send(Err(io::Error::other(e.to_string())));
+        }
+    });
+
+    let filename = format!("together-export-{}.zip", user.wallet_address.to_lowercase());
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+        ],
+        Body::from_stream(ReceiverStream::new(receiver)),
+    ).into_response())
+}
@@ -0,0 +1,107 @@
+use axum::{extract::{State, Path, Query}, response::Json};
+use alloy::primitives::Address;
+use crate::{
+    AppState,
+    db::{account_deletions, merkle as merkle_db},
+    models::MerkleSnapshot,
+    proof::leaf_hash,
+    services::merkle,
+    handlers::error::{ApiError, ApiErrorBody},
+};
+pub use together_types::merkle::{MerkleProofQuery, MerkleSnapshotResponse, MerkleProofResponse};
+
+impl From<MerkleSnapshot> for MerkleSnapshotResponse {
+    fn from(snapshot: MerkleSnapshot) -> Self {
+        Self {
+            snapshot_id: snapshot.id,
+            root: snapshot.root,
+            leaf_count: snapshot.leaf_count,
+            block_height: snapshot.block_height,
+            created_at: snapshot.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// The most recently published Merkle root
+#[utoipa::path(
+    get,
+    path = "/api/v2/merkle/snapshots/latest",
+    tag = "proofs",
+    responses(
+        (status = 200, description = "Most recent Merkle snapshot", body = MerkleSnapshotResponse),
+        (status = 404, description = "No snapshot taken yet", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
+pub async fn get_latest_merkle_snapshot(
+    State(AppState { pool, .. }): State<AppState>,
+) -> Result<Json<MerkleSnapshotResponse>, ApiError> {
+    let snapshot = merkle_db::get_latest_snapshot(&pool).await
+        .map_err(ApiError::internal("Failed to load Merkle snapshot"))?
+        .ok_or(ApiError::NotFound("Snapshot not found"))?;
+
+    Ok(Json(snapshot.into()))
+}
+
+/// Inclusion proof for one attestation in a snapshot (the latest unless `?snapshot_id=` is given)
+#[utoipa::path(
+    get,
+    path = "/api/v2/merkle/proofs/{address_a}/{address_b}/{timestamp}",
+    tag = "proofs",
+    params(
+        ("address_a" = String, Path, description = "First wallet address"),
+        ("address_b" = String, Path, description = "Second wallet address"),
+        ("timestamp" = i64, Path, description = "Attestation timestamp"),
+        MerkleProofQuery,
+    ),
+    responses(
+        (status = 200, description = "Inclusion proof for the attestation", body = MerkleProofResponse),
+        (status = 400, description = "Invalid wallet address", body = ApiErrorBody),
+        (status = 404, description = "Snapshot or attestation not found", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
+pub async fn get_merkle_proof(
+    State(AppState { pool, .. }): State<AppState>,
+    Path((address_a, address_b, timestamp)): Path<(String, String, i64)>,
+    Query(query): Query<MerkleProofQuery>,
+) -> Result<Json<MerkleProofResponse>, ApiError> {
+    let parse = |address: &str| address.parse::<Address>().map_err(|_| ApiError::InvalidAddress("address"));
+    let (a, b) = (parse(&address_a)?, parse(&address_b)?);
+
+    // Erased accounts' attestations stay in published trees, but their proofs aren't served
+    if account_deletions::any_address_erased(&pool, &[&address_a, &address_b]).await
+        .map_err(ApiError::internal("Failed to load Merkle proof"))? {
+        return Err(ApiError::NotFound("Attestation is not included in this snapshot"));
+    }
+
+    let snapshot = match query.snapshot_id {
+        Some(id) => merkle_db::get_snapshot(&pool, id).await,
+        None => merkle_db::get_latest_snapshot(&pool).await,
+    }
+    .map_err(ApiError::internal("Failed to load Merkle snapshot"))?
+    .ok_or(ApiError::NotFound("Snapshot not found"))?;
+
+    let leaf = leaf_hash(a, b, timestamp as u64);
+    let Some(proof) = merkle::get_proof(&pool, &snapshot, leaf).await
+        .map_err(ApiError::internal("Failed to load Merkle proof"))?
+    else {
+        let stored = merkle_db::has_snapshot_nodes(&pool, snapshot.id).await
+            .map_err(ApiError::internal("Failed to load Merkle proof"))?;
+        return Err(ApiError::NotFound(if stored {
+            "Attestation is not included in this snapshot"
+        } else {
+            "Snapshot predates stored proofs, use a later snapshot"
+        }));
+    };
+
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    Ok(Json(MerkleProofResponse {
+        snapshot: snapshot.into(),
+        address_1: low.to_string(),
+        address_2: high.to_string(),
+        timestamp,
+        leaf: leaf.to_string(),
+        proof: proof.iter().map(|node| node.to_string()).collect(),
+    }))
+}
@@ -1,5 +1,34 @@
 pub mod together;
 pub mod rpc;
+pub mod auth;
+pub mod events;
+pub mod proximity;
+pub mod signatures;
+pub mod proof;
+pub mod merkle;
+pub mod credentials;
+pub mod eas;
+pub mod pairs;
+pub mod profile_card;
+pub mod badge;
+pub mod export;
+pub mod account;
+pub mod error;
+pub mod v1;
 
 pub use together::*;
 pub use rpc::*;
+pub use auth::*;
+pub use events::*;
+pub use proximity::*;
+pub use signatures::*;
+pub use proof::*;
+pub use merkle::*;
+pub use credentials::*;
+pub use eas::*;
+pub use pairs::*;
+pub use profile_card::*;
+pub use badge::*;
+pub use export::*;
+pub use account::*;
+pub use error::*;
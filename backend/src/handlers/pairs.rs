@@ -0,0 +1,108 @@
+use axum::{
+    extract::{State, Path},
+    http::header,
+    response::{IntoResponse, Json},
+};
+use sqlx::PgPool;
+use alloy::primitives::U256;
+use std::str::FromStr;
+use crate::{
+    AppState,
+    models::PairCard,
+    render,
+    services::pairs,
+    handlers::error::{ApiError, ApiErrorBody},
+};
+pub use together_types::pairs::{PairMetadata, MetadataAttribute};
+
+fn attribute(trait_type: &str, display_type: Option<&str>, value: impl Into<serde_json::Value>) -> MetadataAttribute {
+    MetadataAttribute {
+        trait_type: trait_type.to_string(),
+        display_type: display_type.map(str::to_string),
+        value: value.into(),
+    }
+}
+
+/// Look up a pair by token id, given in decimal or 0x-prefixed hex
+async fn load_pair_card(pool: &PgPool, token_id: &str) -> Result<PairCard, ApiError> {
+    let token_id = U256::from_str(token_id.trim())
+        .map_err(|_| ApiError::InvalidRequest("Invalid token id"))?;
+
+    pairs::get_pair_card(pool, &token_id.to_string()).await
+        .map_err(ApiError::internal("Failed to load pair"))?
+        .ok_or(ApiError::NotFound("No pair with this token id"))
+}
+
+/// ERC-721 metadata for a pair's soulbound collectible
+#[utoipa::path(
+    get,
+    path = "/api/v2/pairs/{token_id}",
+    tag = "collectibles",
+    params(
+        ("token_id" = String, Path, description = "Pair token id, decimal or 0x-prefixed hex"),
+    ),
+    responses(
+        (status = 200, description = "ERC-721 metadata", body = PairMetadata),
+        (status = 400, description = "Invalid token id", body = ApiErrorBody),
+        (status = 404, description = "Unknown pair", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
+pub async fn get_pair_metadata(
+    State(AppState { pool, config, .. }): State<AppState>,
+    Path(token_id): Path<String>,
+) -> Result<Json<PairMetadata>, ApiError> {
+    let card = load_pair_card(&pool, &token_id).await?;
+    let name_1 = render::display_name(card.username_1.as_deref(), &card.address_1);
+    let name_2 = render::display_name(card.username_2.as_deref(), &card.address_2);
+
+    Ok(Json(PairMetadata {
+        name: format!("{} & {}", name_1, name_2),
+        description: format!(
+            "{} and {} have been together {} time{}, first on {}.",
+            name_1,
+            name_2,
+            card.strength,
+            if card.strength == 1 { "" } else { "s" },
+            render::format_date(card.first_met),
+        ),
+        image: format!("{}/api/v2/pairs/{}/image.svg", config.public_base_url, card.token_id),
+        attributes: vec![
+            attribute("First met", Some("date"), card.first_met),
+            attribute("Last met", Some("date"), card.last_met),
+            attribute("Strength", Some("number"), card.strength),
+            attribute("Wallet 1", None, card.address_1.clone()),
+            attribute("Wallet 2", None, card.address_2.clone()),
+        ],
+    }))
+}
+
+/// Generated SVG image for a pair's collectible
+#[utoipa::path(
+    get,
+    path = "/api/v2/pairs/{token_id}/image.svg",
+    tag = "collectibles",
+    params(
+        ("token_id" = String, Path, description = "Pair token id, decimal or 0x-prefixed hex"),
+    ),
+    responses(
+        (status = 200, description = "Generated pair image", content_type = "image/svg+xml", body = String),
+        (status = 400, description = "Invalid token id", body = ApiErrorBody),
+        (status = 404, description = "Unknown pair", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
+pub async fn get_pair_image(
+    State(AppState { pool, .. }): State<AppState>,
+    Path(token_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let card = load_pair_card(&pool, &token_id).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/svg+xml"),
+            (header::CACHE_CONTROL, "public, max-age=300"),
+        ],
+        render::pair_svg(&card),
+    ))
+}
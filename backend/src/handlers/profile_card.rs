@@ -0,0 +1,119 @@
+use axum::{
+    extract::{State, Path},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use alloy::primitives::Address;
+use crate::{
+    AppState,
+    constants::PROFILE_CARD_MAX_AGE_SECS,
+    services::profile_card::{self, CardFormat},
+    handlers::error::{ApiError, ApiErrorBody},
+};
+
+/// Serve a profile card, answering 304 when the client's copy is still current
+async fn card_response(
+    state: &AppState,
+    address: &str,
+    headers: &HeaderMap,
+    format: CardFormat,
+) -> Result<Response, ApiError> {
+    let _: Address = address.parse()
+        .map_err(|_| ApiError::InvalidAddress("wallet address"))?;
+
+    let card = profile_card::get_profile_card(&state.pool, &state.card_cache, &state.config.public_app_url, address, format).await
+        .map_err(ApiError::internal("Failed to render profile card"))?;
+
+    let cache_control = format!("public, max-age={}", PROFILE_CARD_MAX_AGE_SECS);
+    let not_modified = headers.get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == card.etag));
+    if not_modified {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, card.etag), (header::CACHE_CONTROL, cache_control)],
+        ).into_response());
+    }
+
+    let content_type = match format {
+        CardFormat::Svg => "image/svg+xml",
+        CardFormat::Png => "image/png",
+    };
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::ETAG, card.etag),
+            (header::CACHE_CONTROL, cache_control),
+        ],
+        card.body.as_ref().clone(),
+    ).into_response())
+}
+
+/// Shareable profile card as SVG
+#[utoipa::path(
+    get,
+    path = "/api/v2/profiles/{address}/card.svg",
+    tag = "embeds",
+    params(
+        ("address" = String, Path, description = "Wallet address"),
+    ),
+    responses(
+        (status = 200, description = "Profile card", content_type = "image/svg+xml", body = String),
+        (status = 304, description = "Unchanged since `If-None-Match`"),
+        (status = 400, description = "Invalid wallet address", body = ApiErrorBody),
+        (status = 500, description = "Rendering failed", body = ApiErrorBody),
+    ),
+)]
+pub async fn get_profile_card_svg(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    card_response(&state, &address, &headers, CardFormat::Svg).await
+}
+
+/// Shareable profile card rasterized to PNG
+#[utoipa::path(
+    get,
+    path = "/api/v2/profiles/{address}/card.png",
+    tag = "embeds",
+    params(
+        ("address" = String, Path, description = "Wallet address"),
+    ),
+    responses(
+        (status = 200, description = "Profile card", content_type = "image/png", body = Vec<u8>),
+        (status = 304, description = "Unchanged since `If-None-Match`"),
+        (status = 400, description = "Invalid wallet address", body = ApiErrorBody),
+        (status = 500, description = "Rendering failed", body = ApiErrorBody),
+    ),
+)]
+pub async fn get_profile_card_png(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    card_response(&state, &address, &headers, CardFormat::Png).await
+}
+
+/// Open Graph image for a profile link preview (the PNG card, sized 1200x630)
+#[utoipa::path(
+    get,
+    path = "/api/v2/profiles/{address}/og-image",
+    tag = "embeds",
+    params(
+        ("address" = String, Path, description = "Wallet address"),
+    ),
+    responses(
+        (status = 200, description = "Open Graph image", content_type = "image/png", body = Vec<u8>),
+        (status = 304, description = "Unchanged since `If-None-Match`"),
+        (status = 400, description = "Invalid wallet address", body = ApiErrorBody),
+        (status = 500, description = "Rendering failed", body = ApiErrorBody),
+    ),
+)]
+pub async fn get_profile_og_image(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    card_response(&state, &address, &headers, CardFormat::Png).await
+}
@@ -0,0 +1,97 @@
+use axum::{extract::{State, Path}, response::Json};
+use alloy::primitives::{Address, B256};
+use chrono::Utc;
+use crate::{
+    AppState,
+    constants::*,
+    db::attestations,
+    proof::{self, ProofCertificate},
+    handlers::error::{ApiError, ApiErrorBody},
+};
+
+/// Issue a signed, offline-verifiable certificate for a pair's latest attestation
+#[utoipa::path(
+    get,
+    path = "/api/v2/proofs/{address_a}/{address_b}",
+    tag = "proofs",
+    params(
+        ("address_a" = String, Path, description = "First wallet address"),
+        ("address_b" = String, Path, description = "Second wallet address"),
+    ),
+    responses(
+        (status = 200, description = "Signed proof certificate for the pair's latest attestation", body = ProofCertificate),
+        (status = 400, description = "Invalid wallet address", body = ApiErrorBody),
+        (status = 404, description = "No on-chain attestation for this pair", body = ApiErrorBody),
+        (status = 500, description = "Signing failed", body = ApiErrorBody),
+        (status = 503, description = "No signer available", body = ApiErrorBody),
+    ),
+)]
+pub async fn get_proof_certificate(
+    State(AppState { pool, config, contract, signers, .. }): State<AppState>,
+    Path((address_a, address_b)): Path<(String, String)>,
+) -> Result<Json<ProofCertificate>, ApiError> {
+    let _: Address = address_a.parse().map_err(|_| ApiError::InvalidAddress("address"))?;
+    let _: Address = address_b.parse().map_err(|_| ApiError::InvalidAddress("address"))?;
+
+    let attestation = attestations::check_together(&pool, &address_a, &address_b).await
+        .map_err(ApiError::internal("Failed to issue proof certificate"))?
+        .ok_or(ApiError::NotFound("These addresses have not been together"))?;
+
+    let (Some(tx_hash), Some(block_number)) = (attestation.tx_hash.as_deref(), attestation.block_number) else {
+        return Err(ApiError::NotFound("Attestation has no on-chain record yet"));
+    };
+    let tx_hash: B256 = tx_hash.parse()
+        .map_err(ApiError::internal("Invalid stored tx hash"))?;
+    let address_1 = attestation.address_1.parse::<Address>()
+        .map_err(ApiError::internal("Invalid stored attestation address"))?;
+    let address_2 = attestation.address_2.parse::<Address>()
+        .map_err(ApiError::internal("Invalid stored attestation address"))?;
+    let contract_address: Address = config.together_contract_address.parse()
+        .map_err(ApiError::internal("Invalid contract address in config"))?;
+
+    // Attestations recorded before log positions were tracked are looked up once and backfilled
+    let stored_position = attestation.block_hash.as_deref()
+        .and_then(|hash| hash.parse::<B256>().ok())
+        .zip(attestation.log_index);
+    let (block_hash, log_index) = match stored_position {
+        Some((block_hash, log_index)) => (block_hash, log_index as u64),
+        None => {
+            let (block_hash, log_index) = contract
+                .get_together_log_position(tx_hash, address_1, address_2, attestation.attestation_timestamp as u64)
+                .await
+                .map_err(ApiError::internal("Failed to look up attestation log position"))?
+                .ok_or(ApiError::NotFound("Together event not found in the attestation transaction"))?;
+
+            if let Err(e) = attestations::set_attestation_log_position(&pool, attestation.id, &block_hash.to_string(), log_index as i64).await {
+                tracing::warn!("Failed to backfill log position for attestation {}: {}", attestation.id, e);
+            }
+            (block_hash, log_index)
+        }
+    };
+
+    let signer = signers.pick(&pool).await
+        .map_err(|e| {
+            tracing::error!("Failed to pick attestation signer: {}", e);
+            ApiError::SignerUnavailable
+        })?;
+
+    let certificate = ProofCertificate {
+        version: PROOF_CERTIFICATE_VERSION,
+        address_1,
+        address_2,
+        timestamp: attestation.attestation_timestamp as u64,
+        tx_hash,
+        block_number: block_number as u64,
+        block_hash,
+        log_index,
+        contract_address,
+        chain_id: WORLDCHAIN_MAINNET_CHAIN_ID,
+        issued_at: Utc::now().timestamp() as u64,
+        signer: Address::ZERO,
+        signature: String::new(),
+    };
+    let certificate = proof::sign_certificate(certificate, signer.as_ref()).await
+        .map_err(ApiError::internal("Failed to sign proof certificate"))?;
+
+    Ok(Json(certificate))
+}
@@ -0,0 +1,101 @@
+use axum::{extract::{State, Path}, response::Json};
+use chrono::Utc;
+use crate::{
+    AppState,
+    constants::*,
+    db::proximity,
+    handlers::{auth::AuthSession, error::{ApiError, ApiErrorBody}},
+};
+pub use together_types::proximity::{ProximityChallengeResponse, ReportProximityRequest, ReportProximityResponse};
+
+// Attempts at drawing a nonce that no active challenge is using
+const NONCE_ATTEMPTS: usize = 3;
+
+/// Issue a challenge nonce for the user's device to broadcast to the person they're with
+#[utoipa::path(
+    post,
+    path = "/api/v2/users/{user_id}/proximity-challenges",
+    tag = "pairing",
+    params(
+        ("user_id" = i32, Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "Challenge nonce to broadcast", body = ProximityChallengeResponse),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid session token", body = ApiErrorBody),
+        (status = 403, description = "Session belongs to another user", body = ApiErrorBody),
+    ),
+    security(("session_token" = [])),
+)]
+pub async fn create_proximity_challenge(
+    State(AppState { pool, .. }): State<AppState>,
+    Path(user_id): Path<i32>,
+    session: AuthSession,
+) -> Result<Json<ProximityChallengeResponse>, ApiError> {
+    session.require_user(user_id)?;
+
+    let mut nonce = format!("{:08x}", rand::random::<u32>());
+    for _ in 1..NONCE_ATTEMPTS {
+        let taken = proximity::active_challenge_exists(&pool, &nonce).await
+            .map_err(ApiError::internal("Failed to create proximity challenge"))?;
+        if !taken {
+            break;
+        }
+        nonce = format!("{:08x}", rand::random::<u32>());
+    }
+
+    let expires_at = Utc::now() + chrono::Duration::seconds(PROXIMITY_CHALLENGE_TTL_SECS);
+    let challenge = proximity::create_challenge(&pool, user_id, &nonce, expires_at).await
+        .map_err(ApiError::internal("Failed to create proximity challenge"))?;
+
+    Ok(Json(ProximityChallengeResponse {
+        challenge_id: challenge.id.to_string(),
+        nonce: challenge.nonce,
+        expires_at: challenge.expires_at.to_rfc3339(),
+    }))
+}
+
+/// Report a challenge nonce the user's device just received from someone nearby
+#[utoipa::path(
+    post,
+    path = "/api/v2/users/{user_id}/proximity-responses",
+    tag = "pairing",
+    params(
+        ("user_id" = i32, Path, description = "User id"),
+    ),
+    request_body = ReportProximityRequest,
+    responses(
+        (status = 200, description = "Response recorded", body = ReportProximityResponse),
+        (status = 404, description = "Challenge is unknown or expired", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid session token", body = ApiErrorBody),
+        (status = 403, description = "Session belongs to another user", body = ApiErrorBody),
+    ),
+    security(("session_token" = [])),
+)]
+pub async fn report_proximity_challenge(
+    State(AppState { pool, .. }): State<AppState>,
+    Path(user_id): Path<i32>,
+    session: AuthSession,
+    Json(req): Json<ReportProximityRequest>,
+) -> Result<Json<ReportProximityResponse>, ApiError> {
+    session.require_user(user_id)?;
+
+    let nonce = req.nonce.trim().to_lowercase();
+    let (response, challenge) = proximity::record_response(&pool, &nonce, user_id).await
+        .map_err(ApiError::internal("Failed to record proximity response"))?
+        .ok_or(ApiError::NotFound("Challenge is unknown or expired"))?;
+
+    tracing::info!(
+        "User {} answered proximity challenge from user {} in {}ms",
+        user_id,
+        challenge.issuer_user_id,
+        response.latency_ms
+    );
+
+    Ok(Json(ReportProximityResponse {
+        challenge_id: challenge.id.to_string(),
+        issuer_user_id: challenge.issuer_user_id,
+        latency_ms: response.latency_ms,
+    }))
+}
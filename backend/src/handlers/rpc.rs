@@ -1,209 +1,142 @@
 use axum::{
     extract::{Query, State},
-    http::{HeaderMap, StatusCode},
-    response::Json,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+    Extension,
     Json as JsonBody,
 };
-use reqwest::Client;
 use serde_json::{Value, json};
-use std::collections::HashMap;
-use tokio::time::{Duration, Instant};
-use std::sync::Arc;
-use tokio::sync::Mutex;
-use crate::{Config, PgPool};
+use std::time::Duration;
+use crate::{
+    constants::{RPC_DEFAULT_NETWORK, RPC_MAX_BATCH_CALLS, RPC_PROXY_TIMEOUT_SECS},
+    handlers::error::{ApiError, ApiErrorBody},
+    rate_limit::{self, Decision, RateLimiter},
+    services::rpc_proxy::{cache_ttl, RpcCache, RpcNetwork},
+    utils::client_ip::ClientIp,
+    AppState,
+};
+pub use together_types::rpc::RpcQuery;
+
+/// Proxy JSON-RPC requests to Worldchain (the default) or another supported network
+///
+/// Only the network's allowlisted methods are forwarded, so `debug_*`/`trace_*` and
+/// other expensive calls are refused. Single calls whose answer can't change (see
+/// [`cache_ttl`]) are answered from a short-lived cache. Requests are rate limited per
+/// IP by the route's `RateLimitLayer`, and every call in a batch (at most
+/// [`RPC_MAX_BATCH_CALLS`]) counts as one request.
+#[utoipa::path(
+    post,
+    path = "/api/v2/rpc",
+    tag = "rpc",
+    params(
+        RpcQuery,
+    ),
+    request_body = Object,
+    responses(
+        (status = 200, description = "JSON-RPC response from the upstream node or the cache", body = Object),
+        (status = 400, description = "Invalid JSON-RPC request, batch too large, unsupported network or method not allowed", body = Object),
+        (status = 429, description = "Rate limited", body = ApiErrorBody),
+        (status = 502, description = "Upstream node failed", body = Object),
+    ),
+)]
+pub async fn proxy_rpc(
+    Query(params): Query<RpcQuery>,
+    client_ip: ClientIp,
+    State(AppState { http, config, rpc_cache, .. }): State<AppState>,
+    Extension(limiter): Extension<RateLimiter>,
+    JsonBody(body): JsonBody<Value>,
+) -> Result<Json<Value>, Response> {
+    let bad_request = |error: String| (StatusCode::BAD_REQUEST, Json(json!({ "error": error }))).into_response();
 
-// Rate limiting structure for RPC requests
-#[derive(Debug)]
-struct RpcRateLimiter {
-    requests: HashMap<String, Vec<Instant>>,
-    max_requests_per_minute: u32,
-}
+    let requested = params.network.as_deref().unwrap_or(RPC_DEFAULT_NETWORK);
+    let Some(network) = RpcNetwork::find(requested) else {
+        return Err(bad_request(format!("Unsupported network: {}", requested)));
+    };
 
-impl RpcRateLimiter {
-    fn new(max_requests_per_minute: u32) -> Self {
-        Self {
-            requests: HashMap::new(),
-            max_requests_per_minute,
-        }
+    // Validate JSON-RPC request structure
+    if !is_valid_jsonrpc_request(&body) {
+        return Err(bad_request("Invalid JSON-RPC request format".to_string()));
     }
 
-    async fn can_make_request(&mut self, key: &str) -> bool {
-        let now = Instant::now();
-        let minute_ago = now - Duration::from_secs(60);
-        
-        let requests = self.requests.entry(key.to_string()).or_insert_with(Vec::new);
-        
-        // Remove old requests
-        requests.retain(|&time| time > minute_ago);
-        
-        if requests.len() < self.max_requests_per_minute as usize {
-            requests.push(now);
-            true
-        } else {
-            false
-        }
+    let calls = match &body {
+        Value::Array(calls) => calls.iter().collect(),
+        call => vec![call],
+    };
+    if calls.len() > RPC_MAX_BATCH_CALLS {
+        return Err(bad_request(format!("Batch has {} calls; at most {} are allowed", calls.len(), RPC_MAX_BATCH_CALLS)));
     }
-}
 
-// Global rate limiter instance
-use once_cell::sync::Lazy;
-
-static RPC_RATE_LIMITER: Lazy<Arc<Mutex<RpcRateLimiter>>> = 
-    Lazy::new(|| Arc::new(Mutex::new(RpcRateLimiter::new(300)))); // 300 requests per minute
-
-#[derive(serde::Deserialize)]
-pub struct RpcQuery {
-    #[serde(default)]
-    network: Option<String>,
-}
-
-/// Proxy RPC requests to avoid frontend rate limiting
-/// 
-/// This endpoint forwards JSON-RPC requests to the appropriate network endpoint.
-/// It includes rate limiting per IP and basic request validation.
-pub async fn proxy_rpc(
-    Query(params): Query<RpcQuery>,
-    headers: HeaderMap,
-    State((_pool, _config)): State<(PgPool, Config)>,
-    JsonBody(body): JsonBody<Value>,
-) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    // Get client IP for rate limiting
-    let client_ip = get_client_ip(&headers);
-    
-    // Rate limiting
+    // The route's layer already counted this request as one call; refused calls count too
+    if calls.len() > 1
+        && let Decision::Deny { retry_after } = limiter.charge_ip(&rate_limit::RPC, &client_ip, calls.len() as u32 - 1).await
     {
-        let mut rate_limiter = RPC_RATE_LIMITER.lock().await;
-        if !rate_limiter.can_make_request(&client_ip).await {
-            return Err((
-                StatusCode::TOO_MANY_REQUESTS,
-                Json(json!({
-                    "error": "Rate limit exceeded. Please try again later."
-                }))
-            ));
-        }
+        return Err(ApiError::RateLimited(retry_after).into_response());
     }
 
-    // Determine the RPC endpoint based on network parameter
-    let rpc_url = match params.network.as_deref() {
-        Some("base") | None => {
-            // Default to Base network, use Alchemy if available
-            if let Ok(alchemy_key) = std::env::var("ALCHEMY_API_KEY") {
-                format!("https://base-mainnet.g.alchemy.com/v2/{}", alchemy_key)
-            } else {
-                "https://mainnet.base.org".to_string()
-            }
-        },
-        Some("ethereum") => {
-            if let Ok(alchemy_key) = std::env::var("ALCHEMY_API_KEY") {
-                format!("https://eth-mainnet.g.alchemy.com/v2/{}", alchemy_key)
-            } else {
-                "https://eth.llamarpc.com".to_string()
-            }
-        },
-        Some("polygon") => {
-            if let Ok(alchemy_key) = std::env::var("ALCHEMY_API_KEY") {
-                format!("https://polygon-mainnet.g.alchemy.com/v2/{}", alchemy_key)
-            } else {
-                "https://polygon-rpc.com".to_string()
-            }
-        },
-        Some(network) => {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                Json(json!({
-                    "error": format!("Unsupported network: {}", network)
-                }))
-            ));
+    for call in &calls {
+        let method = call["method"].as_str().unwrap_or_default();
+        if !network.allows(method) {
+            return Err(bad_request(format!("Method not allowed on {}: {}", network.name, method)));
         }
-    };
-
-    // Validate JSON-RPC request structure
-    if !is_valid_jsonrpc_request(&body) {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "error": "Invalid JSON-RPC request format"
-            }))
-        ));
     }
 
     // Log the request for monitoring
     tracing::info!(
         "RPC proxy request from {} to {} network: method={}",
         client_ip,
-        params.network.as_deref().unwrap_or("base"),
-        body.get("method").and_then(|m| m.as_str()).unwrap_or("unknown")
+        network.name,
+        calls.iter().map(|call| call["method"].as_str().unwrap_or_default()).collect::<Vec<_>>().join(",")
     );
 
-    // Forward the request
-    let client = Client::new();
-    let response = client
-        .post(&rpc_url)
-        .json(&body)
-        .timeout(Duration::from_secs(30))
-        .send()
-        .await;
-
-    match response {
-        Ok(resp) => {
-            if resp.status().is_success() {
-                match resp.json::<Value>().await {
-                    Ok(json_response) => Ok(Json(json_response)),
-                    Err(e) => {
-                        tracing::error!("Failed to parse RPC response as JSON: {}", e);
-                        Err((
-                            StatusCode::BAD_GATEWAY,
-                            Json(json!({
-                                "error": "Invalid response from RPC endpoint"
-                            }))
-                        ))
-                    }
-                }
-            } else {
-                tracing::error!("RPC endpoint returned error status: {}", resp.status());
-                Err((
-                    StatusCode::BAD_GATEWAY,
-                    Json(json!({
-                        "error": "RPC endpoint error"
-                    }))
-                ))
-            }
-        }
-        Err(e) => {
-            tracing::error!("Failed to connect to RPC endpoint: {}", e);
-            Err((
-                StatusCode::BAD_GATEWAY,
-                Json(json!({
-                    "error": "Failed to connect to RPC endpoint"
-                }))
-            ))
+    // Batches are always forwarded; only single calls go through the cache
+    let cacheable = match &body {
+        Value::Object(call) => {
+            let method = call["method"].as_str().unwrap_or_default();
+            let params = call.get("params").cloned().unwrap_or(Value::Null);
+            cache_ttl(method, &params).map(|ttl| (RpcCache::key(network, method, &params), ttl))
         }
+        _ => None,
+    };
+    if let Some((key, _)) = &cacheable
+        && let Some(result) = rpc_cache.get(key)
+    {
+        return Ok(Json(json!({ "jsonrpc": "2.0", "id": body["id"], "result": result })));
+    }
+
+    let response = forward(&http, &network.url(&config.alchemy_api_key), &body).await
+        .map_err(IntoResponse::into_response)?;
+    if let Some((key, ttl)) = cacheable
+        && response.get("error").is_none()
+        && let Some(result) = response.get("result")
+    {
+        rpc_cache.insert(key, result.clone(), ttl);
     }
+    Ok(Json(response))
 }
 
-fn get_client_ip(headers: &HeaderMap) -> String {
-    // Try various headers that might contain the real IP
-    let ip_headers = [
-        "cf-connecting-ip",      // Cloudflare
-        "x-forwarded-for",       // Standard proxy header
-        "x-real-ip",            // Nginx
-        "x-client-ip",          // Apache
-    ];
+async fn forward(http: &reqwest::Client, url: &str, body: &Value) -> Result<Value, (StatusCode, Json<Value>)> {
+    let bad_gateway = |error: &str| (StatusCode::BAD_GATEWAY, Json(json!({ "error": error })));
 
-    for header_name in &ip_headers {
-        if let Some(header_value) = headers.get(*header_name) {
-            if let Ok(ip_str) = header_value.to_str() {
-                // x-forwarded-for can be a comma-separated list, take the first one
-                let ip = ip_str.split(',').next().unwrap_or(ip_str).trim();
-                if !ip.is_empty() {
-                    return ip.to_string();
-                }
-            }
-        }
+    let response = http
+        .post(url)
+        .json(body)
+        .timeout(Duration::from_secs(RPC_PROXY_TIMEOUT_SECS))
+        .send()
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to connect to RPC endpoint: {}", e);
+            bad_gateway("Failed to connect to RPC endpoint")
+        })?;
+
+    if !response.status().is_success() {
+        tracing::error!("RPC endpoint returned error status: {}", response.status());
+        return Err(bad_gateway("RPC endpoint error"));
     }
 
-    // Fallback to "unknown"
-    "unknown".to_string()
+    response.json::<Value>().await.map_err(|e| {
+        tracing::error!("Failed to parse RPC response as JSON: {}", e);
+        bad_gateway("Invalid response from RPC endpoint")
+    })
 }
 
 fn is_valid_jsonrpc_request(body: &Value) -> bool {
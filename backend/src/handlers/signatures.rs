@@ -0,0 +1,132 @@
+use axum::{extract::{State, Path}, response::Json};
+use alloy::primitives::U256;
+use chrono::Utc;
+use std::str::FromStr;
+use crate::{
+    AppState,
+    db::signatures as signatures_db,
+    models::SignatureIssuance,
+    services::signatures,
+    handlers::error::{ApiError, ApiErrorBody},
+};
+pub use together_types::signatures::SignatureStatusResponse;
+
+/// Report an issued signature and whether its nonce has been consumed on chain
+///
+/// Accepts the nonce as returned by `/api/attest` (decimal) or as 0x-prefixed hex. If
+/// the watcher hasn't linked a Together event yet, the contract's `authNoncesUsed` is
+/// checked directly.
+#[utoipa::path(
+    get,
+    path = "/api/v2/signatures/{nonce}",
+    tag = "attestations",
+    params(
+        ("nonce" = String, Path, description = "Signature nonce, decimal or 0x-prefixed hex"),
+    ),
+    responses(
+        (status = 200, description = "Issued signature and whether it was used", body = SignatureStatusResponse),
+        (status = 400, description = "Invalid nonce", body = ApiErrorBody),
+        (status = 404, description = "No signature with this nonce", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
+pub async fn get_signature_status(
+    State(AppState { pool, contract, .. }): State<AppState>,
+    Path(nonce): Path<String>,
+) -> Result<Json<SignatureStatusResponse>, ApiError> {
+    let nonce = U256::from_str(nonce.trim())
+        .map_err(|_| ApiError::InvalidRequest("Invalid nonce format"))?;
+    let nonce_key = signatures::format_nonce(nonce);
+
+    let mut issuance = signatures_db::get_issuance_by_nonce(&pool, &nonce_key).await
+        .map_err(ApiError::internal("Failed to look up signature"))?
+        .ok_or(ApiError::NotFound("No signature was issued with this nonce"))?;
+
+    if issuance.consumed_at.is_none() {
+        match contract.is_nonce_used(nonce).await {
+            Ok(true) => {
+                let updated = signatures_db::mark_consumed_by_nonce(&pool, &nonce_key).await
+                    .map_err(ApiError::internal("Failed to look up signature"))?;
+                if let Some(updated) = updated {
+                    issuance = updated;
+                }
+            }
+            Ok(false) => {}
+            Err(e) => {
+                // Report what the ledger knows rather than failing the lookup
+                tracing::warn!("Failed to check nonce {} on chain: {}", nonce_key, e);
+            }
+        }
+    }
+
+    Ok(Json(status_response(issuance, Utc::now().timestamp())))
+}
+
+// A signature only expires unused; once consumed its deadline no longer matters
+fn status_response(issuance: SignatureIssuance, now: i64) -> SignatureStatusResponse {
+    let consumed = issuance.consumed_at.is_some();
+    SignatureStatusResponse {
+        expired: !consumed && issuance.deadline < now,
+        nonce: issuance.nonce,
+        on_behalf_of: issuance.on_behalf_of,
+        together_with: issuance.together_with,
+        attestation_timestamp: issuance.attestation_timestamp,
+        deadline: issuance.deadline,
+        source: issuance.source,
+        issued_at: issuance.created_at.to_rfc3339(),
+        consumed,
+        consumed_at: issuance.consumed_at.map(|at| at.to_rfc3339()),
+        consumed_tx_hash: issuance.consumed_tx_hash,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn issuance(deadline: i64, consumed: bool) -> SignatureIssuance {
+        let created_at = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        SignatureIssuance {
+            id: uuid::Uuid::new_v4(),
+            nonce: signatures::format_nonce(U256::from(7)),
+            on_behalf_of: "0x0000000000000000000000000000000000000001".to_string(),
+            together_with: "0x0000000000000000000000000000000000000002".to_string(),
+            attestation_timestamp: 1_700_000_000,
+            deadline,
+            signer_address: "0x0000000000000000000000000000000000000003".to_string(),
+            source: "attest".to_string(),
+            requester_ip: Some("203.0.113.7".to_string()),
+            session_id: None,
+            requester: Some("ip:203.0.113.7".to_string()),
+            created_at,
+            consumed_at: consumed.then_some(created_at),
+            consumed_tx_hash: consumed.then(|| format!("0x{}", "ab".repeat(32))),
+        }
+    }
+
+    #[test]
+    fn test_decimal_and_hex_nonces_share_a_key() {
+        let decimal = U256::from_str("255").unwrap();
+        let hex = U256::from_str("0xff").unwrap();
+        assert_eq!(signatures::format_nonce(decimal), signatures::format_nonce(hex));
+        assert_eq!(signatures::format_nonce(hex), format!("0x{:0>64}", "ff"));
+    }
+
+    #[test]
+    fn test_status_expires_only_unused_signatures() {
+        let now = 1_700_000_600;
+
+        let pending = status_response(issuance(now + 60, false), now);
+        assert!(!pending.consumed && !pending.expired);
+
+        let expired = status_response(issuance(now - 60, false), now);
+        assert!(!expired.consumed && expired.expired);
+        assert!(expired.consumed_at.is_none() && expired.consumed_tx_hash.is_none());
+
+        let used = status_response(issuance(now - 60, true), now);
+        assert!(used.consumed && !used.expired);
+        assert_eq!(used.consumed_at.as_deref(), Some(used.issued_at.as_str()));
+        assert!(used.consumed_tx_hash.is_some());
+    }
+}
@@ -1,38 +1,27 @@
 use axum::{extract::{State, Path, Query}, http::StatusCode, response::Json};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use sqlx::PgPool;
 use alloy::primitives::Address;
+use chrono::Utc;
+use uuid::Uuid;
 use crate::{
-    utils::{Config, eip712::Eip712Signer},
+    AppState,
+    utils::{Config, client_ip::ClientIp, eip712::Eip712Signer, pairing_code},
     constants::*,
-    models::attestations::{UserProfile, TogetherAttestation},
-    db::{attestations, users},
-    services::contract::ContractService,
+    models::{attestations::{UserProfile, TogetherAttestation}, NewSignatureIssuance},
+    db::{account_deletions, attestations, pairing, proximity as proximity_db, users},
+    services::{contract::ContractService, events::{self, PairingEvent}, proximity::{self, ProximityPolicy}, signatures::{self, QuotaSubject}},
+    handlers::{auth::AuthSession, error::{ApiError, ApiErrorBody}},
+};
+pub use together_types::together::{
+    AttestTogetherRequest, AttestTogetherResponse, ProfileQuery, CreateUserRequest, UserResponse,
+    CreatePendingConnectionRequest, PairingCodeResponse, ResolvePendingConnectionQuery, PendingConnectionResponse,
+    UserPendingConnectionsResponse, OptimisticConnectionResponse, UserOptimisticConnectionsResponse,
 };
-
-// Request to create an attestation signature
-#[derive(Debug, Deserialize)]
-pub struct AttestTogetherRequest {
-    pub my_address: String,
-    pub partner_address: String, 
-    pub timestamp: i64,
-    pub password: String,
-    pub my_username: Option<String>,
-    pub partner_username: Option<String>,
-    pub my_profile_picture_url: Option<String>,
-    pub partner_profile_picture_url: Option<String>,
-}
-
-// Response with signature for on-chain attestation
-#[derive(Debug, Serialize)]
-pub struct AttestTogetherResponse {
-    pub signature: String,
-    pub nonce: String,
-    pub deadline: u64,
-}
 
 // Request to submit an attestation (from blockchain watcher or direct submission)
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SubmitAttestationRequest {
     pub address_1: String,
     pub address_2: String,
@@ -45,95 +34,42 @@ pub struct SubmitAttestationRequest {
     pub profile_picture_url_2: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SubmitAttestationResponse {
     pub success: bool,
     pub attestation_id: Option<String>,
 }
 
-// Query parameters for profile endpoint
-#[derive(Debug, Deserialize)]
-pub struct ProfileQuery {
-    #[serde(default)]
-    pub limit: Option<i64>,
-    pub username: Option<String>,
-    pub profile_picture_url: Option<String>,
-}
-
-// Query parameters for checking if two addresses have been together
-#[derive(Debug, Deserialize)]
+// Query parameters for checking if two addresses have been together (v1 only; v2 takes both in the path)
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct CheckTogetherQuery {
     pub address_2: String,
 }
 
-#[derive(Debug, Serialize)]
-pub struct TogetherError {
-    pub error: String,
-}
-
-#[derive(Debug, Serialize)]
-pub struct UserResponse {
-    pub id: i32,
-    pub wallet_address: String,
-    pub created_at: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct CreatePendingConnectionRequest {
-    pub to_user_id: i32,
-}
-
-#[derive(Debug, Serialize)]
-pub struct PendingConnectionResponse {
-    pub id: String,
-    pub from_user_id: i32,
-    pub to_user_id: i32,
-    pub from_user_address: Option<String>,
-    pub to_user_address: Option<String>,
-    pub from_username: Option<String>,
-    pub to_username: Option<String>,
-    pub created_at: String,
-    pub expires_at: String,
-}
-
-#[derive(Debug, Serialize)]
-pub struct UserPendingConnectionsResponse {
-    pub outgoing: Vec<PendingConnectionResponse>,
-    pub incoming: Vec<PendingConnectionResponse>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct OptimisticConnectionResponse {
-    pub id: String,
-    pub user_id_1: i32,
-    pub user_id_2: i32,
-    pub user_1_address: Option<String>,
-    pub user_2_address: Option<String>,
-    pub user_1_username: Option<String>,
-    pub user_2_username: Option<String>,
-    pub processed: bool,
-    pub created_at: String,
-}
-
-#[derive(Debug, Serialize)]
-pub struct UserOptimisticConnectionsResponse {
-    pub connections: Vec<OptimisticConnectionResponse>,
-}
-
 /// Get user profile with their together connections
+#[utoipa::path(
+    get,
+    path = "/api/v2/profiles/{address}",
+    tag = "profiles",
+    params(
+        ("address" = String, Path, description = "Wallet address"),
+        ProfileQuery,
+    ),
+    responses(
+        (status = 200, description = "Profile with recent connections", body = UserProfile),
+        (status = 400, description = "Invalid wallet address", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
 pub async fn get_profile(
-    State((pool, _config)): State<(PgPool, Config)>,
+    State(AppState { pool, .. }): State<AppState>,
     Path(address): Path<String>,
     Query(params): Query<ProfileQuery>,
-) -> Result<Json<UserProfile>, (StatusCode, Json<TogetherError>)> {
+) -> Result<Json<UserProfile>, ApiError> {
     // Validate address format
     let _address: Address = address.parse()
-        .map_err(|_| (
-            StatusCode::BAD_REQUEST,
-            Json(TogetherError {
-                error: "Invalid wallet address format".to_string(),
-            }),
-        ))?;
+        .map_err(|_| ApiError::InvalidAddress("wallet address"))?;
 
     // Cache username if provided in query params (from frontend when user visits their own profile)
     if params.username.is_some() || params.profile_picture_url.is_some() {
@@ -148,43 +84,40 @@ pub async fn get_profile(
     }
 
     let profile = attestations::get_user_profile(&pool, &address, params.limit).await
-        .map_err(|e| {
-            tracing::error!("Failed to get user profile: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(TogetherError {
-                    error: "Failed to retrieve user profile".to_string(),
-                }),
-            )
-        })?;
+        .map_err(ApiError::internal("Failed to retrieve user profile"))?;
 
     Ok(Json(profile))
 }
 
 /// Get or create user by wallet address, returning user ID
+#[utoipa::path(
+    post,
+    path = "/api/v2/users",
+    tag = "users",
+    request_body = CreateUserRequest,
+    responses(
+        (status = 200, description = "The user for this wallet, created if new", body = UserResponse),
+        (status = 400, description = "Invalid wallet address", body = ApiErrorBody),
+        (status = 410, description = "The wallet's account was deleted", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
 pub async fn get_or_create_user(
-    State((pool, _config)): State<(PgPool, Config)>,
-    Path(address): Path<String>,
-) -> Result<Json<UserResponse>, (StatusCode, Json<TogetherError>)> {
+    State(AppState { pool, .. }): State<AppState>,
+    Json(req): Json<CreateUserRequest>,
+) -> Result<Json<UserResponse>, ApiError> {
+    let address = req.wallet_address;
     // Validate address format
     let _address: Address = address.parse()
-        .map_err(|_| (
-            StatusCode::BAD_REQUEST,
-            Json(TogetherError {
-                error: "Invalid wallet address format".to_string(),
-            }),
-        ))?;
+        .map_err(|_| ApiError::InvalidAddress("wallet address"))?;
 
+    // Erased wallets stay erased rather than coming back as an account whose history is hidden
+    if account_deletions::is_address_erased(&pool, &address).await
+        .map_err(ApiError::internal("Failed to check account status"))? {
+        return Err(ApiError::AccountErased);
+    }
     let user = users::get_or_create_user(&pool, &address).await
-        .map_err(|e| {
-            tracing::error!("Failed to get or create user: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(TogetherError {
-                    error: "Failed to get or create user".to_string(),
-                }),
-            )
-        })?;
+        .map_err(ApiError::internal("Failed to get or create user"))?;
 
     Ok(Json(UserResponse {
         id: user.id,
@@ -194,125 +127,109 @@ pub async fn get_or_create_user(
 }
 
 /// Check if two addresses have been together
+#[utoipa::path(
+    get,
+    path = "/api/v2/profiles/{address}/together/{other_address}",
+    tag = "attestations",
+    params(
+        ("address" = String, Path, description = "First wallet address"),
+        ("other_address" = String, Path, description = "Second wallet address"),
+    ),
+    responses(
+        (status = 200, description = "The pair's attestation, or null if they've never been together", body = Option<TogetherAttestation>),
+        (status = 400, description = "Invalid wallet address", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
 pub async fn check_together(
-    State((pool, _config)): State<(PgPool, Config)>,
-    Path(address_1): Path<String>,
-    Query(params): Query<CheckTogetherQuery>,
-) -> Result<Json<Option<TogetherAttestation>>, (StatusCode, Json<TogetherError>)> {
+    State(AppState { pool, .. }): State<AppState>,
+    Path((address_1, address_2)): Path<(String, String)>,
+) -> Result<Json<Option<TogetherAttestation>>, ApiError> {
     // Validate address formats
     let _addr1: Address = address_1.parse()
-        .map_err(|_| (
-            StatusCode::BAD_REQUEST,
-            Json(TogetherError {
-                error: "Invalid wallet address format for address_1".to_string(),
-            }),
-        ))?;
-
-    let _addr2: Address = params.address_2.parse()
-        .map_err(|_| (
-            StatusCode::BAD_REQUEST,
-            Json(TogetherError {
-                error: "Invalid wallet address format for address_2".to_string(),
-            }),
-        ))?;
-
-    let attestation = attestations::check_together(&pool, &address_1, &params.address_2).await
-        .map_err(|e| {
-            tracing::error!("Failed to check together status: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(TogetherError {
-                    error: "Failed to check together status".to_string(),
-                }),
-            )
-        })?;
+        .map_err(|_| ApiError::InvalidAddress("address_1"))?;
+
+    let _addr2: Address = address_2.parse()
+        .map_err(|_| ApiError::InvalidAddress("address_2"))?;
+
+    // Erased accounts' attestations stay on chain but `check_together` never serves them
+    let attestation = attestations::check_together(&pool, &address_1, &address_2).await
+        .map_err(ApiError::internal("Failed to check together status"))?;
 
     Ok(Json(attestation))
 }
 
-/// Create a pending connection from one user to another
+/// Create a pending connection from the signed-in user to the owner of a pairing code
+#[utoipa::path(
+    post,
+    path = "/api/v2/users/{user_id}/pending-connections",
+    tag = "pairing",
+    params(
+        ("user_id" = i32, Path, description = "User id"),
+    ),
+    request_body = CreatePendingConnectionRequest,
+    responses(
+        (status = 200, description = "Pending connection created", body = PendingConnectionResponse),
+        (status = 400, description = "Malformed pairing code, or the code is the caller's own", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid session token", body = ApiErrorBody),
+        (status = 403, description = "Session belongs to another user", body = ApiErrorBody),
+        (status = 404, description = "Pairing code matches nobody", body = ApiErrorBody),
+        (status = 409, description = "Connection already pending, or pairing code is ambiguous", body = ApiErrorBody),
+        (status = 429, description = "Recipient recently declined a request, too many pending connections between the pair, too many codes that matched nobody, or rate limited", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+    security(("session_token" = [])),
+)]
 pub async fn create_pending_connection(
-    State((pool, _config)): State<(PgPool, Config)>,
+    State(AppState { pool, .. }): State<AppState>,
     Path(from_user_id): Path<i32>,
+    session: AuthSession,
     Json(req): Json<CreatePendingConnectionRequest>,
-) -> Result<Json<PendingConnectionResponse>, (StatusCode, Json<TogetherError>)> {
+) -> Result<Json<PendingConnectionResponse>, ApiError> {
+    session.require_user(from_user_id)?;
+
+    let to_user_id = resolve_pending_connection_target(&pool, &session, &req.pairing_code).await?;
+
     // Validate that both users exist
     let _from_user = users::get_user_by_id(&pool, from_user_id).await
-        .map_err(|e| {
-            tracing::error!("Failed to get from_user: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(TogetherError {
-                    error: "Failed to validate from_user".to_string(),
-                }),
-            )
-        })?
-        .ok_or_else(|| (
-            StatusCode::NOT_FOUND,
-            Json(TogetherError {
-                error: "From user not found".to_string(),
-            }),
-        ))?;
-
-    let _to_user = users::get_user_by_id(&pool, req.to_user_id).await
-        .map_err(|e| {
-            tracing::error!("Failed to get to_user: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(TogetherError {
-                    error: "Failed to validate to_user".to_string(),
-                }),
-            )
-        })?
-        .ok_or_else(|| (
-            StatusCode::NOT_FOUND,
-            Json(TogetherError {
-                error: "To user not found".to_string(),
-            }),
-        ))?;
+        .map_err(ApiError::internal("Failed to validate from_user"))?
+        .ok_or(ApiError::UserNotFound("From user"))?;
+
+    let _to_user = users::get_user_by_id(&pool, to_user_id).await
+        .map_err(ApiError::internal("Failed to validate to_user"))?
+        .ok_or(ApiError::UserNotFound("To user"))?;
 
     // Prevent self-connections
-    if from_user_id == req.to_user_id {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(TogetherError {
-                error: "Cannot create connection with yourself".to_string(),
-            }),
-        ));
+    if from_user_id == to_user_id {
+        return Err(ApiError::SelfConnection);
+    }
+
+    // Respect a recent decline from the recipient
+    if let Some(suppressed_until) = users::get_decline_suppression(&pool, from_user_id, to_user_id).await
+        .map_err(ApiError::internal("Failed to check decline cooldown"))? {
+        return Err(ApiError::DeclineCooldown(suppressed_until));
     }
 
     // Check if pending connection already exists
-    if let Some(_existing) = users::get_pending_connection(&pool, from_user_id, req.to_user_id).await
-        .map_err(|e| {
-            tracing::error!("Failed to check existing pending connection: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(TogetherError {
-                    error: "Failed to check existing pending connection".to_string(),
-                }),
-            )
-        })? {
-        return Err((
-            StatusCode::CONFLICT,
-            Json(TogetherError {
-                error: "Pending connection already exists".to_string(),
-            }),
-        ));
+    if let Some(_existing) = users::get_pending_connection(&pool, from_user_id, to_user_id).await
+        .map_err(ApiError::internal("Failed to check existing pending connection"))? {
+        return Err(ApiError::PendingConnectionExists);
     }
 
     // Create the pending connection
-    let pending = users::create_pending_connection(&pool, from_user_id, req.to_user_id).await
-        .map_err(|e| {
-            tracing::error!("Failed to create pending connection: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(TogetherError {
-                    error: "Failed to create pending connection".to_string(),
-                }),
-            )
-        })?;
+    let pending = users::create_pending_connection(&pool, from_user_id, to_user_id).await
+        .map_err(ApiError::internal("Failed to create pending connection"))?;
+
+    tracing::info!("Created pending connection from user {} to user {}", from_user_id, to_user_id);
 
-    tracing::info!("Created pending connection from user {} to user {}", from_user_id, req.to_user_id);
+    if let Err(e) = events::publish(&pool, &PairingEvent::PendingCreated {
+        pending_id: pending.id,
+        from_user_id: pending.from_user_id,
+        to_user_id: pending.to_user_id,
+        expires_at: pending.expires_at,
+    }).await {
+        tracing::warn!("Failed to publish pending_created event: {}", e);
+    }
 
     Ok(Json(PendingConnectionResponse {
         id: pending.id.to_string(),
@@ -327,28 +244,195 @@ pub async fn create_pending_connection(
     }))
 }
 
+/// Resolve the recipient of a new pending connection from their pairing code
+///
+/// Codes are short, so a session that keeps typing codes that match nobody is locked
+/// out for a while rather than left to enumerate them.
+async fn resolve_pending_connection_target(
+    pool: &PgPool,
+    session: &AuthSession,
+    raw_code: &str,
+) -> Result<i32, ApiError> {
+    if let Some(locked_until) = pairing::get_pairing_code_lockout(
+        pool,
+        session.session_id,
+        PAIRING_CODE_MAX_FAILURES,
+        PAIRING_CODE_FAILURE_WINDOW_SECS,
+    ).await.map_err(ApiError::internal("Failed to resolve pairing code"))? {
+        let retry_after = (locked_until - Utc::now()).to_std().unwrap_or_default();
+        return Err(ApiError::RateLimited(retry_after));
+    }
+
+    let code = pairing_code::normalize_pairing_code(raw_code)
+        .ok_or(ApiError::InvalidPairingCode)?;
+
+    let user_ids = pairing::find_users_by_pairing_code(pool, &code).await
+        .map_err(ApiError::internal("Failed to resolve pairing code"))?;
+
+    match user_ids.as_slice() {
+        [user_id] => Ok(*user_id),
+        [] => {
+            pairing::record_pairing_code_failure(pool, session.session_id).await
+                .map_err(ApiError::internal("Failed to resolve pairing code"))?;
+            Err(ApiError::PairingCodeNotFound)
+        }
+        // Two users' codes collided this window; the next rotation will differ
+        _ => Err(ApiError::PairingCodeAmbiguous),
+    }
+}
+
+/// Get the user's current rotating pairing code
+#[utoipa::path(
+    get,
+    path = "/api/v2/users/{user_id}/pairing-code",
+    tag = "pairing",
+    params(
+        ("user_id" = i32, Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "Current rotating pairing code", body = PairingCodeResponse),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid session token", body = ApiErrorBody),
+        (status = 403, description = "Session belongs to another user", body = ApiErrorBody),
+    ),
+    security(("session_token" = [])),
+)]
+pub async fn get_pairing_code(
+    State(AppState { pool, .. }): State<AppState>,
+    Path(user_id): Path<i32>,
+    session: AuthSession,
+) -> Result<Json<PairingCodeResponse>, ApiError> {
+    session.require_user(user_id)?;
+
+    let secret = pairing::get_or_create_pairing_secret(&pool, user_id, &pairing_code::generate_pairing_secret()).await
+        .map_err(ApiError::internal("Failed to get pairing code"))?;
+
+    let window = pairing_code::window_index(Utc::now());
+    let code = pairing_code::derive_pairing_code(&secret, window);
+    let expires_at = pairing_code::window_expires_at(window);
+
+    pairing::upsert_pairing_code(&pool, user_id, &code, window, expires_at).await
+        .map_err(ApiError::internal("Failed to get pairing code"))?;
+
+    Ok(Json(PairingCodeResponse {
+        code,
+        // Display expiry excludes the grace period so clients refresh on time
+        expires_at: (expires_at - chrono::Duration::seconds(PAIRING_CODE_GRACE_SECS)).to_rfc3339(),
+        rotation_seconds: PAIRING_CODE_ROTATION_SECS,
+    }))
+}
+
+/// Cancel one of the user's own outgoing pending connections
+#[utoipa::path(
+    delete,
+    path = "/api/v2/users/{user_id}/pending-connections/{connection_id}",
+    tag = "pairing",
+    params(
+        ("user_id" = i32, Path, description = "User id"),
+        ("connection_id" = Uuid, Path, description = "Pending connection id"),
+        ResolvePendingConnectionQuery,
+    ),
+    responses(
+        (status = 204, description = "Pending connection cancelled"),
+        (status = 404, description = "No such outgoing pending connection", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid session token", body = ApiErrorBody),
+        (status = 403, description = "Session belongs to another user", body = ApiErrorBody),
+    ),
+    security(("session_token" = [])),
+)]
+pub async fn cancel_pending_connection(
+    State(AppState { pool, .. }): State<AppState>,
+    Path((user_id, connection_id)): Path<(i32, Uuid)>,
+    Query(params): Query<ResolvePendingConnectionQuery>,
+    session: AuthSession,
+) -> Result<StatusCode, ApiError> {
+    session.require_user(user_id)?;
+
+    let pending = users::cancel_pending_connection(&pool, connection_id, user_id, params.reason).await
+        .map_err(ApiError::internal("Failed to cancel pending connection"))?
+        .ok_or(ApiError::PendingConnectionNotFound("Outgoing"))?;
+
+    tracing::info!("User {} cancelled pending connection to user {} ({})", user_id, pending.to_user_id, params.reason.as_str());
+
+    if let Err(e) = events::publish(&pool, &PairingEvent::Cancelled {
+        pending_id: pending.id,
+        from_user_id: pending.from_user_id,
+        to_user_id: pending.to_user_id,
+    }).await {
+        tracing::warn!("Failed to publish cancelled event: {}", e);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Decline a pending connection sent to the user, blocking the sender for a cooldown period
+#[utoipa::path(
+    post,
+    path = "/api/v2/users/{user_id}/pending-connections/{connection_id}/decline",
+    tag = "pairing",
+    params(
+        ("user_id" = i32, Path, description = "User id"),
+        ("connection_id" = Uuid, Path, description = "Pending connection id"),
+        ResolvePendingConnectionQuery,
+    ),
+    responses(
+        (status = 204, description = "Pending connection declined"),
+        (status = 404, description = "No such incoming pending connection", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid session token", body = ApiErrorBody),
+        (status = 403, description = "Session belongs to another user", body = ApiErrorBody),
+    ),
+    security(("session_token" = [])),
+)]
+pub async fn decline_pending_connection(
+    State(AppState { pool, .. }): State<AppState>,
+    Path((user_id, connection_id)): Path<(i32, Uuid)>,
+    Query(params): Query<ResolvePendingConnectionQuery>,
+    session: AuthSession,
+) -> Result<StatusCode, ApiError> {
+    session.require_user(user_id)?;
+
+    let suppressed_until = Utc::now() + chrono::Duration::minutes(DECLINE_COOLDOWN_MINUTES);
+    let pending = users::decline_pending_connection(&pool, connection_id, user_id, params.reason, suppressed_until).await
+        .map_err(ApiError::internal("Failed to decline pending connection"))?
+        .ok_or(ApiError::PendingConnectionNotFound("Incoming"))?;
+
+    tracing::info!("User {} declined pending connection from user {} ({})", user_id, pending.from_user_id, params.reason.as_str());
+
+    if let Err(e) = events::publish(&pool, &PairingEvent::Declined {
+        pending_id: pending.id,
+        from_user_id: pending.from_user_id,
+        to_user_id: pending.to_user_id,
+    }).await {
+        tracing::warn!("Failed to publish declined event: {}", e);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 /// Get all pending connections for a user (both outgoing and incoming)
+#[utoipa::path(
+    get,
+    path = "/api/v2/users/{user_id}/pending-connections",
+    tag = "pairing",
+    params(
+        ("user_id" = i32, Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "Outgoing and incoming pending connections", body = UserPendingConnectionsResponse),
+        (status = 404, description = "User not found", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
 pub async fn get_user_pending_connections(
-    State((pool, _config)): State<(PgPool, Config)>,
+    State(AppState { pool, .. }): State<AppState>,
     Path(user_id): Path<i32>,
-) -> Result<Json<UserPendingConnectionsResponse>, (StatusCode, Json<TogetherError>)> {
+) -> Result<Json<UserPendingConnectionsResponse>, ApiError> {
     // Validate user exists
     let _user = users::get_user_by_id(&pool, user_id).await
-        .map_err(|e| {
-            tracing::error!("Failed to get user: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(TogetherError {
-                    error: "Failed to validate user".to_string(),
-                }),
-            )
-        })?
-        .ok_or_else(|| (
-            StatusCode::NOT_FOUND,
-            Json(TogetherError {
-                error: "User not found".to_string(),
-            }),
-        ))?;
+        .map_err(ApiError::internal("Failed to validate user"))?
+        .ok_or(ApiError::UserNotFound("User"))?;
 
     // Get outgoing pending connections (connections this user initiated)
     let outgoing_result = sqlx::query!(
@@ -363,15 +447,7 @@ pub async fn get_user_pending_connections(
     )
     .fetch_all(&pool)
     .await
-    .map_err(|e| {
-        tracing::error!("Failed to get outgoing pending connections: {}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(TogetherError {
-                error: "Failed to get outgoing pending connections".to_string(),
-            }),
-        )
-    })?;
+    .map_err(ApiError::internal("Failed to get outgoing pending connections"))?;
 
     // Get incoming pending connections (connections sent to this user)
     let incoming_result = sqlx::query!(
@@ -386,15 +462,7 @@ pub async fn get_user_pending_connections(
     )
     .fetch_all(&pool)
     .await
-    .map_err(|e| {
-        tracing::error!("Failed to get incoming pending connections: {}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(TogetherError {
-                error: "Failed to get incoming pending connections".to_string(),
-            }),
-        )
-    })?;
+    .map_err(ApiError::internal("Failed to get incoming pending connections"))?;
 
     let outgoing: Vec<PendingConnectionResponse> = outgoing_result.into_iter().map(|p| {
         PendingConnectionResponse {
@@ -431,27 +499,27 @@ pub async fn get_user_pending_connections(
 }
 
 /// Get all optimistic connections for a user (both processed and unprocessed)
+#[utoipa::path(
+    get,
+    path = "/api/v2/users/{user_id}/optimistic-connections",
+    tag = "pairing",
+    params(
+        ("user_id" = i32, Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "Matched connections awaiting or past on-chain confirmation", body = UserOptimisticConnectionsResponse),
+        (status = 404, description = "User not found", body = ApiErrorBody),
+        (status = 500, description = "Database error", body = ApiErrorBody),
+    ),
+)]
 pub async fn get_user_optimistic_connections(
-    State((pool, _config)): State<(PgPool, Config)>,
+    State(AppState { pool, .. }): State<AppState>,
     Path(user_id): Path<i32>,
-) -> Result<Json<UserOptimisticConnectionsResponse>, (StatusCode, Json<TogetherError>)> {
+) -> Result<Json<UserOptimisticConnectionsResponse>, ApiError> {
     // Validate user exists
     let _user = users::get_user_by_id(&pool, user_id).await
-        .map_err(|e| {
-            tracing::error!("Failed to get user: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(TogetherError {
-                    error: "Failed to validate user".to_string(),
-                }),
-            )
-        })?
-        .ok_or_else(|| (
-            StatusCode::NOT_FOUND,
-            Json(TogetherError {
-                error: "User not found".to_string(),
-            }),
-        ))?;
+        .map_err(ApiError::internal("Failed to validate user"))?
+        .ok_or(ApiError::UserNotFound("User"))?;
 
     // Get all optimistic connections where this user is involved
     let connections_result = sqlx::query!(
@@ -466,15 +534,7 @@ pub async fn get_user_optimistic_connections(
     )
     .fetch_all(&pool)
     .await
-    .map_err(|e| {
-        tracing::error!("Failed to get optimistic connections: {}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(TogetherError {
-                error: "Failed to get optimistic connections".to_string(),
-            }),
-        )
-    })?;
+    .map_err(ApiError::internal("Failed to get optimistic connections"))?;
 
     let connections: Vec<OptimisticConnectionResponse> = connections_result.into_iter().map(|c| {
         OptimisticConnectionResponse {
@@ -496,58 +556,66 @@ pub async fn get_user_optimistic_connections(
 }
 
 /// Generate a signature for attesting that two users were together
+#[utoipa::path(
+    post,
+    path = "/api/v2/signatures",
+    tag = "attestations",
+    request_body = AttestTogetherRequest,
+    responses(
+        (status = 200, description = "Signature to submit on chain", body = AttestTogetherResponse),
+        (status = 400, description = "Invalid request", body = ApiErrorBody),
+        (status = 401, description = "Invalid session token", body = ApiErrorBody),
+        (status = 403, description = "Proximity proof required before attesting", body = ApiErrorBody),
+        (status = 429, description = "Signature quota or rate limit exceeded", body = ApiErrorBody),
+        (status = 500, description = "Signing failed", body = ApiErrorBody),
+        (status = 503, description = "No signer available", body = ApiErrorBody),
+    ),
+)]
 pub async fn attest_together(
-    State((pool, config)): State<(PgPool, Config)>,
+    State(AppState { pool, config, contract, signers, .. }): State<AppState>,
+    client_ip: ClientIp,
+    session: Option<AuthSession>,
     Json(req): Json<AttestTogetherRequest>,
-) -> Result<Json<AttestTogetherResponse>, (StatusCode, Json<TogetherError>)> {
+) -> Result<Json<AttestTogetherResponse>, ApiError> {
     // Validate wallet addresses
     let my_address: Address = req.my_address.parse()
-        .map_err(|_| (
-            StatusCode::BAD_REQUEST,
-            Json(TogetherError {
-                error: "Invalid my_address format".to_string(),
-            }),
-        ))?;
+        .map_err(|_| ApiError::InvalidAddress("my_address"))?;
 
     let partner_address: Address = req.partner_address.parse()
-        .map_err(|_| (
-            StatusCode::BAD_REQUEST,
-            Json(TogetherError {
-                error: "Invalid partner_address format".to_string(),
-            }),
-        ))?;
+        .map_err(|_| ApiError::InvalidAddress("partner_address"))?;
+
+    let proximity_proof = require_proximity_proof(&pool, config.proximity_policy, &req.my_address, &req.partner_address).await?;
+
+    // Quotas are charged to the caller as well as to the pair and addresses they named
+    let requester = signatures::requester_key(session.as_ref().map(|s| s.wallet_address.as_str()), &client_ip);
+    let exceeded = {
+        let mut conn = pool.acquire().await
+            .map_err(ApiError::internal("Failed to check signature quota"))?;
+        signatures::check_quota(&mut conn, QuotaSubject::Requester(&requester), &req.my_address, &req.partner_address).await
+            .map_err(ApiError::internal("Failed to check signature quota"))?
+    };
+    if let Some(exceeded) = exceeded {
+        tracing::warn!("Refusing signature for {} and {} to {}: {}", req.my_address, req.partner_address, requester, exceeded.message());
+        return Err(ApiError::SignatureQuotaExceeded(exceeded.message()));
+    }
 
     // Check if they've already been together at this exact timestamp
     if let Some(_existing) = attestations::check_together(&pool, &req.my_address, &req.partner_address).await
-        .map_err(|e| {
-            tracing::error!("Failed to check existing attestation: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(TogetherError {
-                    error: "Failed to check existing attestation".to_string(),
-                }),
-            )
-        })? {
+        .map_err(ApiError::internal("Failed to check existing attestation"))? {
         // They've already been together - could still allow but warn frontend
         tracing::info!("Addresses {} and {} have already been together", req.my_address, req.partner_address);
     }
 
     // Generate EIP712 signature for the together attestation
-    let signer = Eip712Signer::new(&config.private_key_signer, WORLDCHAIN_MAINNET_CHAIN_ID)
-        .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(TogetherError {
-                error: format!("Failed to initialize signer: {}", e),
-            }),
-        ))?;
+    let backend = signers.pick(&pool).await
+        .map_err(|e| {
+            tracing::error!("Failed to pick attestation signer: {}", e);
+            ApiError::SignerUnavailable
+        })?;
+    let signer = Eip712Signer::new(backend, WORLDCHAIN_MAINNET_CHAIN_ID);
 
     let contract_address: Address = config.together_contract_address.parse()
-        .map_err(|_| (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(TogetherError {
-                error: "Invalid contract address in config".to_string(),
-            }),
-        ))?;
+        .map_err(ApiError::internal("Invalid contract address in config"))?;
 
     let nonce = Eip712Signer::generate_nonce();
     let deadline = Eip712Signer::generate_deadline_10_minutes();
@@ -561,12 +629,32 @@ pub async fn attest_together(
         nonce,
         deadline,
     ).await
-    .map_err(|e| (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(TogetherError {
-            error: format!("Failed to generate signature: {}", e),
-        }),
-    ))?;
+    .map_err(ApiError::internal("Failed to generate signature"))?;
+
+    // Never hand out a signature the ledger doesn't know about
+    let issuance = NewSignatureIssuance {
+        nonce: signatures::format_nonce(nonce),
+        on_behalf_of: req.my_address.clone(),
+        together_with: req.partner_address.clone(),
+        attestation_timestamp: req.timestamp,
+        deadline: deadline as i64,
+        signer_address: signer.address().to_string(),
+        source: SIGNATURE_SOURCE_ATTEST,
+        requester_ip: client_ip.0.map(|ip| ip.to_string()),
+        session_id: session.map(|session| session.session_id),
+        requester: Some(requester),
+    };
+    if let Some(exceeded) = signatures::record_within_quota(&pool, &issuance).await
+        .map_err(ApiError::internal("Failed to record signature issuance"))? {
+        tracing::warn!("Refusing signature for {} and {}: {}", req.my_address, req.partner_address, exceeded.message());
+        return Err(ApiError::SignatureQuotaExceeded(exceeded.message()));
+    }
+
+    if let Some((user_id_1, user_id_2, response_ids)) = proximity_proof
+        && let Err(e) = proximity_db::record_evidence(&pool, user_id_1, user_id_2, req.timestamp, None, &response_ids).await
+    {
+        tracing::warn!("Failed to record proximity evidence for {} and {}: {}", req.my_address, req.partner_address, e);
+    }
 
     // Cache usernames if provided
     if req.my_username.is_some() || req.my_profile_picture_url.is_some() {
@@ -593,6 +681,7 @@ pub async fn attest_together(
 
     // Spawn background task to submit transaction to blockchain
     let bg_config = config.clone();
+    let bg_contract = contract.clone();
     let bg_my_address = my_address;
     let bg_partner_address = partner_address;
     let bg_timestamp = req.timestamp;
@@ -603,6 +692,7 @@ pub async fn attest_together(
     tokio::spawn(async move {
         if let Err(e) = submit_together_transaction_background(
             bg_config,
+            &bg_contract,
             bg_my_address,
             bg_partner_address,
             bg_timestamp,
@@ -621,27 +711,43 @@ pub async fn attest_together(
     }))
 }
 
+/// Enforce the configured proximity policy for two wallets, returning their user IDs and the proof to record
+async fn require_proximity_proof(
+    pool: &PgPool,
+    policy: ProximityPolicy,
+    address_1: &str,
+    address_2: &str,
+) -> Result<Option<(i32, i32, Vec<uuid::Uuid>)>, ApiError> {
+    if policy == ProximityPolicy::Off {
+        return Ok(None);
+    }
+
+    let proof_required = || ApiError::ProximityProofRequired;
+    let internal_error = |e: anyhow::Error| ApiError::internal("Failed to check proximity proof")(e);
+
+    let user_1 = users::get_user_by_wallet_address(pool, address_1).await.map_err(internal_error)?
+        .ok_or_else(proof_required)?;
+    let user_2 = users::get_user_by_wallet_address(pool, address_2).await.map_err(internal_error)?
+        .ok_or_else(proof_required)?;
+
+    let response_ids = proximity::check_proximity(pool, policy, user_1.id, user_2.id).await
+        .map_err(internal_error)?
+        .ok_or_else(proof_required)?;
+
+    Ok(Some((user_1.id, user_2.id, response_ids)))
+}
+
 /// Submit an attestation (typically called by blockchain watcher)
 pub async fn submit_attestation(
-    State((pool, _config)): State<(PgPool, Config)>,
+    State(AppState { pool, .. }): State<AppState>,
     Json(req): Json<SubmitAttestationRequest>,
-) -> Result<Json<SubmitAttestationResponse>, (StatusCode, Json<TogetherError>)> {
+) -> Result<Json<SubmitAttestationResponse>, ApiError> {
     // Validate addresses
     let _addr1: Address = req.address_1.parse()
-        .map_err(|_| (
-            StatusCode::BAD_REQUEST,
-            Json(TogetherError {
-                error: "Invalid address_1 format".to_string(),
-            }),
-        ))?;
+        .map_err(|_| ApiError::InvalidAddress("address_1"))?;
 
     let _addr2: Address = req.address_2.parse()
-        .map_err(|_| (
-            StatusCode::BAD_REQUEST,
-            Json(TogetherError {
-                error: "Invalid address_2 format".to_string(),
-            }),
-        ))?;
+        .map_err(|_| ApiError::InvalidAddress("address_2"))?;
 
     let attestation = attestations::insert_attestation(
         &pool,
@@ -651,15 +757,7 @@ pub async fn submit_attestation(
         req.tx_hash.as_deref(),
         req.block_number,
     ).await
-    .map_err(|e| {
-        tracing::error!("Failed to insert attestation: {}", e);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(TogetherError {
-                error: "Failed to insert attestation".to_string(),
-            }),
-        )
-    })?;
+    .map_err(ApiError::internal("Failed to insert attestation"))?;
 
     // Cache usernames if provided
     if req.username_1.is_some() || req.profile_picture_url_1.is_some() {
@@ -699,7 +797,7 @@ pub async fn submit_attestation(
 
 
 // pub async fn wrap_cast(
-//     State((pool, config)): State<(PgPool, Config)>,
+//     State(AppState { pool, config, .. }): State<AppState>,
 //     Json(req): Json<WrapRequest>,
 // ) -> Result<Json<WrapResponse>, (StatusCode, Json<WrapError>)> {
 //     // Validate wallet address format
@@ -817,8 +915,10 @@ pub async fn submit_attestation(
 //     }))
 // }
 
+#[allow(clippy::too_many_arguments)]
 async fn submit_together_transaction_background(
     config: Config,
+    contract_service: &ContractService,
     my_address: Address,
     partner_address: Address,
     timestamp: i64,
@@ -833,19 +933,12 @@ async fn submit_together_transaction_background(
         timestamp
     );
 
-    // Create contract service
-    let contract_service = ContractService::new(
-        config.rpc_url,
-        config.together_contract_address,
-        config.alchemy_api_key,
-    ).await?;
-
     // Convert timestamp to U256
     let timestamp_u256 = alloy::primitives::U256::from(timestamp as u64);
 
     // Submit the transaction
-    let tx_hash = contract_service.submit_together_transaction(
-        &config.private_key_deployer,
+    let sent = contract_service.submit_together_transaction(
+        config.deployer.backend()?,
         my_address,
         partner_address,
         timestamp_u256,
@@ -856,7 +949,7 @@ async fn submit_together_transaction_background(
 
     tracing::info!(
         "Successfully submitted together transaction with hash: {} for {} and {}",
-        tx_hash,
+        sent.tx_hash,
         my_address,
         partner_address
     );
@@ -0,0 +1,119 @@
+//! Pre-v2 paths, kept so mini-app builds already in the wild keep working
+//!
+//! Only the routes those builds call have a shim; everything added since is v2-only.
+//!
+//! Each shim only adapts the old path (or method, or query) to its `/api/v2` handler;
+//! behaviour changes belong there. Shims are `#[deprecated]`, which marks them
+//! deprecated in the OpenAPI document too.
+#![allow(deprecated)]
+
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Json},
+    Extension,
+};
+use serde_json::Value;
+use crate::{
+    handlers::{
+        self,
+        auth::AuthSession,
+        rpc::RpcQuery,
+        together::{
+            AttestTogetherRequest, CheckTogetherQuery, CreatePendingConnectionRequest, CreateUserRequest,
+            ProfileQuery,
+        },
+    },
+    rate_limit::RateLimiter,
+    utils::client_ip::ClientIp,
+    AppState,
+};
+
+// Profiles
+
+#[deprecated(note = "use GET /api/v2/profiles/{address}")]
+#[utoipa::path(get, path = "/api/profile/{address}", tag = "v1",
+    params(("address" = String, Path, description = "Wallet address"), ProfileQuery),
+    responses((status = 200, description = "See `GET /api/v2/profiles/{address}`")))]
+pub async fn get_profile(state: State<AppState>, address: Path<String>, query: Query<ProfileQuery>) -> impl IntoResponse {
+    handlers::get_profile(state, address, query).await
+}
+
+#[deprecated(note = "use GET /api/v2/profiles/{address}/together/{other_address}")]
+#[utoipa::path(get, path = "/api/check-together/{address}", tag = "v1",
+    params(("address" = String, Path, description = "First wallet address"), CheckTogetherQuery),
+    responses((status = 200, description = "See `GET /api/v2/profiles/{address}/together/{other_address}`")))]
+pub async fn check_together(state: State<AppState>, Path(address): Path<String>, Query(query): Query<CheckTogetherQuery>) -> impl IntoResponse {
+    handlers::check_together(state, Path((address, query.address_2))).await
+}
+
+// Users and connections
+
+#[deprecated(note = "use POST /api/v2/users")]
+#[utoipa::path(get, path = "/api/user/{address}", tag = "v1",
+    params(("address" = String, Path, description = "Wallet address")),
+    responses((status = 200, description = "See `POST /api/v2/users`")))]
+pub async fn get_or_create_user(state: State<AppState>, Path(address): Path<String>) -> impl IntoResponse {
+    handlers::get_or_create_user(state, Json(CreateUserRequest { wallet_address: address })).await
+}
+
+#[deprecated(note = "use POST /api/v2/users/{user_id}/pending-connections")]
+#[utoipa::path(post, path = "/api/user/{user_id}/pending-connection", tag = "v1",
+    params(("user_id" = i32, Path, description = "User id")),
+    request_body = CreatePendingConnectionRequest,
+    responses((status = 200, description = "See `POST /api/v2/users/{user_id}/pending-connections`")),
+    security(("session_token" = [])))]
+pub async fn create_pending_connection(
+    state: State<AppState>,
+    user_id: Path<i32>,
+    session: AuthSession,
+    req: Json<CreatePendingConnectionRequest>,
+) -> impl IntoResponse {
+    handlers::create_pending_connection(state, user_id, session, req).await
+}
+
+#[deprecated(note = "use GET /api/v2/users/{user_id}/pending-connections")]
+#[utoipa::path(get, path = "/api/user/{user_id}/pending-connections", tag = "v1",
+    params(("user_id" = i32, Path, description = "User id")),
+    responses((status = 200, description = "See `GET /api/v2/users/{user_id}/pending-connections`")))]
+pub async fn get_user_pending_connections(state: State<AppState>, user_id: Path<i32>) -> impl IntoResponse {
+    handlers::get_user_pending_connections(state, user_id).await
+}
+
+#[deprecated(note = "use GET /api/v2/users/{user_id}/optimistic-connections")]
+#[utoipa::path(get, path = "/api/user/{user_id}/optimistic-connections", tag = "v1",
+    params(("user_id" = i32, Path, description = "User id")),
+    responses((status = 200, description = "See `GET /api/v2/users/{user_id}/optimistic-connections`")))]
+pub async fn get_user_optimistic_connections(state: State<AppState>, user_id: Path<i32>) -> impl IntoResponse {
+    handlers::get_user_optimistic_connections(state, user_id).await
+}
+
+// Attestations
+
+#[deprecated(note = "use POST /api/v2/signatures")]
+#[utoipa::path(post, path = "/api/attest", tag = "v1", request_body = AttestTogetherRequest,
+    responses((status = 200, description = "See `POST /api/v2/signatures`")))]
+pub async fn attest_together(
+    state: State<AppState>,
+    client_ip: ClientIp,
+    session: Option<AuthSession>,
+    req: Json<AttestTogetherRequest>,
+) -> impl IntoResponse {
+    handlers::attest_together(state, client_ip, session, req).await
+}
+
+// RPC
+
+#[deprecated(note = "use POST /api/v2/rpc")]
+#[utoipa::path(post, path = "/api/rpc", tag = "v1", params(RpcQuery), request_body = Object,
+    responses((status = 200, description = "See `POST /api/v2/rpc`")))]
+pub async fn proxy_rpc(
+    Query(mut query): Query<RpcQuery>,
+    client_ip: ClientIp,
+    state: State<AppState>,
+    limiter: Extension<RateLimiter>,
+    body: axum::extract::Json<Value>,
+) -> impl IntoResponse {
+    // v1 defaulted to Base
+    query.network.get_or_insert_with(|| "base".to_string());
+    handlers::proxy_rpc(Query(query), client_ip, state, limiter, body).await
+}
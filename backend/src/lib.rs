@@ -4,8 +4,16 @@ pub mod services;
 pub mod handlers;
 pub mod utils;
 pub mod constants;
+pub mod audio;
+pub mod proof;
+pub mod render;
+pub mod router;
+pub mod openapi;
+pub mod rate_limit;
+pub mod state;
 
 pub use utils::config::Config;
+pub use state::AppState;
 pub use db::connection::get_db_pool;
 
 // Re-export common types
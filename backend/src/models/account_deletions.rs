@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AccountDeletion {
+    pub id: Uuid,
+    pub user_id: Option<i32>,
+    pub wallet_address: String,
+    pub status: String,
+    pub requested_at: DateTime<Utc>,
+    pub scheduled_for: DateTime<Utc>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
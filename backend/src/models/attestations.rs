@@ -2,17 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
-
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
-pub struct TogetherAttestation {
-    pub id: Uuid,
-    pub address_1: String,
-    pub address_2: String,
-    pub attestation_timestamp: i64,
-    pub tx_hash: Option<String>,
-    pub block_number: Option<i64>,
-    pub created_at: DateTime<Utc>,
-}
+pub use together_types::models::{TogetherAttestation, UserProfile, ConnectionInfo};
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct TogetherCount {
@@ -30,26 +20,6 @@ pub struct WatcherState {
     pub updated_at: DateTime<Utc>,
 }
 
-// DTOs for API responses
-#[derive(Debug, Serialize, Deserialize)]
-pub struct UserProfile {
-    pub address: String,
-    pub username: Option<String>,
-    pub profile_picture_url: Option<String>,
-    pub total_connections: i64,
-    pub recent_connections: Vec<ConnectionInfo>,
-}
-
-#[derive(Debug, Serialize, Deserialize, FromRow)]
-pub struct ConnectionInfo {
-    pub partner_address: String,
-    pub attestation_timestamp: i64,
-    pub tx_hash: Option<String>,
-    pub partner_username: Option<String>,
-    pub connection_strength: Option<i64>, // Number of times connected with this partner
-    pub has_optimistic: Option<bool>, // Whether there are unprocessed optimistic connections
-}
-
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct UsernameCache {
     pub id: uuid::Uuid,
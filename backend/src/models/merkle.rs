@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MerkleSnapshot {
+    pub id: i64,
+    pub root: String,
+    pub leaf_count: i64,
+    pub block_height: i64,
+    pub created_at: DateTime<Utc>,
+}
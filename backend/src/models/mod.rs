@@ -1,5 +1,23 @@
 pub mod attestations;
 pub mod users;
+pub mod sessions;
+pub mod pairing;
+pub mod proximity;
+pub mod signatures;
+pub mod signers;
+pub mod merkle;
+pub mod pairs;
+pub mod account_deletions;
+pub mod webhooks;
 
 pub use attestations::{TogetherAttestation, TogetherCount, UserProfile, ConnectionInfo};
-pub use users::{User, PendingConnection, OptimisticConnection, PendingConnectionMatch};
+pub use users::{User, PendingConnection, OptimisticConnection, PendingConnectionMatch, PendingConnectionResolution, ResolutionAction, ResolutionReason};
+pub use sessions::{AuthNonce, UserSession};
+pub use pairing::PairingCode;
+pub use proximity::{ProximityChallenge, ProximityResponse, ProximityExchange, ProximityEvidence};
+pub use signatures::{SignatureIssuance, NewSignatureIssuance};
+pub use signers::SignerStatus;
+pub use merkle::MerkleSnapshot;
+pub use pairs::{TogetherPair, PairStats, PairCard};
+pub use account_deletions::AccountDeletion;
+pub use webhooks::{WebhookEndpoint, WebhookDelivery, DueWebhookDelivery};
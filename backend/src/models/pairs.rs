@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TogetherPair {
+    pub token_id: String,
+    pub address_1: String,
+    pub address_2: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PairStats {
+    pub first_met: i64,
+    pub last_met: i64,
+    pub strength: i64,
+}
+
+/// Everything shown on a pair's collectible
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairCard {
+    pub token_id: String,
+    pub address_1: String,
+    pub address_2: String,
+    pub username_1: Option<String>,
+    pub username_2: Option<String>,
+    pub first_met: i64,
+    pub last_met: i64,
+    pub strength: i64,
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProximityChallenge {
+    pub id: Uuid,
+    pub issuer_user_id: i32,
+    pub nonce: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProximityResponse {
+    pub id: Uuid,
+    pub challenge_id: Uuid,
+    pub responder_user_id: i32,
+    pub received_at: DateTime<Utc>,
+    pub latency_ms: i64,
+    pub consumed_at: Option<DateTime<Utc>>,
+}
+
+// A response joined with the challenge it answers
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProximityExchange {
+    pub response_id: Uuid,
+    pub challenge_id: Uuid,
+    pub issuer_user_id: i32,
+    pub responder_user_id: i32,
+    pub latency_ms: i64,
+    pub received_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProximityEvidence {
+    pub id: Uuid,
+    pub user_id_1: i32,
+    pub user_id_2: i32,
+    pub attestation_timestamp: i64,
+    pub optimistic_connection_id: Option<Uuid>,
+    pub response_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SignatureIssuance {
+    pub id: Uuid,
+    pub nonce: String,
+    pub on_behalf_of: String,
+    pub together_with: String,
+    pub attestation_timestamp: i64,
+    pub deadline: i64,
+    pub signer_address: String,
+    pub source: String,
+    pub requester_ip: Option<String>,
+    pub session_id: Option<Uuid>,
+    pub requester: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub consumed_at: Option<DateTime<Utc>>,
+    pub consumed_tx_hash: Option<String>,
+}
+
+// Everything needed to record a new issuance
+#[derive(Debug, Clone)]
+pub struct NewSignatureIssuance<'a> {
+    pub nonce: String,
+    pub on_behalf_of: String,
+    pub together_with: String,
+    pub attestation_timestamp: i64,
+    pub deadline: i64,
+    pub signer_address: String,
+    pub source: &'a str,
+    pub requester_ip: Option<String>,
+    pub session_id: Option<Uuid>,
+    /// Quota key for client requests, from [`crate::services::signatures::requester_key`]; `None` for relayed matches
+    pub requester: Option<String>,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SignerStatus {
+    pub address: String,
+    pub allowed: bool,
+    pub tx_hash: String,
+    pub block_number: i64,
+    pub updated_at: DateTime<Utc>,
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
+pub use together_types::models::ResolutionReason;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
@@ -36,3 +37,41 @@ pub struct PendingConnectionMatch {
     pub pending_1: PendingConnection,
     pub pending_2: PendingConnection,
 }
+
+/// How a pending connection was resolved before it matched or expired
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionAction {
+    /// Withdrawn by the sender
+    Cancelled,
+    /// Refused by the recipient, who won't hear from the sender again until `suppressed_until`
+    Declined { suppressed_until: DateTime<Utc> },
+}
+
+impl ResolutionAction {
+    /// Value of `pending_connection_resolutions.action`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResolutionAction::Cancelled => "cancelled",
+            ResolutionAction::Declined { .. } => "declined",
+        }
+    }
+
+    pub fn suppressed_until(&self) -> Option<DateTime<Utc>> {
+        match self {
+            ResolutionAction::Cancelled => None,
+            ResolutionAction::Declined { suppressed_until } => Some(*suppressed_until),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PendingConnectionResolution {
+    pub id: Uuid,
+    pub pending_connection_id: Uuid,
+    pub from_user_id: i32,
+    pub to_user_id: i32,
+    pub action: String,
+    pub reason_code: String,
+    pub suppressed_until: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,50 @@
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+
+/// Top-level OpenAPI document
+///
+/// Paths and schemas aren't listed here: `router::api_routes` adds each route's
+/// `#[utoipa::path]` as it registers the handler, so the spec can't miss a route.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Together API",
+        description = "Backend for the Together mini-app: wallet sessions, pairing, attestations and their proofs."
+    ),
+    modifiers(&SessionTokenAuth),
+    tags(
+        (name = "health", description = "Liveness"),
+        (name = "auth", description = "Wallet sign-in sessions"),
+        (name = "users", description = "User records"),
+        (name = "profiles", description = "Public profiles"),
+        (name = "pairing", description = "Pairing codes, proximity challenges, pending and optimistic connections"),
+        (name = "attestations", description = "Attestation signatures and lookups"),
+        (name = "proofs", description = "Proof certificates, Merkle proofs, verifiable credentials and EAS attestations"),
+        (name = "collectibles", description = "Pair token metadata"),
+        (name = "embeds", description = "Profile cards and badges"),
+        (name = "account", description = "Data export and account deletion"),
+        (name = "rpc", description = "Worldchain JSON-RPC proxy"),
+        (name = "v1", description = "Deprecated pre-v2 paths, kept for clients already in the wild"),
+    )
+)]
+pub struct ApiDoc;
+
+// `session_token`: the bearer token from `POST /api/v2/auth/sessions`
+struct SessionTokenAuth;
+
+impl Modify for SessionTokenAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "session_token",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some("Token from `POST /api/v2/auth/sessions`"))
+                    .build(),
+            ),
+        );
+    }
+}
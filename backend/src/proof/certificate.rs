@@ -0,0 +1,153 @@
+use alloy::{
+    primitives::{Address, B256, Signature, U256},
+    sol_types::{Eip712Domain, SolStruct},
+};
+use anyhow::Result;
+use thiserror::Error;
+use crate::{constants::*, utils::signer::SignerBackend};
+pub use together_types::proof::ProofCertificate;
+
+alloy::sol! {
+    #[derive(Debug, PartialEq, Eq)]
+    struct TogetherProof {
+        address addressOne;
+        address addressTwo;
+        uint256 timestamp;
+        bytes32 txHash;
+        uint256 blockNumber;
+        bytes32 blockHash;
+        uint256 logIndex;
+        address togetherContract;
+        uint256 chainId;
+        uint256 issuedAt;
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ProofError {
+    #[error("unsupported certificate version {0}")]
+    UnsupportedVersion(u8),
+    #[error("malformed signature")]
+    MalformedSignature,
+    #[error("signature was made by {recovered}, not the stated signer {stated}")]
+    SignerMismatch { recovered: Address, stated: Address },
+    #[error("signer {0} is not trusted")]
+    UntrustedSigner(Address),
+}
+
+impl From<&ProofCertificate> for TogetherProof {
+    fn from(certificate: &ProofCertificate) -> Self {
+        TogetherProof {
+            addressOne: certificate.address_1,
+            addressTwo: certificate.address_2,
+            timestamp: U256::from(certificate.timestamp),
+            txHash: certificate.tx_hash,
+            blockNumber: U256::from(certificate.block_number),
+            blockHash: certificate.block_hash,
+            logIndex: U256::from(certificate.log_index),
+            togetherContract: certificate.contract_address,
+            chainId: U256::from(certificate.chain_id),
+            issuedAt: U256::from(certificate.issued_at),
+        }
+    }
+}
+
+/// The EIP-712 digest a certificate's signature covers
+pub fn signing_hash(certificate: &ProofCertificate) -> B256 {
+    TogetherProof::from(certificate).eip712_signing_hash(&proof_domain())
+}
+
+fn proof_domain() -> Eip712Domain {
+    Eip712Domain {
+        name: Some(PROOF_DOMAIN_NAME.into()),
+        version: Some(PROOF_DOMAIN_VERSION.into()),
+        chain_id: Some(U256::from(WORLDCHAIN_MAINNET_CHAIN_ID)),
+        verifying_contract: None,
+        salt: None,
+    }
+}
+
+/// Fill in `signer` and `signature` on an otherwise complete certificate
+pub async fn sign_certificate(mut certificate: ProofCertificate, signer: &dyn SignerBackend) -> Result<ProofCertificate> {
+    certificate.version = PROOF_CERTIFICATE_VERSION;
+    certificate.signer = signer.address();
+    let signature = signer.sign_hash(&signing_hash(&certificate)).await?;
+    certificate.signature = signature.to_string();
+    Ok(certificate)
+}
+
+/// Check a certificate offline against a set of trusted signer addresses
+pub fn verify_certificate(certificate: &ProofCertificate, trusted_signers: &[Address]) -> Result<(), ProofError> {
+    if certificate.version != PROOF_CERTIFICATE_VERSION {
+        return Err(ProofError::UnsupportedVersion(certificate.version));
+    }
+
+    let signature: Signature = certificate.signature.parse()
+        .map_err(|_| ProofError::MalformedSignature)?;
+    let recovered = signature.recover_address_from_prehash(&signing_hash(certificate))
+        .map_err(|_| ProofError::MalformedSignature)?;
+
+    if recovered != certificate.signer {
+        return Err(ProofError::SignerMismatch { recovered, stated: certificate.signer });
+    }
+    if !trusted_signers.contains(&recovered) {
+        return Err(ProofError::UntrustedSigner(recovered));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::signer::InMemorySigner;
+    use alloy::signers::local::PrivateKeySigner;
+
+    fn unsigned() -> ProofCertificate {
+        ProofCertificate {
+            version: PROOF_CERTIFICATE_VERSION,
+            address_1: "0x59888BE579194C701F16a9425f57ECce3906AF4b".parse().unwrap(),
+            address_2: "0xAefC770D8515C552C952a30e597d9fbEa99aA756".parse().unwrap(),
+            timestamp: 1758989464,
+            tx_hash: B256::repeat_byte(0x11),
+            block_number: 19791200,
+            block_hash: B256::repeat_byte(0x22),
+            log_index: 3,
+            contract_address: TOGETHER_CONTRACT_ADDRESS.parse().unwrap(),
+            chain_id: WORLDCHAIN_MAINNET_CHAIN_ID,
+            issued_at: 1759000000,
+            signer: Address::ZERO,
+            signature: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_certificate_round_trip() {
+        let key = PrivateKeySigner::random();
+        let backend = InMemorySigner::from_private_key(&hex::encode(key.to_bytes())).unwrap();
+        let certificate = sign_certificate(unsigned(), &backend).await.unwrap();
+
+        // Survives a trip through JSON
+        let json = serde_json::to_string(&certificate).unwrap();
+        let parsed: ProofCertificate = serde_json::from_str(&json).unwrap();
+        assert_eq!(verify_certificate(&parsed, &[key.address()]), Ok(()));
+
+        assert_eq!(
+            verify_certificate(&parsed, &[PrivateKeySigner::random().address()]),
+            Err(ProofError::UntrustedSigner(key.address()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tampered_certificate_is_rejected() {
+        let key = PrivateKeySigner::random();
+        let backend = InMemorySigner::from_private_key(&hex::encode(key.to_bytes())).unwrap();
+        let mut certificate = sign_certificate(unsigned(), &backend).await.unwrap();
+
+        certificate.timestamp += 1;
+        assert!(matches!(
+            verify_certificate(&certificate, &[key.address()]),
+            Err(ProofError::SignerMismatch { .. })
+        ));
+    }
+}
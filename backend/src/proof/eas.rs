@@ -0,0 +1,258 @@
+use alloy::{
+    primitives::{Address, B256, Signature, U256, keccak256},
+    sol_types::{Eip712Domain, SolStruct, SolValue},
+};
+use anyhow::Result;
+use thiserror::Error;
+use crate::{constants::*, models::TogetherAttestation, utils::signer::SignerBackend};
+pub use together_types::proof::{OffchainAttestation, OffchainAttestationSig, EasDomain, AttestMessage, SignatureParts};
+
+alloy::sol! {
+    /// EAS's offchain attestation type (version 2)
+    #[derive(Debug, PartialEq, Eq)]
+    struct Attest {
+        uint16 version;
+        bytes32 schema;
+        address recipient;
+        uint64 time;
+        uint64 expirationTime;
+        bool revocable;
+        bytes32 refUID;
+        bytes data;
+        bytes32 salt;
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum EasError {
+    #[error("unsupported offchain attestation version {0}")]
+    UnsupportedVersion(u16),
+    #[error("malformed signature")]
+    MalformedSignature,
+    #[error("uid does not match the attestation contents")]
+    UidMismatch,
+    #[error("signature was made by {recovered}, not the stated signer {stated}")]
+    SignerMismatch { recovered: Address, stated: Address },
+}
+
+impl From<&AttestMessage> for Attest {
+    fn from(message: &AttestMessage) -> Self {
+        Attest {
+            version: message.version,
+            schema: message.schema,
+            recipient: message.recipient,
+            time: message.time,
+            expirationTime: message.expiration_time,
+            revocable: message.revocable,
+            refUID: message.ref_uid,
+            data: message.data.clone(),
+            salt: message.salt,
+        }
+    }
+}
+
+fn eip712_domain(domain: &EasDomain) -> Eip712Domain {
+    Eip712Domain {
+        name: Some(domain.name.clone().into()),
+        version: Some(domain.version.clone().into()),
+        chain_id: Some(U256::from(domain.chain_id)),
+        verifying_contract: Some(domain.verifying_contract),
+        salt: None,
+    }
+}
+
+/// UID of `EAS_ATTESTATION_SCHEMA` in the schema registry (no resolver, not revocable)
+///
+/// The schema doesn't have to be registered for offchain attestations to verify, only
+/// for explorers to decode the data by name.
+pub fn schema_uid() -> B256 {
+    let mut packed = EAS_ATTESTATION_SCHEMA.as_bytes().to_vec();
+    packed.extend_from_slice(Address::ZERO.as_slice());
+    packed.push(0);
+    keccak256(packed)
+}
+
+/// The offchain UID, as `getOffchainUID` in the EAS SDK computes it for version 2
+pub fn offchain_uid(message: &AttestMessage) -> B256 {
+    let mut packed = Vec::new();
+    packed.extend_from_slice(&message.version.to_be_bytes());
+    packed.extend_from_slice(message.schema.to_string().as_bytes());
+    packed.extend_from_slice(message.recipient.as_slice());
+    packed.extend_from_slice(Address::ZERO.as_slice());
+    packed.extend_from_slice(&message.time.to_be_bytes());
+    packed.extend_from_slice(&message.expiration_time.to_be_bytes());
+    packed.push(message.revocable as u8);
+    packed.extend_from_slice(message.ref_uid.as_slice());
+    packed.extend_from_slice(&message.data);
+    packed.extend_from_slice(message.salt.as_slice());
+    packed.extend_from_slice(&0u32.to_be_bytes());
+    keccak256(packed)
+}
+
+fn attest_types() -> serde_json::Value {
+    let fields = [
+        ("version", "uint16"),
+        ("schema", "bytes32"),
+        ("recipient", "address"),
+        ("time", "uint64"),
+        ("expirationTime", "uint64"),
+        ("revocable", "bool"),
+        ("refUID", "bytes32"),
+        ("data", "bytes"),
+        ("salt", "bytes32"),
+    ];
+    serde_json::json!({
+        "Attest": fields.iter()
+            .map(|(name, ty)| serde_json::json!({ "name": name, "type": ty }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Map an on-chain attestation onto `EAS_ATTESTATION_SCHEMA` for `recipient`
+///
+/// The salt is derived from the attestation id, so exporting the same row again gives
+/// the same UID. Returns `None` for attestations not yet seen on chain.
+pub fn attest_message(attestation: &TogetherAttestation, recipient: Address) -> Result<Option<AttestMessage>> {
+    let Some(tx_hash) = &attestation.tx_hash else {
+        return Ok(None);
+    };
+    let address_1: Address = attestation.address_1.parse()?;
+    let address_2: Address = attestation.address_2.parse()?;
+    let tx_hash: B256 = tx_hash.parse()?;
+    let timestamp = attestation.attestation_timestamp as u64;
+
+    let data = (address_1, address_2, U256::from(timestamp), tx_hash).abi_encode_params();
+
+    Ok(Some(AttestMessage {
+        version: EAS_OFFCHAIN_ATTESTATION_VERSION,
+        schema: schema_uid(),
+        recipient,
+        time: timestamp,
+        expiration_time: 0,
+        revocable: false,
+        ref_uid: B256::ZERO,
+        data: data.into(),
+        salt: keccak256(attestation.id.as_bytes()),
+    }))
+}
+
+/// Sign an `Attest` message under the EAS domain for `eas_version` on Worldchain
+///
+/// `eas_version` must be what the EAS contract's `version()` returns, since that's the
+/// domain version EAS tools verify against.
+pub async fn sign_offchain_attestation(
+    message: AttestMessage,
+    eas_version: &str,
+    signer: &dyn SignerBackend,
+) -> Result<OffchainAttestation> {
+    let domain = EasDomain {
+        name: EAS_DOMAIN_NAME.to_string(),
+        version: eas_version.to_string(),
+        chain_id: WORLDCHAIN_MAINNET_CHAIN_ID,
+        verifying_contract: EAS_CONTRACT_ADDRESS.parse()?,
+    };
+    let hash = Attest::from(&message).eip712_signing_hash(&eip712_domain(&domain));
+    let signature = signer.sign_hash(&hash).await?;
+
+    Ok(OffchainAttestation {
+        sig: OffchainAttestationSig {
+            domain,
+            primary_type: "Attest".to_string(),
+            types: attest_types(),
+            uid: offchain_uid(&message),
+            message,
+            signature: SignatureParts {
+                v: 27 + signature.v() as u8,
+                r: signature.r().into(),
+                s: signature.s().into(),
+            },
+        },
+        signer: signer.address(),
+    })
+}
+
+/// Check an offchain attestation's UID and signature, returning the signer
+///
+/// Whether the signer is one of ours is up to the caller.
+pub fn verify_offchain_attestation(attestation: &OffchainAttestation) -> Result<Address, EasError> {
+    let sig = &attestation.sig;
+    if sig.message.version != EAS_OFFCHAIN_ATTESTATION_VERSION {
+        return Err(EasError::UnsupportedVersion(sig.message.version));
+    }
+    if offchain_uid(&sig.message) != sig.uid {
+        return Err(EasError::UidMismatch);
+    }
+
+    let parity = match sig.signature.v {
+        27 | 0 => false,
+        28 | 1 => true,
+        _ => return Err(EasError::MalformedSignature),
+    };
+    let signature = Signature::new(sig.signature.r.into(), sig.signature.s.into(), parity);
+    let hash = Attest::from(&sig.message).eip712_signing_hash(&eip712_domain(&sig.domain));
+    let recovered = signature.recover_address_from_prehash(&hash)
+        .map_err(|_| EasError::MalformedSignature)?;
+
+    if recovered != attestation.signer {
+        return Err(EasError::SignerMismatch { recovered, stated: attestation.signer });
+    }
+    Ok(recovered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::signer::InMemorySigner;
+    use alloy::signers::local::PrivateKeySigner;
+    use chrono::Utc;
+
+    fn attestation() -> TogetherAttestation {
+        TogetherAttestation {
+            id: uuid::Uuid::new_v4(),
+            address_1: "0x59888BE579194C701F16a9425f57ECce3906AF4b".to_string(),
+            address_2: "0xAefC770D8515C552C952a30e597d9fbEa99aA756".to_string(),
+            attestation_timestamp: 1758989464,
+            tx_hash: Some(format!("0x{}", "ab".repeat(32))),
+            block_number: Some(19791200),
+            created_at: Utc::now(),
+            block_hash: None,
+            log_index: None,
+        }
+    }
+
+    #[test]
+    fn test_data_matches_schema() {
+        let attestation = attestation();
+        let message = attest_message(&attestation, Address::ZERO).unwrap().unwrap();
+        let (a, b, timestamp, tx_hash) = <(Address, Address, U256, B256)>::abi_decode_params(&message.data).unwrap();
+        assert_eq!(a.to_string(), attestation.address_1);
+        assert_eq!(b.to_string(), attestation.address_2);
+        assert_eq!(timestamp, U256::from(attestation.attestation_timestamp));
+        assert_eq!(tx_hash, B256::repeat_byte(0xab));
+
+        // Re-exporting the same row yields the same UID
+        assert_eq!(offchain_uid(&message), offchain_uid(&attest_message(&attestation, Address::ZERO).unwrap().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_offchain_attestation_round_trip() {
+        let key = PrivateKeySigner::random();
+        let backend = InMemorySigner::from_private_key(&hex::encode(key.to_bytes())).unwrap();
+        let message = attest_message(&attestation(), key.address()).unwrap().unwrap();
+        let signed = sign_offchain_attestation(message, "1.0.1", &backend).await.unwrap();
+
+        let json = serde_json::to_string(&signed).unwrap();
+        assert!(json.contains("\"refUID\""));
+        assert!(json.contains("\"time\":\"1758989464\""));
+        let parsed: OffchainAttestation = serde_json::from_str(&json).unwrap();
+        assert_eq!(verify_offchain_attestation(&parsed), Ok(key.address()));
+
+        let mut tampered = parsed.clone();
+        tampered.sig.message.recipient = Address::with_last_byte(1);
+        assert_eq!(verify_offchain_attestation(&tampered), Err(EasError::UidMismatch));
+
+        let mut tampered = parsed;
+        tampered.sig.domain.version = "0.26".to_string();
+        assert!(matches!(verify_offchain_attestation(&tampered), Err(EasError::SignerMismatch { .. })));
+    }
+}
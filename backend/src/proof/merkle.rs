@@ -0,0 +1,163 @@
+use alloy::{
+    primitives::{keccak256, Address, B256, U256},
+    sol_types::SolValue,
+};
+
+/// Leaf for one attestation: `keccak256(keccak256(abi.encode(low, high, timestamp)))`
+///
+/// Addresses are put in ascending order so a pair has one leaf whichever way round it
+/// was attested. The double hash and sorted-pair node hashing match OpenZeppelin's
+/// `MerkleProof.verify`, so roots can later be anchored and checked on chain.
+pub fn leaf_hash(address_a: Address, address_b: Address, timestamp: u64) -> B256 {
+    let (low, high) = if address_a <= address_b { (address_a, address_b) } else { (address_b, address_a) };
+    keccak256(keccak256((low, high, U256::from(timestamp)).abi_encode()))
+}
+
+fn hash_pair(a: B256, b: B256) -> B256 {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(low.as_slice());
+    bytes[32..].copy_from_slice(high.as_slice());
+    keccak256(bytes)
+}
+
+/// Binary Merkle tree over sorted leaves; an odd node at the end of a layer moves up as is
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    layers: Vec<Vec<B256>>,
+}
+
+impl MerkleTree {
+    pub fn new(mut leaves: Vec<B256>) -> Self {
+        leaves.sort();
+        leaves.dedup();
+
+        let mut layers = vec![leaves];
+        while layers.last().map_or(0, Vec::len) > 1 {
+            let next = layers.last().unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(*a, *b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+
+        Self { layers }
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        self.layers[0].len()
+    }
+
+    /// Root of the tree (zero for an empty tree)
+    pub fn root(&self) -> B256 {
+        self.layers.last().and_then(|layer| layer.first()).copied().unwrap_or(B256::ZERO)
+    }
+
+    /// Position of a leaf among the sorted leaves
+    pub fn leaf_index(&self, leaf: B256) -> Option<usize> {
+        self.layers[0].binary_search(&leaf).ok()
+    }
+
+    /// Every layer, leaves first and the root last
+    pub fn layers(&self) -> &[Vec<B256>] {
+        &self.layers
+    }
+
+    /// Sibling hashes from the leaf up to the root
+    pub fn proof(&self, index: usize) -> Option<Vec<B256>> {
+        let positions = proof_positions(self.leaf_count(), index)?;
+        Some(positions.into_iter().map(|(layer, position)| self.layers[layer][position]).collect())
+    }
+}
+
+/// `(layer, position)` of each sibling on the path from leaf `index` to the root
+///
+/// Depends only on the leaf count, so a stored tree can be proven from these nodes alone.
+pub fn proof_positions(leaf_count: usize, mut index: usize) -> Option<Vec<(usize, usize)>> {
+    if index >= leaf_count {
+        return None;
+    }
+
+    let mut positions = Vec::new();
+    let (mut layer, mut layer_len) = (0, leaf_count);
+    while layer_len > 1 {
+        let sibling = index ^ 1;
+        if sibling < layer_len {
+            positions.push((layer, sibling));
+        }
+        index /= 2;
+        layer += 1;
+        layer_len = layer_len.div_ceil(2);
+    }
+    Some(positions)
+}
+
+/// Check an inclusion proof against a published root
+pub fn verify_proof(root: B256, leaf: B256, proof: &[B256]) -> bool {
+    proof.iter().fold(leaf, |node, sibling| hash_pair(node, *sibling)) == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: u64) -> Vec<B256> {
+        (0..count)
+            .map(|i| leaf_hash(Address::with_last_byte(i as u8), Address::with_last_byte(200), 1_700_000_000 + i))
+            .collect()
+    }
+
+    #[test]
+    fn test_leaf_is_order_independent() {
+        let a = Address::with_last_byte(1);
+        let b = Address::with_last_byte(2);
+        assert_eq!(leaf_hash(a, b, 42), leaf_hash(b, a, 42));
+        assert_ne!(leaf_hash(a, b, 42), leaf_hash(a, b, 43));
+    }
+
+    #[test]
+    fn test_every_leaf_proves_for_all_sizes() {
+        for count in 1..=9 {
+            let leaves = leaves(count);
+            let tree = MerkleTree::new(leaves.clone());
+            assert_eq!(tree.leaf_count(), count as usize);
+
+            for leaf in leaves {
+                let index = tree.leaf_index(leaf).unwrap();
+                let proof = tree.proof(index).unwrap();
+                assert!(verify_proof(tree.root(), leaf, &proof), "leaf {} of {}", index, count);
+            }
+        }
+    }
+
+    #[test]
+    fn test_proof_positions_match_the_tree() {
+        for count in 1..=9 {
+            let tree = MerkleTree::new(leaves(count));
+            assert_eq!(tree.layers().len(), if count == 1 { 1 } else { (count as f64).log2().ceil() as usize + 1 });
+            for index in 0..count as usize {
+                let from_positions: Vec<B256> = proof_positions(count as usize, index).unwrap()
+                    .into_iter()
+                    .map(|(layer, position)| tree.layers()[layer][position])
+                    .collect();
+                assert_eq!(Some(from_positions), tree.proof(index));
+            }
+        }
+        assert_eq!(proof_positions(3, 3), None);
+        assert_eq!(proof_positions(3, 2), Some(vec![(1, 0)]));
+    }
+
+    #[test]
+    fn test_wrong_leaf_does_not_verify() {
+        let tree = MerkleTree::new(leaves(5));
+        let proof = tree.proof(0).unwrap();
+        let outsider = leaf_hash(Address::with_last_byte(99), Address::with_last_byte(98), 1);
+        assert!(!verify_proof(tree.root(), outsider, &proof));
+        assert_eq!(tree.leaf_index(outsider), None);
+        assert_eq!(MerkleTree::new(vec![]).root(), B256::ZERO);
+    }
+}
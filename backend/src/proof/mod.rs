@@ -0,0 +1,9 @@
+pub mod certificate;
+pub mod eas;
+pub mod merkle;
+pub mod vc;
+
+pub use certificate::{ProofCertificate, ProofError, sign_certificate, verify_certificate};
+pub use eas::{OffchainAttestation, EasError, attest_message, sign_offchain_attestation, verify_offchain_attestation};
+pub use merkle::{MerkleTree, leaf_hash, proof_positions, verify_proof};
+pub use vc::{VerifiableCredential, CredentialError, credential_from_attestation, sign_credential, verify_credential};
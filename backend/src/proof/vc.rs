@@ -0,0 +1,258 @@
+use alloy::primitives::{Address, B256, Signature};
+use alloy_dyn_abi::TypedData;
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use serde_json::json;
+use thiserror::Error;
+use crate::{constants::*, models::TogetherAttestation, utils::signer::SignerBackend};
+pub use together_types::proof::{VerifiableCredential, CredentialSubject, CredentialEvidence, CredentialProof};
+
+const CREDENTIALS_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
+const EIP712_SIGNATURE_CONTEXT: &str = "https://w3id.org/security/suites/eip712sig-2021/v1";
+const PROOF_TYPE: &str = "EthereumEip712Signature2021";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CredentialError {
+    #[error("credential has no proof")]
+    MissingProof,
+    #[error("unsupported proof type {0}")]
+    UnsupportedProofType(String),
+    #[error("issuer is not a did:pkh for this chain")]
+    InvalidIssuer,
+    #[error("verification method does not belong to the issuer")]
+    VerificationMethodMismatch,
+    #[error("malformed proof value")]
+    MalformedSignature,
+    #[error("credential could not be encoded as EIP-712: {0}")]
+    Encoding(String),
+    #[error("signature was made by {0}, not the issuer")]
+    IssuerMismatch(Address),
+}
+
+/// `did:pkh` identifier for a wallet on Worldchain
+pub fn did_pkh(address: Address) -> String {
+    format!("did:pkh:eip155:{}:{}", WORLDCHAIN_MAINNET_CHAIN_ID, address)
+}
+
+fn address_from_did_pkh(did: &str) -> Option<Address> {
+    did.strip_prefix(&format!("did:pkh:eip155:{}:", WORLDCHAIN_MAINNET_CHAIN_ID))?
+        .parse()
+        .ok()
+}
+
+fn rfc3339(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Build the unsigned credential for an on-chain attestation
+///
+/// Returns `None` for attestations not yet seen on chain, which have no evidence to point at.
+pub fn credential_from_attestation(
+    attestation: &TogetherAttestation,
+    issuer: Address,
+    contract_address: Address,
+    issued_at: DateTime<Utc>,
+) -> Result<Option<VerifiableCredential>> {
+    let (Some(tx_hash), Some(block_number)) = (&attestation.tx_hash, attestation.block_number) else {
+        return Ok(None);
+    };
+    let address_1: Address = attestation.address_1.parse()?;
+    let address_2: Address = attestation.address_2.parse()?;
+    let met_at = Utc.timestamp_opt(attestation.attestation_timestamp, 0).single()
+        .ok_or_else(|| anyhow::anyhow!("Invalid attestation timestamp {}", attestation.attestation_timestamp))?;
+
+    Ok(Some(VerifiableCredential {
+        context: vec![CREDENTIALS_CONTEXT.to_string(), EIP712_SIGNATURE_CONTEXT.to_string()],
+        credential_type: vec!["VerifiableCredential".to_string(), "TogetherCredential".to_string()],
+        id: format!("urn:uuid:{}", attestation.id),
+        issuer: did_pkh(issuer),
+        issuance_date: rfc3339(issued_at),
+        credential_subject: CredentialSubject {
+            id: did_pkh(address_1),
+            together_with: did_pkh(address_2),
+            met_at: rfc3339(met_at),
+        },
+        evidence: CredentialEvidence {
+            id: format!("eip155:{}:tx:{}", WORLDCHAIN_MAINNET_CHAIN_ID, tx_hash),
+            evidence_type: vec!["TogetherEvent".to_string()],
+            transaction_hash: tx_hash.clone(),
+            block_number: block_number.to_string(),
+            contract_address: contract_address.to_string(),
+        },
+        proof: None,
+    }))
+}
+
+// The EIP-712 description of the credential, embedded in the proof as the suite requires
+fn eip712_descriptor() -> serde_json::Value {
+    json!({
+        "domain": {
+            "name": CREDENTIAL_DOMAIN_NAME,
+            "version": CREDENTIAL_DOMAIN_VERSION,
+            "chainId": WORLDCHAIN_MAINNET_CHAIN_ID,
+        },
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+            ],
+            "VerifiableCredential": [
+                { "name": "@context", "type": "string[]" },
+                { "name": "type", "type": "string[]" },
+                { "name": "id", "type": "string" },
+                { "name": "issuer", "type": "string" },
+                { "name": "issuanceDate", "type": "string" },
+                { "name": "credentialSubject", "type": "CredentialSubject" },
+                { "name": "evidence", "type": "Evidence" },
+                { "name": "proof", "type": "Proof" },
+            ],
+            "CredentialSubject": [
+                { "name": "id", "type": "string" },
+                { "name": "togetherWith", "type": "string" },
+                { "name": "metAt", "type": "string" },
+            ],
+            "Evidence": [
+                { "name": "id", "type": "string" },
+                { "name": "type", "type": "string[]" },
+                { "name": "transactionHash", "type": "string" },
+                { "name": "blockNumber", "type": "string" },
+                { "name": "contractAddress", "type": "string" },
+            ],
+            "Proof": [
+                { "name": "type", "type": "string" },
+                { "name": "created", "type": "string" },
+                { "name": "proofPurpose", "type": "string" },
+                { "name": "verificationMethod", "type": "string" },
+            ],
+        },
+        "primaryType": "VerifiableCredential",
+    })
+}
+
+/// The digest signed by the issuer: the credential plus the proof options, minus `proofValue`
+fn signing_hash(credential: &VerifiableCredential, proof: &CredentialProof) -> Result<B256, CredentialError> {
+    let mut message = serde_json::to_value(credential).map_err(|e| CredentialError::Encoding(e.to_string()))?;
+    message["proof"] = json!({
+        "type": proof.proof_type,
+        "created": proof.created,
+        "proofPurpose": proof.proof_purpose,
+        "verificationMethod": proof.verification_method,
+    });
+
+    let mut typed_data = eip712_descriptor();
+    typed_data["message"] = message;
+    let typed_data: TypedData = serde_json::from_value(typed_data).map_err(|e| CredentialError::Encoding(e.to_string()))?;
+    typed_data.eip712_signing_hash().map_err(|e| CredentialError::Encoding(e.to_string()))
+}
+
+/// Attach an EthereumEip712Signature2021 proof from the issuer's key
+pub async fn sign_credential(mut credential: VerifiableCredential, signer: &dyn SignerBackend) -> Result<VerifiableCredential> {
+    credential.issuer = did_pkh(signer.address());
+    credential.proof = None;
+
+    let mut proof = CredentialProof {
+        proof_type: PROOF_TYPE.to_string(),
+        created: rfc3339(Utc::now()),
+        proof_purpose: "assertionMethod".to_string(),
+        verification_method: format!("{}#blockchainAccountId", credential.issuer),
+        proof_value: String::new(),
+        eip712: eip712_descriptor(),
+    };
+    let hash = signing_hash(&credential, &proof)?;
+    proof.proof_value = signer.sign_hash(&hash).await?.to_string();
+
+    credential.proof = Some(proof);
+    Ok(credential)
+}
+
+/// Verify a credential's proof and return the issuer address it was signed by
+///
+/// The EIP-712 types are always our own, never taken from the embedded descriptor, so a
+/// credential can't redefine what was signed. Whether the issuer is trusted is up to the caller.
+pub fn verify_credential(credential: &VerifiableCredential) -> Result<Address, CredentialError> {
+    let proof = credential.proof.as_ref().ok_or(CredentialError::MissingProof)?;
+    if proof.proof_type != PROOF_TYPE {
+        return Err(CredentialError::UnsupportedProofType(proof.proof_type.clone()));
+    }
+
+    let issuer = address_from_did_pkh(&credential.issuer).ok_or(CredentialError::InvalidIssuer)?;
+    if proof.verification_method.split('#').next() != Some(credential.issuer.as_str()) {
+        return Err(CredentialError::VerificationMethodMismatch);
+    }
+
+    let mut unsigned = credential.clone();
+    unsigned.proof = None;
+    let hash = signing_hash(&unsigned, proof)?;
+
+    let signature: Signature = proof.proof_value.parse().map_err(|_| CredentialError::MalformedSignature)?;
+    let recovered = signature.recover_address_from_prehash(&hash).map_err(|_| CredentialError::MalformedSignature)?;
+    if recovered != issuer {
+        return Err(CredentialError::IssuerMismatch(recovered));
+    }
+
+    Ok(issuer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::signer::InMemorySigner;
+    use alloy::signers::local::PrivateKeySigner;
+
+    fn attestation() -> TogetherAttestation {
+        TogetherAttestation {
+            id: uuid::Uuid::new_v4(),
+            address_1: "0x59888BE579194C701F16a9425f57ECce3906AF4b".to_string(),
+            address_2: "0xAefC770D8515C552C952a30e597d9fbEa99aA756".to_string(),
+            attestation_timestamp: 1758989464,
+            tx_hash: Some(format!("0x{}", "ab".repeat(32))),
+            block_number: Some(19791200),
+            created_at: Utc::now(),
+            block_hash: None,
+            log_index: None,
+        }
+    }
+
+    async fn signed(key: &PrivateKeySigner) -> VerifiableCredential {
+        let backend = InMemorySigner::from_private_key(&hex::encode(key.to_bytes())).unwrap();
+        let contract: Address = TOGETHER_CONTRACT_ADDRESS.parse().unwrap();
+        let credential = credential_from_attestation(&attestation(), backend.address(), contract, Utc::now())
+            .unwrap()
+            .unwrap();
+        sign_credential(credential, &backend).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_credential_round_trip() {
+        let key = PrivateKeySigner::random();
+        let credential = signed(&key).await;
+
+        let json = serde_json::to_string(&credential).unwrap();
+        assert!(json.contains("\"@context\""));
+        assert!(json.contains("EthereumEip712Signature2021"));
+
+        let parsed: VerifiableCredential = serde_json::from_str(&json).unwrap();
+        assert_eq!(verify_credential(&parsed), Ok(key.address()));
+    }
+
+    #[tokio::test]
+    async fn test_tampered_credential_is_rejected() {
+        let key = PrivateKeySigner::random();
+        let mut credential = signed(&key).await;
+        credential.credential_subject.together_with = did_pkh(Address::with_last_byte(1));
+        assert!(matches!(verify_credential(&credential), Err(CredentialError::IssuerMismatch(_))));
+
+        let mut credential = signed(&key).await;
+        credential.issuer = did_pkh(Address::with_last_byte(1));
+        assert_eq!(verify_credential(&credential), Err(CredentialError::VerificationMethodMismatch));
+    }
+
+    #[test]
+    fn test_unconfirmed_attestation_has_no_credential() {
+        let mut pending = attestation();
+        pending.tx_hash = None;
+        let credential = credential_from_attestation(&pending, Address::ZERO, Address::ZERO, Utc::now()).unwrap();
+        assert!(credential.is_none());
+    }
+}
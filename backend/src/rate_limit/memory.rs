@@ -0,0 +1,112 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use crate::constants::{RATE_LIMIT_MAX_KEYS, RATE_LIMIT_SWEEP_INTERVAL_SECS};
+use super::{Decision, Policy, RateLimitStore};
+
+/// Per-process store; limits aren't shared between replicas
+///
+/// Bounded: recovered keys are swept periodically, and past `max_keys` the keys
+/// closest to recovering are dropped, which only ever errs towards allowing.
+#[derive(Debug)]
+pub struct MemoryStore {
+    epoch: Instant,
+    max_keys: usize,
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    tats: HashMap<String, Duration>,
+    last_sweep: Duration,
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::with_max_keys(RATE_LIMIT_MAX_KEYS)
+    }
+
+    pub fn with_max_keys(max_keys: usize) -> Self {
+        Self {
+            epoch: Instant::now(),
+            max_keys: max_keys.max(1),
+            state: Mutex::new(MemoryState::default()),
+        }
+    }
+
+    fn check_at(&self, key: &str, policy: &Policy, cost: u32, now: Duration) -> Decision {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let sweep_due = now.saturating_sub(state.last_sweep) >= Duration::from_secs(RATE_LIMIT_SWEEP_INTERVAL_SECS);
+        let full = state.tats.len() >= self.max_keys && !state.tats.contains_key(key);
+        if sweep_due || full {
+            state.tats.retain(|_, tat| *tat > now);
+            state.last_sweep = now;
+        }
+        if state.tats.len() >= self.max_keys && !state.tats.contains_key(key) {
+            // Keep the half furthest from recovering
+            let mut tats: Vec<Duration> = state.tats.values().copied().collect();
+            let cut = (tats.len() - self.max_keys / 2).min(tats.len() - 1);
+            let (_, threshold, _) = tats.select_nth_unstable(cut);
+            let threshold = *threshold;
+            state.tats.retain(|_, tat| *tat > threshold);
+        }
+
+        match policy.step_n(state.tats.get(key).copied(), now, cost) {
+            Ok(tat) => {
+                state.tats.insert(key.to_string(), tat);
+                Decision::Allow
+            }
+            Err(retry_after) => Decision::Deny { retry_after },
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryStore {
+    async fn check_n(&self, key: &str, policy: &Policy, cost: u32) -> Result<Decision> {
+        Ok(self.check_at(key, policy, cost, self.epoch.elapsed()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: Policy = Policy { name: "test", limit: 2, period: Duration::from_secs(60) };
+
+    #[test]
+    fn test_keys_are_limited_independently() {
+        let store = MemoryStore::new();
+        let now = Duration::from_secs(1);
+        assert_eq!(store.check_at("a", &POLICY, 1, now), Decision::Allow);
+        assert_eq!(store.check_at("a", &POLICY, 1, now), Decision::Allow);
+        assert_eq!(store.check_at("a", &POLICY, 1, now), Decision::Deny { retry_after: Duration::from_secs(30) });
+        assert_eq!(store.check_at("b", &POLICY, 1, now), Decision::Allow);
+    }
+
+    #[test]
+    fn test_store_stays_bounded() {
+        let store = MemoryStore::with_max_keys(10);
+        let now = Duration::from_secs(1);
+        for i in 0..100 {
+            assert_eq!(store.check_at(&format!("ip:{}", i), &POLICY, 1, now + Duration::from_millis(i)), Decision::Allow);
+        }
+        assert!(store.state.lock().unwrap().tats.len() <= 10);
+
+        // Recovered keys are swept once the interval passes
+        let later = now + Duration::from_secs(RATE_LIMIT_SWEEP_INTERVAL_SECS + 60);
+        store.check_at("fresh", &POLICY, 1, later);
+        assert_eq!(store.state.lock().unwrap().tats.len(), 1);
+    }
+}
@@ -0,0 +1,321 @@
+//! Rate limiting as a tower layer with pluggable storage
+//!
+//! Limits use GCRA (the generic cell rate algorithm): a key's whole state is one
+//! timestamp, its theoretical arrival time (TAT). A request is allowed while the TAT is
+//! at most `period - period / limit` ahead of now, and each allowed request pushes the
+//! TAT forward by `period / limit`. Once the TAT is in the past the key carries no state,
+//! which is what lets both stores evict freely.
+
+mod memory;
+mod postgres;
+
+pub use memory::MemoryStore;
+pub use postgres::PostgresStore;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use axum::{
+    extract::Request,
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use sqlx::PgPool;
+use std::{
+    convert::Infallible,
+    fmt,
+    future::Future,
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tower::{Layer, Service};
+use crate::{
+    constants::*,
+    handlers::{auth::AuthSession, error::ApiError},
+    utils::client_ip::ClientIp,
+};
+
+/// How many requests a key may make per period; bursts of up to `limit` are allowed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Policy {
+    /// Prefix for this policy's keys, so policies never share a budget
+    pub name: &'static str,
+    pub limit: u32,
+    pub period: Duration,
+}
+
+pub const RPC: Policy = Policy::per_minute("rpc", RPC_RATE_LIMIT_PER_MINUTE);
+pub const AUTH: Policy = Policy::per_minute("auth", AUTH_RATE_LIMIT_PER_MINUTE);
+pub const ATTEST: Policy = Policy::per_minute("attest", ATTEST_RATE_LIMIT_PER_MINUTE);
+pub const PENDING_CONNECTION: Policy = Policy::per_minute("pending_connection", PENDING_CONNECTION_RATE_LIMIT_PER_MINUTE);
+pub const PENDING_CONNECTION_IP: Policy = Policy::per_minute("pending_connection_ip", PENDING_CONNECTION_IP_RATE_LIMIT_PER_MINUTE);
+pub const PROOF_EXPORT: Policy = Policy::per_minute("proof_export", PROOF_EXPORT_RATE_LIMIT_PER_MINUTE);
+
+impl Policy {
+    pub const fn per_minute(name: &'static str, limit: u32) -> Self {
+        Self { name, limit, period: Duration::from_secs(RATE_LIMIT_WINDOW_SECONDS) }
+    }
+
+    /// Spacing between requests at the sustained rate
+    pub fn emission_interval(&self) -> Duration {
+        self.period / self.limit.max(1)
+    }
+
+    /// How far ahead of now the TAT may be before requests are refused
+    pub fn tolerance(&self) -> Duration {
+        self.period.saturating_sub(self.emission_interval())
+    }
+
+    /// One GCRA step: the key's next TAT if allowed, otherwise how long until it would be
+    ///
+    /// Times are offsets from any fixed epoch; `tat` is `None` for an unseen key.
+    pub fn step(&self, tat: Option<Duration>, now: Duration) -> Result<Duration, Duration> {
+        self.step_n(tat, now, 1)
+    }
+
+    /// [`Policy::step`] for `cost` requests at once: all of them are allowed or none are
+    ///
+    /// A cost above `limit` can never fit and is refused with a full period's wait.
+    pub fn step_n(&self, tat: Option<Duration>, now: Duration, cost: u32) -> Result<Duration, Duration> {
+        let Some(tolerance) = self.tolerance_for(cost) else {
+            return Err(self.period);
+        };
+        let tat = tat.unwrap_or(now).max(now);
+        match (tat - now).checked_sub(tolerance) {
+            Some(wait) if !wait.is_zero() => Err(wait),
+            _ => Ok(tat + self.emission_interval() * cost),
+        }
+    }
+
+    /// How far ahead of now the TAT may be for `cost` requests to fit, if they ever can
+    pub fn tolerance_for(&self, cost: u32) -> Option<Duration> {
+        if cost == 0 || cost > self.limit.max(1) {
+            return None;
+        }
+        self.tolerance().checked_sub(self.emission_interval() * (cost - 1))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    Deny { retry_after: Duration },
+}
+
+/// Where limit state lives; one store serves every policy
+#[async_trait]
+pub trait RateLimitStore: Send + Sync + fmt::Debug {
+    /// Count `cost` requests for `key` under `policy`, all or none
+    async fn check_n(&self, key: &str, policy: &Policy, cost: u32) -> Result<Decision>;
+
+    /// Count one request for `key` under `policy`
+    async fn check(&self, key: &str, policy: &Policy) -> Result<Decision> {
+        self.check_n(key, policy, 1).await
+    }
+}
+
+/// Which store backs the HTTP limits, from `RATE_LIMIT_STORE`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StoreKind {
+    /// Per process; fine for a single replica
+    #[default]
+    Memory,
+    /// Shared by every replica pointed at the same database
+    Postgres,
+}
+
+impl FromStr for StoreKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "memory" | "" => Ok(StoreKind::Memory),
+            "postgres" => Ok(StoreKind::Postgres),
+            other => Err(anyhow::anyhow!("Unknown rate limit store: {}", other)),
+        }
+    }
+}
+
+/// What a route's requests are counted against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyBy {
+    /// The client IP
+    Ip,
+    /// The signed-in wallet, or the client IP for anonymous requests
+    Wallet,
+}
+
+/// The store plus what the layer needs to build keys; added to every request as an extension
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    store: Arc<dyn RateLimitStore>,
+    pool: PgPool,
+}
+
+impl RateLimiter {
+    pub fn new(kind: StoreKind, pool: PgPool) -> Self {
+        let store: Arc<dyn RateLimitStore> = match kind {
+            StoreKind::Memory => Arc::new(MemoryStore::new()),
+            StoreKind::Postgres => Arc::new(PostgresStore::new(pool.clone())),
+        };
+        Self { store, pool }
+    }
+
+    pub fn store(&self) -> &Arc<dyn RateLimitStore> {
+        &self.store
+    }
+
+    /// Count `cost` more requests from `client_ip` under `policy`, for a handler whose one
+    /// request does the work of several (a JSON-RPC batch)
+    ///
+    /// Like the layer, a failing store lets the request through.
+    pub async fn charge_ip(&self, policy: &Policy, client_ip: &ClientIp, cost: u32) -> Decision {
+        let key = Self::ip_key(policy, client_ip);
+        self.store.check_n(&key, policy, cost).await.unwrap_or_else(|e| {
+            tracing::warn!("Rate limit store failed, allowing {}: {:#}", key, e);
+            Decision::Allow
+        })
+    }
+
+    fn ip_key(policy: &Policy, client_ip: &ClientIp) -> String {
+        format!("{}:ip:{}", policy.name, client_ip)
+    }
+
+    async fn key(&self, policy: &Policy, key_by: KeyBy, parts: &mut Parts) -> String {
+        let client_ip = ClientIp::from_parts(parts);
+        match key_by {
+            KeyBy::Ip => Self::ip_key(policy, &client_ip),
+            // A bad token falls back to the IP; the handler rejects it anyway. The session
+            // is left in the request's extensions for the next layer and the handler
+            KeyBy::Wallet => match AuthSession::from_parts(parts, &self.pool).await {
+                Ok(Some(session)) => format!("{}:wallet:{}", policy.name, session.wallet_address.to_lowercase()),
+                _ => Self::ip_key(policy, &client_ip),
+            },
+        }
+    }
+}
+
+/// Applies `policy` to the routes it wraps, answering `429 RATE_LIMITED` once a key's budget is spent
+///
+/// Needs a [`RateLimiter`] request extension, which `create_router` adds. If the store
+/// errors the request is let through: an outage there shouldn't take the API down too.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitLayer {
+    policy: Policy,
+    key_by: KeyBy,
+}
+
+impl RateLimitLayer {
+    pub fn new(policy: Policy, key_by: KeyBy) -> Self {
+        Self { policy, key_by }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService { inner, policy: self.policy, key_by: self.key_by }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    policy: Policy,
+    key_by: KeyBy,
+}
+
+impl<S> Service<Request> for RateLimitService<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // The clone may not be ready; keep the one `poll_ready` was called on
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let (policy, key_by) = (self.policy, self.key_by);
+
+        Box::pin(async move {
+            let Some(limiter) = request.extensions().get::<RateLimiter>().cloned() else {
+                tracing::error!("No RateLimiter extension, {} limit not applied", policy.name);
+                return inner.call(request).await;
+            };
+
+            let (mut parts, body) = request.into_parts();
+            let key = limiter.key(&policy, key_by, &mut parts).await;
+            match limiter.store.check(&key, &policy).await {
+                Ok(Decision::Allow) => {}
+                Ok(Decision::Deny { retry_after }) => {
+                    return Ok(ApiError::RateLimited(retry_after).into_response());
+                }
+                Err(e) => tracing::warn!("Rate limit store failed, allowing {}: {:#}", key, e),
+            }
+
+            inner.call(Request::from_parts(parts, body)).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn test_step_allows_a_burst_then_the_sustained_rate() {
+        let policy = Policy { name: "test", limit: 3, period: Duration::from_secs(3) };
+        let now = Duration::from_secs(100);
+
+        let mut tat = None;
+        for _ in 0..3 {
+            tat = Some(policy.step(tat, now).unwrap());
+        }
+        assert_eq!(policy.step(tat, now), Err(SECOND));
+
+        // One emission interval later exactly one more request fits
+        let later = now + SECOND;
+        let next = policy.step(tat, later).unwrap();
+        assert_eq!(policy.step(Some(next), later), Err(SECOND));
+    }
+
+    #[test]
+    fn test_step_n_takes_all_or_nothing() {
+        let policy = Policy { name: "test", limit: 4, period: Duration::from_secs(4) };
+        let now = Duration::from_secs(100);
+
+        let tat = policy.step_n(None, now, 3).unwrap();
+        assert_eq!(tat, now + 3 * SECOND);
+        // One request is left, so a batch of two waits for a second one to recover
+        assert_eq!(policy.step_n(Some(tat), now, 2), Err(SECOND));
+        assert!(policy.step_n(Some(tat), now, 1).is_ok());
+        assert_eq!(policy.step_n(None, now, 5), Err(policy.period));
+        assert_eq!(policy.step_n(None, now, 1), policy.step(None, now));
+    }
+
+    #[test]
+    fn test_step_forgets_idle_keys() {
+        let policy = Policy { name: "test", limit: 2, period: Duration::from_secs(10) };
+        let stale = Some(Duration::from_secs(5));
+        assert_eq!(policy.step(stale, Duration::from_secs(60)), policy.step(None, Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_store_kind_from_str() {
+        assert_eq!("".parse::<StoreKind>().unwrap(), StoreKind::Memory);
+        assert_eq!("Postgres".parse::<StoreKind>().unwrap(), StoreKind::Postgres);
+        assert!("redis".parse::<StoreKind>().is_err());
+    }
+}
@@ -0,0 +1,58 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::{sync::Mutex, time::Instant};
+use crate::{constants::RATE_LIMIT_SWEEP_INTERVAL_SECS, db::rate_limits};
+use super::{Decision, Policy, RateLimitStore};
+
+/// Store in the `rate_limits` table, so every replica on the database shares one budget
+///
+/// Time comes from the database clock, so replicas with skewed clocks still agree.
+#[derive(Debug)]
+pub struct PostgresStore {
+    pool: PgPool,
+    last_sweep: Mutex<Instant>,
+}
+
+impl PostgresStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            last_sweep: Mutex::new(Instant::now()),
+        }
+    }
+
+    // One replica sweeping per interval is plenty, but any of them may
+    async fn sweep_if_due(&self) {
+        let mut last_sweep = self.last_sweep.lock().await;
+        if last_sweep.elapsed() < Duration::from_secs(RATE_LIMIT_SWEEP_INTERVAL_SECS) {
+            return;
+        }
+        *last_sweep = Instant::now();
+        drop(last_sweep);
+
+        if let Err(e) = rate_limits::delete_expired(&self.pool).await {
+            tracing::warn!("Failed to sweep rate limits: {:#}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for PostgresStore {
+    async fn check_n(&self, key: &str, policy: &Policy, cost: u32) -> Result<Decision> {
+        self.sweep_if_due().await;
+
+        let Some(tolerance) = policy.tolerance_for(cost) else {
+            return Ok(Decision::Deny { retry_after: policy.period });
+        };
+        let advance_ms = (policy.emission_interval() * cost).as_millis() as i64;
+        let tolerance_ms = tolerance.as_millis() as i64;
+        if rate_limits::try_acquire(&self.pool, key, advance_ms, tolerance_ms).await? {
+            return Ok(Decision::Allow);
+        }
+
+        let wait_ms = rate_limits::retry_after_ms(&self.pool, key, tolerance_ms).await?;
+        Ok(Decision::Deny { retry_after: Duration::from_millis(wait_ms.max(0) as u64) })
+    }
+}
@@ -0,0 +1,89 @@
+use super::escape_xml;
+pub use together_types::badge::BadgeStyle;
+
+const LABEL_COLOR: &str = "#555";
+const MESSAGE_COLOR: &str = "#8b5cf6";
+
+/// Approximate rendered width of `text` in Verdana 11px, close enough to size badges
+fn text_width(text: &str) -> f64 {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | 'I' | '.' | ',' | ':' | ';' | '|' | '!' | '\'' | '·' | ' ' => 3.9,
+            'f' | 'r' | 't' => 4.9,
+            'm' | 'w' | 'M' | 'W' => 10.0,
+            c if c.is_ascii_uppercase() => 7.6,
+            c if c.is_ascii_digit() => 7.0,
+            _ => 6.6,
+        })
+        .sum()
+}
+
+/// A shields.io-style two-part badge, e.g. `together | 12`
+pub fn badge_svg(label: &str, message: &str, style: BadgeStyle) -> String {
+    let (label, message) = match style {
+        BadgeStyle::ForTheBadge => (label.to_uppercase(), message.to_uppercase()),
+        _ => (label.to_string(), message.to_string()),
+    };
+    let (height, font_size, padding, letter_spacing, scale) = match style {
+        BadgeStyle::ForTheBadge => (28.0, 10.0, 12.0, 1.25, 10.0 / 11.0),
+        _ => (20.0, 11.0, 6.0, 0.0, 1.0),
+    };
+    let width_of = |text: &str| {
+        (text_width(text) * scale + letter_spacing * text.chars().count() as f64 + 2.0 * padding).round()
+    };
+    let label_width = width_of(&label);
+    let message_width = width_of(&message);
+    let total_width = label_width + message_width;
+
+    let title = escape_xml(&format!("{}: {}", label, message));
+    let (label, message) = (escape_xml(&label), escape_xml(&message));
+    let text_y = if style == BadgeStyle::ForTheBadge { 18.0 } else { 14.0 };
+    let weight = if style == BadgeStyle::ForTheBadge { r#" font-weight="bold""# } else { "" };
+
+    let (radius, gloss) = match style {
+        BadgeStyle::Flat => (
+            3.0,
+            format!(
+                r##"<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient><rect width="{total_width}" height="{height}" fill="url(#s)"/>"##
+            ),
+        ),
+        _ => (0.0, String::new()),
+    };
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{total_width}" height="{height}" role="img" aria-label="{title}"><title>{title}</title><clipPath id="r"><rect width="{total_width}" height="{height}" rx="{radius}" fill="#fff"/></clipPath><g clip-path="url(#r)"><rect width="{label_width}" height="{height}" fill="{LABEL_COLOR}"/><rect x="{label_width}" width="{message_width}" height="{height}" fill="{MESSAGE_COLOR}"/>{gloss}</g><g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="{font_size}" letter-spacing="{letter_spacing}"{weight}><text x="{label_x}" y="{text_y}">{label}</text><text x="{message_x}" y="{text_y}">{message}</text></g></svg>"##,
+        label_x = label_width / 2.0,
+        message_x = label_width + message_width / 2.0,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_badge_styles() {
+        let flat = badge_svg("together", "12", BadgeStyle::Flat);
+        assert!(flat.contains(r#"aria-label="together: 12""#));
+        assert!(flat.contains(r#"rx="3""#));
+        assert!(flat.contains("linearGradient"));
+
+        let square = badge_svg("together", "12", BadgeStyle::FlatSquare);
+        assert!(square.contains(r#"rx="0""#));
+        assert!(!square.contains("linearGradient"));
+
+        let big = badge_svg("together", "12", BadgeStyle::ForTheBadge);
+        assert!(big.contains(r#"height="28""#));
+        assert!(big.contains(">TOGETHER<"));
+    }
+
+    #[test]
+    fn test_badge_escapes_label_and_grows_with_it() {
+        let short = badge_svg("a", "1", BadgeStyle::Flat);
+        let long = badge_svg("<meetup & friends>", "1", BadgeStyle::Flat);
+        assert!(long.contains("&lt;meetup &amp; friends&gt;"));
+
+        let width = |svg: &str| svg.split("width=\"").nth(1).unwrap().split('"').next().unwrap().parse::<f64>().unwrap();
+        assert!(width(&long) > width(&short));
+    }
+}
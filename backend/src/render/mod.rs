@@ -0,0 +1,58 @@
+pub mod badge;
+pub mod pair;
+pub mod profile_card;
+pub mod raster;
+
+pub use badge::{badge_svg, BadgeStyle};
+pub use pair::pair_svg;
+pub use profile_card::profile_card_svg;
+pub use raster::svg_to_png;
+
+use chrono::{TimeZone, Utc};
+
+/// Escape text for use inside SVG elements and attributes
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// `0x1234…abcd` form of an address
+pub fn short_address(address: &str) -> String {
+    if address.len() <= 12 {
+        return address.to_string();
+    }
+    format!("{}…{}", &address[..6], &address[address.len() - 4..])
+}
+
+/// Username if cached, else the shortened address
+pub fn display_name(username: Option<&str>, address: &str) -> String {
+    match username {
+        Some(username) if !username.is_empty() => username.to_string(),
+        _ => short_address(address),
+    }
+}
+
+/// `YYYY-MM-DD` for a unix timestamp
+pub fn format_date(timestamp: i64) -> String {
+    Utc.timestamp_opt(timestamp, 0)
+        .single()
+        .map(|at| at.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// A stable hue (0-359) for an address, so each wallet keeps its colour across images
+pub fn address_hue(address: &str) -> u16 {
+    let digits = address.trim_start_matches("0x");
+    let seed = u32::from_str_radix(digits.get(..6).unwrap_or_default(), 16).unwrap_or(0);
+    (seed % 360) as u16
+}
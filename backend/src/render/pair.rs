@@ -0,0 +1,55 @@
+use crate::models::PairCard;
+use super::{address_hue, display_name, escape_xml, format_date};
+
+/// Square SVG image for a pair's collectible
+pub fn pair_svg(card: &PairCard) -> String {
+    let name_1 = escape_xml(&display_name(card.username_1.as_deref(), &card.address_1));
+    let name_2 = escape_xml(&display_name(card.username_2.as_deref(), &card.address_2));
+    let hue_1 = address_hue(&card.address_1);
+    let hue_2 = address_hue(&card.address_2);
+    let first_met = format_date(card.first_met);
+    let last_met = format_date(card.last_met);
+    let times = if card.strength == 1 { "time" } else { "times" };
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="500" height="500" viewBox="0 0 500 500">
+<defs><linearGradient id="bg" x1="0" y1="0" x2="1" y2="1"><stop offset="0" stop-color="hsl({hue_1},70%,45%)"/><stop offset="1" stop-color="hsl({hue_2},70%,45%)"/></linearGradient></defs>
+<rect width="500" height="500" rx="24" fill="url(#bg)"/>
+<circle cx="200" cy="170" r="70" fill="hsl({hue_1},80%,75%)" fill-opacity="0.85"/>
+<circle cx="300" cy="170" r="70" fill="hsl({hue_2},80%,75%)" fill-opacity="0.85"/>
+<g font-family="Helvetica, Arial, sans-serif" fill="#ffffff" text-anchor="middle">
+<text x="250" y="300" font-size="30" font-weight="bold">{name_1}</text>
+<text x="250" y="334" font-size="20">&amp;</text>
+<text x="250" y="368" font-size="30" font-weight="bold">{name_2}</text>
+<text x="250" y="420" font-size="18">Together {strength} {times}</text>
+<text x="250" y="448" font-size="16" fill-opacity="0.8">First met {first_met} · Last met {last_met}</text>
+</g>
+</svg>"##,
+        strength = card.strength,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pair_svg_escapes_usernames() {
+        let card = PairCard {
+            token_id: "1".to_string(),
+            address_1: "0x59888BE579194C701F16a9425f57ECce3906AF4b".to_string(),
+            address_2: "0xAefC770D8515C552C952a30e597d9fbEa99aA756".to_string(),
+            username_1: Some("<script>".to_string()),
+            username_2: None,
+            first_met: 1758989464,
+            last_met: 1759989464,
+            strength: 3,
+        };
+        let svg = pair_svg(&card);
+        assert!(svg.contains("&lt;script&gt;"));
+        assert!(!svg.contains("<script>"));
+        assert!(svg.contains("0xAefC…A756"));
+        assert!(svg.contains("First met 2025-09-27"));
+        assert!(svg.contains("Together 3 times"));
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use qrcode::{Color, QrCode};
+use crate::{constants::*, models::UserProfile};
+use super::{address_hue, display_name, escape_xml, short_address};
+
+pub const CARD_WIDTH: u32 = 1200;
+pub const CARD_HEIGHT: u32 = 630;
+
+// Side of the QR code's white backing square, and the quiet zone inside it in modules
+const QR_SIZE: f64 = 330.0;
+const QR_QUIET_ZONE: usize = 2;
+
+/// SVG path drawing the dark modules of a QR code for `data`, scaled to `size`
+fn qr_path(data: &str, size: f64) -> Result<String> {
+    let code = QrCode::new(data.as_bytes())?;
+    let width = code.width();
+    let scale = size / (width + 2 * QR_QUIET_ZONE) as f64;
+
+    let mut path = String::new();
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color == Color::Dark {
+            let x = (i % width + QR_QUIET_ZONE) as f64 * scale;
+            let y = (i / width + QR_QUIET_ZONE) as f64 * scale;
+            path.push_str(&format!("M{:.2},{:.2}h{:.2}v{:.2}h-{:.2}z", x, y, scale, scale, scale));
+        }
+    }
+    Ok(path)
+}
+
+/// Open Graph sized profile card: username, connection count, top partners and a QR
+/// code linking to `profile_url`
+pub fn profile_card_svg(profile: &UserProfile, profile_url: &str) -> Result<String> {
+    let hue = address_hue(&profile.address);
+    let name = escape_xml(&display_name(profile.username.as_deref(), &profile.address));
+    let address = escape_xml(&short_address(&profile.address));
+    let connections = if profile.total_connections == 1 { "connection" } else { "connections" };
+
+    let mut partners: Vec<_> = profile.recent_connections.iter().collect();
+    partners.sort_by_key(|c| std::cmp::Reverse(c.connection_strength.unwrap_or(0)));
+    let partner_rows: String = partners.iter()
+        .take(PROFILE_CARD_TOP_PARTNERS)
+        .enumerate()
+        .map(|(i, c)| format!(
+            r#"<text x="80" y="{y}" font-size="30">{name} <tspan fill-opacity="0.7">×{strength}</tspan></text>"#,
+            y = 430 + i as u32 * 46,
+            name = escape_xml(&display_name(c.partner_username.as_deref(), &c.partner_address)),
+            strength = c.connection_strength.unwrap_or(1),
+        ))
+        .collect();
+    let partners_heading = if partner_rows.is_empty() {
+        ""
+    } else {
+        r#"<text x="80" y="380" font-size="24" fill-opacity="0.7" letter-spacing="2">TOP PARTNERS</text>"#
+    };
+
+    let qr_x = CARD_WIDTH as f64 - QR_SIZE - 80.0;
+    let qr_y = (CARD_HEIGHT as f64 - QR_SIZE) / 2.0;
+    let qr = qr_path(profile_url, QR_SIZE)?;
+
+    Ok(format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{CARD_WIDTH}" height="{CARD_HEIGHT}" viewBox="0 0 {CARD_WIDTH} {CARD_HEIGHT}">
+<defs><linearGradient id="bg" x1="0" y1="0" x2="1" y2="1"><stop offset="0" stop-color="hsl({hue},65%,40%)"/><stop offset="1" stop-color="hsl({hue2},65%,30%)"/></linearGradient></defs>
+<rect width="{CARD_WIDTH}" height="{CARD_HEIGHT}" fill="url(#bg)"/>
+<g font-family="Helvetica, Arial, sans-serif" fill="#ffffff">
+<text x="80" y="150" font-size="64" font-weight="bold">{name}</text>
+<text x="80" y="200" font-size="28" fill-opacity="0.7">{address}</text>
+<text x="80" y="290" font-size="44">Together with <tspan font-weight="bold">{total}</tspan> {connections}</text>
+{partners_heading}
+{partner_rows}
+</g>
+<rect x="{qr_x}" y="{qr_y}" width="{QR_SIZE}" height="{QR_SIZE}" rx="16" fill="#ffffff"/>
+<path transform="translate({qr_x},{qr_y})" d="{qr}" fill="#111111"/>
+</svg>"##,
+        hue2 = (hue + 40) % 360,
+        total = profile.total_connections,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ConnectionInfo;
+
+    fn connection(partner: &str, username: Option<&str>, strength: i64) -> ConnectionInfo {
+        ConnectionInfo {
+            partner_address: partner.to_string(),
+            attestation_timestamp: 1758989464,
+            tx_hash: None,
+            partner_username: username.map(str::to_string),
+            connection_strength: Some(strength),
+            has_optimistic: Some(false),
+        }
+    }
+
+    #[test]
+    fn test_profile_card_lists_strongest_partners() {
+        let profile = UserProfile {
+            address: "0x59888BE579194C701F16a9425f57ECce3906AF4b".to_string(),
+            username: Some("alice & co".to_string()),
+            profile_picture_url: None,
+            total_connections: 9,
+            recent_connections: vec![
+                connection("0x0000000000000000000000000000000000000001", Some("weak"), 1),
+                connection("0x0000000000000000000000000000000000000002", Some("strong"), 5),
+                connection("0xAefC770D8515C552C952a30e597d9fbEa99aA756", None, 3),
+                connection("0x0000000000000000000000000000000000000003", Some("weakest"), 1),
+            ],
+        };
+        let svg = profile_card_svg(&profile, "https://example.com/profile?address=0x59888BE579194C701F16a9425f57ECce3906AF4b").unwrap();
+
+        assert!(svg.contains("alice &amp; co"));
+        assert!(svg.contains("Together with <tspan font-weight=\"bold\">9</tspan> connections"));
+        let strong = svg.find("strong").unwrap();
+        let middle = svg.find("0xAefC…A756").unwrap();
+        assert!(strong < middle);
+        assert!(!svg.contains("weakest"));
+        assert!(svg.contains("<path transform"));
+    }
+}
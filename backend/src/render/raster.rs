@@ -0,0 +1,45 @@
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use resvg::{tiny_skia, usvg};
+use std::sync::Arc;
+
+// Loading system fonts is slow, so it happens once per process
+static FONTS: Lazy<Arc<usvg::fontdb::Database>> = Lazy::new(|| {
+    let mut fonts = usvg::fontdb::Database::new();
+    fonts.load_system_fonts();
+    if fonts.is_empty() {
+        tracing::warn!("No system fonts found; text will be missing from rendered PNGs");
+    }
+    Arc::new(fonts)
+});
+
+/// Rasterize an SVG document to PNG at its own size
+///
+/// CPU bound: call it from `spawn_blocking` in async code.
+pub fn svg_to_png(svg: &str) -> Result<Vec<u8>> {
+    let options = usvg::Options {
+        fontdb: FONTS.clone(),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &options)?;
+
+    let size = tree.size().to_int_size();
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| anyhow::anyhow!("Invalid image size {}x{}", size.width(), size.height()))?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    Ok(pixmap.encode_png()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_to_png_keeps_size() {
+        let png = svg_to_png(r#"<svg xmlns="http://www.w3.org/2000/svg" width="120" height="63"><rect width="120" height="63" fill="red"/></svg>"#).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), 120);
+        assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), 63);
+    }
+}
@@ -0,0 +1,233 @@
+use axum::{middleware, Extension, Router};
+use tower_http::cors::{CorsLayer, Any};
+use axum::http::{HeaderName, HeaderValue, Method};
+use utoipa::OpenApi;
+use utoipa_axum::{router::{OpenApiRouter, UtoipaMethodRouterExt}, routes};
+use utoipa_swagger_ui::SwaggerUi;
+use crate::{
+    constants::{API_DOCS_PATH, OPENAPI_JSON_PATH, REQUEST_ID_HEADER},
+    handlers::{self, v1},
+    openapi::ApiDoc,
+    rate_limit::{self, KeyBy, RateLimitLayer},
+    utils::client_ip,
+    AppState,
+    Config,
+};
+
+/// Every HTTP route the server exposes, plus the OpenAPI document and docs UI
+pub fn create_router(state: AppState) -> Router {
+    let cors_layer = create_cors_layer(&state.config);
+    let (router, api) = api_routes().split_for_parts();
+
+    router
+        .merge(SwaggerUi::new(API_DOCS_PATH).url(OPENAPI_JSON_PATH, api))
+        .layer(Extension(state.rate_limits.clone()))
+        .layer(middleware::from_fn_with_state(state.config.trusted_proxies.clone(), client_ip::resolve_client_ip))
+        .layer(middleware::from_fn(handlers::assign_request_id))
+        .layer(cors_layer)
+        .with_state(state)
+}
+
+/// The OpenAPI document served at [`OPENAPI_JSON_PATH`]
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    api_routes().into_openapi()
+}
+
+// Each handler's `#[utoipa::path]` supplies both its route and its spec entry.
+// Handlers sharing a path go in the same `routes!`, unless one needs its own layer.
+fn api_routes() -> OpenApiRouter<AppState> {
+    OpenApiRouter::with_openapi(ApiDoc::openapi())
+        .routes(routes!(health_check))
+        
+        // Auth endpoints
+        .routes(routes!(handlers::create_auth_nonce).layer(auth_limit()))
+        .routes(routes!(handlers::create_session).layer(auth_limit()))
+        .routes(routes!(handlers::delete_session))
+        .routes(routes!(handlers::create_stream_ticket).layer(auth_limit()))
+        
+        // Profile endpoints
+        .routes(routes!(handlers::get_profile))
+        .routes(routes!(handlers::get_profile_card_svg))
+        .routes(routes!(handlers::get_profile_card_png))
+        .routes(routes!(handlers::get_profile_og_image))
+        .routes(routes!(handlers::get_badge))
+        .routes(routes!(handlers::check_together))
+        .routes(routes!(handlers::get_credentials).layer(proof_export_limit()))
+        .routes(routes!(handlers::get_eas_attestations).layer(proof_export_limit()))
+        
+        // User endpoints
+        .routes(routes!(handlers::get_or_create_user))
+        
+        // Pairing code endpoints
+        .routes(routes!(handlers::get_pairing_code))
+        
+        // Proximity challenge endpoints
+        .routes(routes!(handlers::create_proximity_challenge))
+        .routes(routes!(handlers::report_proximity_challenge))
+        
+        // Pending connection endpoints
+        .routes(routes!(handlers::create_pending_connection).layer(pending_connection_limit()).layer(pending_connection_ip_limit()))
+        .routes(routes!(handlers::get_user_pending_connections))
+        .routes(routes!(handlers::cancel_pending_connection))
+        .routes(routes!(handlers::decline_pending_connection))
+        
+        // Optimistic connection endpoints
+        .routes(routes!(handlers::get_user_optimistic_connections))
+        
+        // Pairing lifecycle stream (SSE)
+        .routes(routes!(handlers::stream_user_events))
+        .routes(routes!(handlers::export_user_data))
+        .routes(routes!(
+            handlers::get_account_deletion,
+            handlers::request_account_deletion,
+            handlers::cancel_account_deletion
+        ))
+        
+        // Attestation endpoints
+        .routes(routes!(handlers::attest_together).layer(attest_limit()))
+        .routes(routes!(handlers::get_signature_status))
+        
+        // Proof certificates
+        .routes(routes!(handlers::get_proof_certificate))
+        .routes(routes!(handlers::get_latest_merkle_snapshot))
+        .routes(routes!(handlers::get_merkle_proof))
+        
+        // Pair collectibles
+        .routes(routes!(handlers::get_pair_metadata))
+        .routes(routes!(handlers::get_pair_image))
+        
+        // RPC proxy endpoint
+        .routes(routes!(handlers::proxy_rpc).layer(rpc_limit()))
+        
+        .merge(v1_routes())
+}
+
+// Pre-v2 paths the shipped mini-app builds call, each forwarding to its v2 handler
+#[allow(deprecated)]
+fn v1_routes() -> OpenApiRouter<AppState> {
+    OpenApiRouter::new()
+        .routes(routes!(v1::get_profile))
+        .routes(routes!(v1::check_together))
+        .routes(routes!(v1::get_or_create_user))
+        .routes(routes!(v1::create_pending_connection).layer(pending_connection_limit()).layer(pending_connection_ip_limit()))
+        .routes(routes!(v1::get_user_pending_connections))
+        .routes(routes!(v1::get_user_optimistic_connections))
+        .routes(routes!(v1::attest_together).layer(attest_limit()))
+        .routes(routes!(v1::proxy_rpc).layer(rpc_limit()))
+}
+
+// Shared by each route's v1 and v2 paths, so both draw on one budget
+fn auth_limit() -> RateLimitLayer {
+    RateLimitLayer::new(rate_limit::AUTH, KeyBy::Ip)
+}
+
+fn attest_limit() -> RateLimitLayer {
+    RateLimitLayer::new(rate_limit::ATTEST, KeyBy::Wallet)
+}
+
+// Per sender wallet, plus a looser per-IP cap so one client can't spread guesses across wallets
+fn pending_connection_limit() -> RateLimitLayer {
+    RateLimitLayer::new(rate_limit::PENDING_CONNECTION, KeyBy::Wallet)
+}
+
+fn pending_connection_ip_limit() -> RateLimitLayer {
+    RateLimitLayer::new(rate_limit::PENDING_CONNECTION_IP, KeyBy::Ip)
+}
+
+// Every export signs a page of attestations, so it's budgeted per IP like the RPC proxy
+fn proof_export_limit() -> RateLimitLayer {
+    RateLimitLayer::new(rate_limit::PROOF_EXPORT, KeyBy::Ip)
+}
+
+fn rpc_limit() -> RateLimitLayer {
+    RateLimitLayer::new(rate_limit::RPC, KeyBy::Ip)
+}
+
+fn create_cors_layer(_config: &Config) -> CorsLayer {
+    let mut cors = CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST, Method::DELETE, Method::OPTIONS])
+        .allow_headers(Any)
+        .expose_headers([HeaderName::from_static(REQUEST_ID_HEADER)])
+        .allow_credentials(false);
+
+    // Check if ALLOWED_ORIGINS environment variable is set for multiple domains
+    if let Ok(cors_origins) = std::env::var("ALLOWED_ORIGINS") {
+        let origins: Vec<HeaderValue> = cors_origins
+            .split(',')
+            .filter_map(|origin| {
+                let trimmed = origin.trim();
+                if !trimmed.is_empty() {
+                    trimmed.parse().ok()
+                } else {
+                    None
+                }
+            })
+            .collect();
+        
+        if !origins.is_empty() {
+            cors = cors.allow_origin(origins);
+        } else {
+            // Fallback to permissive if parsing fails
+            cors = cors.allow_origin(Any);
+        }
+    } else {
+        // Default to permissive for development
+        cors = cors.allow_origin(Any);
+    }
+
+    cors
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "Server is up", body = String, content_type = "text/plain"))
+)]
+async fn health_check() -> &'static str {
+    "OK"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    #[test]
+    fn test_every_route_is_in_the_spec() {
+        let spec = openapi();
+        assert!(spec.paths.paths.contains_key("/api/v2/users/{user_id}/deletion"));
+        assert!(!spec.paths.paths.contains_key("/api/user/{user_id}/deletion"));
+        let v1 = spec.paths.paths["/api/user/{user_id}/pending-connection"].post.as_ref().unwrap();
+        assert!(matches!(v1.deprecated, Some(utoipa::openapi::Deprecated::True)));
+
+        // A route added with plain `.route()`/`.nest()` would be served without a spec entry
+        let source = include_str!("router.rs");
+        let bypass = Regex::new(r#"\.(route|nest|route_service|nest_service)\("#).unwrap();
+        let found: Vec<&str> = source.lines()
+            .filter(|line| !line.trim_start().starts_with("//") && bypass.is_match(line))
+            .collect();
+        assert!(found.is_empty(), "routes registered outside `routes!`: {:?}", found);
+
+        // Same-path handlers must share a `routes!`, or a method would be dropped
+        let operations: usize = spec.paths.paths.values()
+            .map(|item| [&item.get, &item.post, &item.put, &item.delete, &item.patch]
+                .iter()
+                .filter(|op| op.is_some())
+                .count())
+            .sum();
+        let handlers: usize = Regex::new(r"routes!\(([^)]*)\)").unwrap()
+            .captures_iter(source)
+            .map(|c| c[1].split(',').filter(|name| !name.trim().is_empty()).count())
+            .sum();
+        assert_eq!(operations, handlers, "every routed handler has an operation");
+    }
+
+    #[test]
+    fn test_spec_is_openapi_3_1() {
+        let json: serde_json::Value = serde_json::from_str(&openapi().to_json().unwrap()).unwrap();
+        assert_eq!(json["openapi"], "3.1.0");
+        assert_eq!(json["info"]["title"], "Together API");
+        assert!(json["components"]["securitySchemes"]["session_token"].is_object());
+    }
+}
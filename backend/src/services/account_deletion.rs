@@ -0,0 +1,22 @@
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::PgPool;
+use crate::db::account_deletions;
+
+/// Erase every account whose deletion grace period has ended, returning how many were erased
+pub async fn process_due_deletions(pool: &PgPool) -> Result<usize> {
+    let due = account_deletions::get_due_deletions(pool, Utc::now()).await?;
+
+    let mut erased = 0;
+    for deletion in &due {
+        match account_deletions::erase_account(pool, deletion).await {
+            Ok(()) => {
+                tracing::info!("Erased account data for deletion request {}", deletion.id);
+                erased += 1;
+            }
+            Err(e) => tracing::error!("Failed to erase account for deletion request {}: {}", deletion.id, e),
+        }
+    }
+
+    Ok(erased)
+}
@@ -1,52 +1,13 @@
 use anyhow::Result;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tokio::time::{Duration, Instant};
-use std::sync::Arc;
-use tokio::sync::Mutex;
 use alloy::primitives::U256;
-use crate::constants::*;
-
-// Rate limiting structure
-#[derive(Debug)]
-struct RateLimiter {
-    requests: HashMap<String, Vec<Instant>>,
-    max_requests_per_minute: u32,
-}
-
-impl RateLimiter {
-    fn new(max_requests_per_minute: u32) -> Self {
-        Self {
-            requests: HashMap::new(),
-            max_requests_per_minute,
-        }
-    }
-
-    async fn can_make_request(&mut self, key: &str) -> bool {
-        let now = Instant::now();
-        let minute_ago = now - Duration::from_secs(RATE_LIMIT_WINDOW_SECONDS);
-        
-        let requests = self.requests.entry(key.to_string()).or_insert_with(Vec::new);
-        
-        // Remove old requests
-        requests.retain(|&time| time > minute_ago);
-        
-        if requests.len() < self.max_requests_per_minute as usize {
-            requests.push(now);
-            true
-        } else {
-            false
-        }
-    }
-}
 
 #[derive(Debug, Clone)]
 pub struct AlchemyService {
     client: Client,
     api_key: String,
     base_url: String,
-    rate_limiter: Arc<Mutex<RateLimiter>>,
 }
 
 impl AlchemyService {
@@ -55,7 +16,6 @@ impl AlchemyService {
             client: Client::new(),
             api_key,
             base_url: "https://worldchain-mainnet.g.alchemy.com/nft/v3".to_string(),
-            rate_limiter: Arc::new(Mutex::new(RateLimiter::new(ALCHEMY_RATE_LIMIT_PER_MINUTE))),
         }
     }
 
@@ -1,16 +1,17 @@
 use anyhow::Result;
 use alloy::{
-    primitives::{Address, U256, Bytes},
-    providers::{Provider, ProviderBuilder},
+    primitives::{Address, B256, U256, Bytes},
+    providers::{DynProvider, Provider, ProviderBuilder},
     rpc::types::{TransactionRequest, TransactionInput},
-    signers::local::PrivateKeySigner,
     sol_types::SolCall,
 };
 
 use alloy::network::TransactionBuilder;
 use serde::Deserialize;
 
-use crate::constants::WORLDCHAIN_MAINNET_CHAIN_ID;
+use crate::constants::{EAS_CONTRACT_ADDRESS, TOGETHER_EVENT_TOPIC, WORLDCHAIN_MAINNET_CHAIN_ID};
+use crate::utils::{eip712::{Eip712Signer, TogetherSignatureData}, signer::{self, SignerBackend}};
+use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
 struct AlchemyGasPriceResponse {
@@ -28,25 +29,64 @@ alloy::sol! {
     }
 
     function together(address onBehalfOf, address togetherWith, uint256 timestamp, AuthData authData);
+
+    function authNoncesUsed(bytes32 nonce) external view returns (bool);
+
+    function version() external view returns (string);
+}
+
+/// What a sent together transaction's receipt showed by the time submission stopped waiting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiptStatus {
+    Succeeded,
+    /// Mined with status 0, so the attestation will never land
+    Reverted,
+    /// No receipt in time; the attestation watcher picks it up if it lands
+    Unknown,
+}
+
+/// A together transaction accepted by the node
+#[derive(Debug, Clone)]
+pub struct SentTransaction {
+    pub tx_hash: String,
+    pub receipt_status: ReceiptStatus,
 }
 
 #[derive(Debug, Clone)]
 pub struct ContractService {
-    rpc_url: String,
+    /// Built once; wallet providers for sending wrap it, so every call shares its connections
+    provider: DynProvider,
     together_contract_address: Address,
     alchemy_api_key: String,
+    http: reqwest::Client,
 }
 
 impl ContractService {
     pub async fn new(rpc_url: String, together_contract_address: String, alchemy_api_key: String) -> Result<Self> {
         let together_contract_address = together_contract_address.parse()?;
+        let provider = ProviderBuilder::new()
+            .connect_http(rpc_url.parse()?)
+            .erased();
         
         Ok(Self {
-            rpc_url,
+            provider,
             together_contract_address,
             alchemy_api_key,
+            http: reqwest::Client::new(),
         })
     }
+
+    /// Reuse an existing HTTP client (and its connection pool) for gas price lookups
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Reuse an existing provider (and its connection pool) for chain reads and sends
+    pub fn with_provider(mut self, provider: DynProvider) -> Self {
+        self.provider = provider;
+        self
+    }
     
     /// Fetch gas price from API with fallback to network gas price
     async fn get_optimal_gas_price<P: Provider>(&self, provider: &P) -> Result<u128> {
@@ -70,8 +110,6 @@ impl ContractService {
     
     /// Fetch gas price from Alchemy API
     async fn fetch_gas_price_from_api(&self) -> Result<u128> {
-        let client = reqwest::Client::new();
-        
         // Build Alchemy URL with API key
         let alchemy_url = format!("https://worldchain-mainnet.g.alchemy.com/v2/{}", self.alchemy_api_key);
         
@@ -83,7 +121,7 @@ impl ContractService {
             "id": 1
         });
         
-        let response = client
+        let response = self.http
             .post(&alchemy_url)
             .header("Content-Type", "application/json")
             .json(&payload)
@@ -134,80 +172,142 @@ impl ContractService {
         unreachable!()
     }
     
-    fn create_provider(&self) -> Result<impl Provider> {
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
-        Ok(provider)
-    }
-    
     pub async fn get_latest_block(&self) -> Result<u64> {
-        let provider = self.create_provider()?;
-        let block = provider.get_block_number().await?;
+        let block = self.provider.get_block_number().await?;
         Ok(block)
     }
     
-    /// Submit a together transaction on behalf of users (server-side signing)
-    pub async fn submit_together_transaction_server_signed(
+    /// Whether the contract has already consumed an AuthData nonce
+    pub async fn is_nonce_used(&self, nonce: U256) -> Result<bool> {
+        let call = authNoncesUsedCall { nonce: nonce.into() };
+        let tx = TransactionRequest::default()
+            .to(self.together_contract_address)
+            .input(TransactionInput::new(Bytes::from(call.abi_encode())));
+
+        let result = self.provider.call(tx).await?;
+        Ok(authNoncesUsedCall::abi_decode_returns(&result)?)
+    }
+
+    /// The EAS contract's `version()`, which offchain attestations use as their domain version
+    pub async fn get_eas_version(&self) -> Result<String> {
+        let tx = TransactionRequest::default()
+            .to(EAS_CONTRACT_ADDRESS.parse::<Address>()?)
+            .input(TransactionInput::new(Bytes::from(versionCall {}.abi_encode())));
+
+        let result = self.provider.call(tx).await?;
+        Ok(versionCall::abi_decode_returns(&result)?)
+    }
+
+    /// Find a Together event's block hash and log index from its transaction receipt
+    pub async fn get_together_log_position(
+        &self,
+        tx_hash: B256,
+        address_1: Address,
+        address_2: Address,
+        timestamp: u64,
+    ) -> Result<Option<(B256, u64)>> {
+        let Some(receipt) = self.provider.get_transaction_receipt(tx_hash).await? else {
+            return Ok(None);
+        };
+
+        let together_topic: B256 = TOGETHER_EVENT_TOPIC.parse()?;
+        let timestamp_topic = B256::from(U256::from(timestamp));
+        let position = receipt.inner.logs().iter()
+            .filter(|log| log.address() == self.together_contract_address)
+            .find(|log| {
+                let topics = log.topics();
+                if topics.len() != 4 || topics[0] != together_topic || topics[3] != timestamp_topic {
+                    return false;
+                }
+                let (a, b) = (Address::from_word(topics[1]), Address::from_word(topics[2]));
+                (a == address_1 && b == address_2) || (a == address_2 && b == address_1)
+            })
+            .and_then(|log| Some((log.block_hash?, log.log_index?)));
+
+        Ok(position)
+    }
+
+    /// Sign a together attestation server-side with a fresh nonce and deadline
+    pub async fn sign_together_server_side(
         &self,
-        private_key: &str,
+        signer: Arc<dyn SignerBackend>,
         address_1: &str,
         address_2: &str,
         timestamp: u64,
-    ) -> Result<String> {
-        use crate::utils::eip712::Eip712Signer;
-        
-        // Parse addresses
+    ) -> Result<(TogetherSignatureData, Address)> {
         let addr_1: Address = address_1.parse()?;
         let addr_2: Address = address_2.parse()?;
-        let contract_address: Address = self.together_contract_address;
-        
-        // Generate nonce and deadline
+
         let nonce = Eip712Signer::generate_nonce();
         let deadline = Eip712Signer::generate_deadline_10_minutes();
-        
-        // Create EIP712 signer
-        let signer = Eip712Signer::new(private_key, WORLDCHAIN_MAINNET_CHAIN_ID)?; // Worldchain mainnet chain ID
-        
-        // Sign the together attestation
+
+        let signer = Eip712Signer::new(signer, WORLDCHAIN_MAINNET_CHAIN_ID); // Worldchain mainnet chain ID
         let signature_data = signer.sign_together_attestation(
-            contract_address,
+            self.together_contract_address,
             addr_1,
             addr_2,
             timestamp as i64,
             nonce,
             deadline,
         ).await?;
-        
-        // Submit the transaction
+
+        Ok((signature_data, signer.address()))
+    }
+
+    /// Submit a together transaction on behalf of users (server-side signing)
+    pub async fn submit_together_transaction_server_signed(
+        &self,
+        signer: Arc<dyn SignerBackend>,
+        address_1: &str,
+        address_2: &str,
+        timestamp: u64,
+    ) -> Result<SentTransaction> {
+        let (signature_data, _signer_address) = self.sign_together_server_side(
+            signer.clone(),
+            address_1,
+            address_2,
+            timestamp,
+        ).await?;
+
+        self.submit_signed_together(signer, signature_data).await
+    }
+
+    /// Submit a together transaction from an already produced signature
+    pub async fn submit_signed_together(
+        &self,
+        sender: Arc<dyn SignerBackend>,
+        signature_data: TogetherSignatureData,
+    ) -> Result<SentTransaction> {
         self.submit_together_transaction(
-            private_key,
-            addr_1,
-            addr_2,
-            U256::from(timestamp),
-            nonce,
-            deadline,
+            sender,
+            signature_data.on_behalf_of,
+            signature_data.together_with,
+            signature_data.timestamp,
+            signature_data.nonce,
+            signature_data.deadline,
             signature_data.signature,
         ).await
     }
     
     pub async fn submit_together_transaction(
         &self,
-        private_key: &str,
+        sender: Arc<dyn SignerBackend>,
         on_behalf_of: Address,
         together_with: Address,
         timestamp: U256,
         nonce: U256,
         deadline: u64,
         signature: String,
-    ) -> Result<String> {
+    ) -> Result<SentTransaction> {
         const MAX_RETRY_ATTEMPTS: u32 = 3;
         const NONCE_RETRY_ATTEMPTS: u32 = 3;
         
-        let signer: PrivateKeySigner = private_key.parse()?;
+        let sender_address = sender.address();
         
-        // Create provider with wallet for transaction signing
+        // Layer the sender's wallet over the shared provider for transaction signing
         let provider = ProviderBuilder::new()
-            .wallet(signer.clone())
-            .connect_http(self.rpc_url.parse()?);
+            .wallet(signer::ethereum_wallet(sender))
+            .connect_provider(self.provider.clone());
         
         tracing::info!(
             "Starting together transaction submission for {} and {}",
@@ -250,7 +350,7 @@ impl ContractService {
         
         for attempt in 0..MAX_RETRY_ATTEMPTS {
             // Get current nonce with retry logic
-            let tx_nonce = self.get_nonce_with_retry(&provider, signer.address(), NONCE_RETRY_ATTEMPTS).await?;
+            let tx_nonce = self.get_nonce_with_retry(&provider, sender_address, NONCE_RETRY_ATTEMPTS).await?;
             
             tracing::info!(
                 "Attempt {} - Using nonce {} with gas price {} gwei",
@@ -314,7 +414,7 @@ impl ContractService {
                         .with_timeout(Some(std::time::Duration::from_secs(30)))
                         .get_receipt();
                     
-                    match tokio::time::timeout(
+                    let receipt_status = match tokio::time::timeout(
                         std::time::Duration::from_secs(30),
                         receipt_future
                     ).await {
@@ -324,11 +424,13 @@ impl ContractService {
                                     "Together transaction confirmed successfully: 0x{:x}",
                                     receipt.transaction_hash
                                 );
+                                ReceiptStatus::Succeeded
                             } else {
                                 tracing::error!(
                                     "Together transaction reverted: 0x{:x}",
                                     receipt.transaction_hash
                                 );
+                                ReceiptStatus::Reverted
                             }
                         }
                         Ok(Err(e)) => {
@@ -336,16 +438,21 @@ impl ContractService {
                                 "Error waiting for together transaction confirmation: {}",
                                 e
                             );
+                            ReceiptStatus::Unknown
                         }
                         Err(_) => {
                             tracing::warn!(
                                 "Timeout waiting for together transaction confirmation: 0x{:x}",
                                 tx_hash
                             );
+                            ReceiptStatus::Unknown
                         }
-                    }
+                    };
                     
-                    return Ok(format!("0x{:x}", tx_hash));
+                    return Ok(SentTransaction {
+                        tx_hash: format!("0x{:x}", tx_hash),
+                        receipt_status,
+                    });
                 }
                 Err(e) => {
                     let error_str = e.to_string().to_lowercase();
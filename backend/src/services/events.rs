@@ -0,0 +1,60 @@
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use sqlx::{postgres::PgListener, PgPool};
+use tokio::sync::broadcast;
+use tokio::time::Duration;
+use crate::constants::*;
+pub use together_types::events::PairingEvent;
+
+// Process-local fan-out of events received over LISTEN
+static PAIRING_EVENTS: Lazy<broadcast::Sender<PairingEvent>> =
+    Lazy::new(|| broadcast::channel(PAIRING_EVENTS_BUFFER).0);
+
+/// Publish an event to every process listening on the pairing channel
+pub async fn publish(pool: &PgPool, event: &PairingEvent) -> Result<()> {
+    let payload = serde_json::to_string(event)?;
+
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(PAIRING_EVENTS_CHANNEL)
+        .bind(payload)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Subscribe to events received by this process
+pub fn subscribe() -> broadcast::Receiver<PairingEvent> {
+    PAIRING_EVENTS.subscribe()
+}
+
+/// Spawn a background task that LISTENs for pairing events and re-broadcasts them locally
+pub fn spawn_listener(pool: PgPool) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = listen(&pool).await {
+                tracing::error!("Pairing event listener failed: {}, reconnecting", e);
+            }
+            tokio::time::sleep(Duration::from_secs(PAIRING_EVENTS_RECONNECT_SECS)).await;
+        }
+    })
+}
+
+async fn listen(pool: &PgPool) -> Result<()> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(PAIRING_EVENTS_CHANNEL).await?;
+    tracing::info!("Listening for pairing events on channel {}", PAIRING_EVENTS_CHANNEL);
+
+    loop {
+        let notification = listener.recv().await?;
+        match serde_json::from_str::<PairingEvent>(notification.payload()) {
+            Ok(event) => {
+                // No subscribers is fine, nobody has an open stream
+                let _ = PAIRING_EVENTS.send(event);
+            }
+            Err(e) => {
+                tracing::warn!("Ignoring malformed pairing event: {}", e);
+            }
+        }
+    }
+}
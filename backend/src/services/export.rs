@@ -0,0 +1,251 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::io::Write;
+use zip::{write::{SimpleFileOptions, StreamWriter}, CompressionMethod, ZipWriter};
+use crate::{
+    constants::*,
+    db::{attestations, sessions, signatures, users},
+    models::{
+        attestations::UsernameCache, OptimisticConnection, PendingConnection, PendingConnectionResolution,
+        SignatureIssuance, User,
+    },
+};
+
+/// An attestation as exported, with the partner and an explorer link spelled out
+#[derive(Debug, Clone, Serialize)]
+pub struct AttestationRecord {
+    pub id: uuid::Uuid,
+    pub partner_address: String,
+    pub attestation_timestamp: i64,
+    pub tx_hash: Option<String>,
+    pub tx_url: Option<String>,
+    pub block_number: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Everything held about one wallet, as written to the export archive
+#[derive(Debug, Clone, Serialize)]
+pub struct UserDataExport {
+    pub address: String,
+    pub generated_at: DateTime<Utc>,
+    pub user: Option<User>,
+    pub username_cache: Option<UsernameCache>,
+    pub attestations: Vec<AttestationRecord>,
+    pub pending_connections: Vec<PendingConnection>,
+    pub pending_connection_resolutions: Vec<PendingConnectionResolution>,
+    pub optimistic_connections: Vec<OptimisticConnection>,
+    pub signature_issuances: Vec<SignatureIssuance>,
+}
+
+#[derive(Debug, Serialize)]
+struct Manifest<'a> {
+    schema_version: u32,
+    address: &'a str,
+    generated_at: DateTime<Utc>,
+    files: &'a [&'a str],
+}
+
+// Every file in the archive; tables come as both .json and .csv
+const ARCHIVE_FILES: &[&str] = &[
+    "manifest.json",
+    "user.json",
+    "username_cache.json",
+    "attestations.json",
+    "attestations.csv",
+    "pending_connections.json",
+    "pending_connections.csv",
+    "pending_connection_resolutions.json",
+    "pending_connection_resolutions.csv",
+    "optimistic_connections.json",
+    "optimistic_connections.csv",
+    "signature_issuances.json",
+    "signature_issuances.csv",
+];
+
+/// Load everything we hold about `address`
+pub async fn collect_user_data(pool: &PgPool, address: &str) -> Result<UserDataExport> {
+    let user = users::get_user_by_wallet_address(pool, address).await?;
+    let username_cache = attestations::get_username_cache(pool, address).await?;
+
+    let attestations = attestations::get_all_attestations_for_address(pool, address).await?
+        .into_iter()
+        .map(|attestation| AttestationRecord {
+            id: attestation.id,
+            partner_address: if attestation.address_1.eq_ignore_ascii_case(address) {
+                attestation.address_2
+            } else {
+                attestation.address_1
+            },
+            attestation_timestamp: attestation.attestation_timestamp,
+            tx_url: attestation.tx_hash.as_ref().map(|hash| format!("{}{}", WORLDCHAIN_EXPLORER_TX_URL, hash)),
+            tx_hash: attestation.tx_hash,
+            block_number: attestation.block_number,
+            created_at: attestation.created_at,
+        })
+        .collect();
+
+    let (pending_connections, pending_connection_resolutions, optimistic_connections, session_ids) = match &user {
+        Some(user) => (
+            users::get_pending_connections_involving(pool, user.id).await?,
+            users::get_pending_connection_resolutions_involving(pool, user.id).await?,
+            users::get_optimistic_connections_involving(pool, user.id).await?,
+            sessions::get_session_ids_for_user(pool, user.id).await?,
+        ),
+        None => (Vec::new(), Vec::new(), Vec::new(), Vec::new()),
+    };
+
+    let mut signature_issuances = signatures::get_issuances_for_address(pool, address).await?;
+    redact_other_requesters(&mut signature_issuances, address, &session_ids);
+
+    Ok(UserDataExport {
+        address: address.to_string(),
+        generated_at: Utc::now(),
+        user,
+        username_cache,
+        attestations,
+        pending_connections,
+        pending_connection_resolutions,
+        optimistic_connections,
+        signature_issuances,
+    })
+}
+
+// Issuances name both partners, but who asked for one (their IP and session) is only the
+// requester's to export. Rows from a session or wallet we can't tie to `address` lose it.
+fn redact_other_requesters(issuances: &mut [SignatureIssuance], address: &str, session_ids: &[uuid::Uuid]) {
+    let own_requester = format!("wallet:{}", address.to_lowercase());
+    for issuance in issuances {
+        let own = issuance.requester.as_deref() == Some(own_requester.as_str())
+            || issuance.session_id.is_some_and(|id| session_ids.contains(&id));
+        if !own {
+            issuance.requester_ip = None;
+            issuance.session_id = None;
+            issuance.requester = None;
+        }
+    }
+}
+
+fn write_json<W: Write, T: Serialize>(zip: &mut ZipWriter<StreamWriter<W>>, name: &str, value: &T) -> Result<()> {
+    zip.start_file(name, SimpleFileOptions::default().compression_method(CompressionMethod::Deflated))?;
+    serde_json::to_writer_pretty(&mut *zip, value)?;
+    Ok(())
+}
+
+fn write_table<W: Write, T: Serialize>(zip: &mut ZipWriter<StreamWriter<W>>, name: &str, rows: &[T]) -> Result<()> {
+    write_json(zip, &format!("{}.json", name), &rows)?;
+
+    zip.start_file(format!("{}.csv", name), SimpleFileOptions::default().compression_method(CompressionMethod::Deflated))?;
+    let mut csv = csv::Writer::from_writer(&mut *zip);
+    for row in rows {
+        csv.serialize(row)?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+/// Write the export as a ZIP of JSON and CSV files, front to back
+///
+/// The writer never needs to seek, so it can be a file or a response body stream.
+pub fn write_archive<W: Write>(export: &UserDataExport, writer: W) -> Result<W> {
+    let mut zip = ZipWriter::new_stream(writer);
+
+    write_json(&mut zip, "manifest.json", &Manifest {
+        schema_version: EXPORT_SCHEMA_VERSION,
+        address: &export.address,
+        generated_at: export.generated_at,
+        files: ARCHIVE_FILES,
+    })?;
+    write_json(&mut zip, "user.json", &export.user)?;
+    write_json(&mut zip, "username_cache.json", &export.username_cache)?;
+    write_table(&mut zip, "attestations", &export.attestations)?;
+    write_table(&mut zip, "pending_connections", &export.pending_connections)?;
+    write_table(&mut zip, "pending_connection_resolutions", &export.pending_connection_resolutions)?;
+    write_table(&mut zip, "optimistic_connections", &export.optimistic_connections)?;
+    write_table(&mut zip, "signature_issuances", &export.signature_issuances)?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+
+    #[test]
+    fn test_archive_contains_every_listed_file() {
+        let export = UserDataExport {
+            address: "0x59888BE579194C701F16a9425f57ECce3906AF4b".to_string(),
+            generated_at: Utc::now(),
+            user: None,
+            username_cache: None,
+            attestations: vec![AttestationRecord {
+                id: uuid::Uuid::new_v4(),
+                partner_address: "0xAefC770D8515C552C952a30e597d9fbEa99aA756".to_string(),
+                attestation_timestamp: 1758989464,
+                tx_hash: Some("0xabc".to_string()),
+                tx_url: Some(format!("{}0xabc", WORLDCHAIN_EXPLORER_TX_URL)),
+                block_number: Some(1),
+                created_at: Utc::now(),
+            }],
+            pending_connections: Vec::new(),
+            pending_connection_resolutions: Vec::new(),
+            optimistic_connections: Vec::new(),
+            signature_issuances: Vec::new(),
+        };
+
+        let bytes = write_archive(&export, Vec::new()).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut names: Vec<_> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        let mut expected: Vec<_> = ARCHIVE_FILES.iter().map(|name| name.to_string()).collect();
+        expected.sort();
+        assert_eq!(names, expected);
+
+        let mut csv = String::new();
+        archive.by_name("attestations.csv").unwrap().read_to_string(&mut csv).unwrap();
+        assert!(csv.starts_with("id,partner_address,attestation_timestamp,tx_hash,tx_url"));
+        assert!(csv.contains("https://worldscan.org/tx/0xabc"));
+    }
+
+    #[test]
+    fn test_only_own_requests_keep_requester_details() {
+        let address = "0x59888BE579194C701F16a9425f57ECce3906AF4b";
+        let own_session = uuid::Uuid::new_v4();
+        let issuance = |requester: Option<&str>, session_id: Option<uuid::Uuid>| SignatureIssuance {
+            id: uuid::Uuid::new_v4(),
+            nonce: "0x01".to_string(),
+            on_behalf_of: "0xAefC770D8515C552C952a30e597d9fbEa99aA756".to_string(),
+            together_with: address.to_string(),
+            attestation_timestamp: 1758989464,
+            deadline: 1758990064,
+            signer_address: "0x0000000000000000000000000000000000000003".to_string(),
+            source: SIGNATURE_SOURCE_ATTEST.to_string(),
+            requester_ip: Some("203.0.113.7".to_string()),
+            session_id,
+            requester: requester.map(str::to_string),
+            created_at: Utc::now(),
+            consumed_at: None,
+            consumed_tx_hash: None,
+        };
+
+        let mut issuances = vec![
+            // The partner asked, signed in
+            issuance(Some("wallet:0xaefc770d8515c552c952a30e597d9fbea99aa756"), Some(uuid::Uuid::new_v4())),
+            // Someone signed out named this wallet
+            issuance(Some("ip:203.0.113.7"), None),
+            // This wallet asked, before requesters were recorded
+            issuance(None, Some(own_session)),
+            // This wallet asked from a session that has since been swept
+            issuance(Some("wallet:0x59888be579194c701f16a9425f57ecce3906af4b"), Some(uuid::Uuid::new_v4())),
+        ];
+        redact_other_requesters(&mut issuances, address, &[own_session]);
+
+        let kept: Vec<bool> = issuances.iter().map(|i| i.requester_ip.is_some()).collect();
+        assert_eq!(kept, [false, false, true, true]);
+        assert!(issuances[0].session_id.is_none() && issuances[0].requester.is_none());
+        assert!(issuances[1].requester.is_none());
+        assert_eq!(issuances[2].session_id, Some(own_session));
+    }
+}
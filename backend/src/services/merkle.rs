@@ -0,0 +1,77 @@
+use anyhow::Result;
+use alloy::primitives::{Address, B256};
+use sqlx::PgPool;
+use crate::{
+    db::merkle,
+    models::MerkleSnapshot,
+    proof::{leaf_hash, proof_positions, MerkleTree},
+};
+
+/// Rebuild the tree over every on-chain attestation up to `block_height`
+///
+/// Attestations without a block number (submitted directly, not yet seen on chain)
+/// are left out, so a snapshot at a given height is reproducible.
+pub async fn build_tree(pool: &PgPool, block_height: i64) -> Result<MerkleTree> {
+    let rows = merkle::get_attestation_leaves(pool, block_height).await?;
+
+    let mut leaves = Vec::with_capacity(rows.len());
+    for (address_1, address_2, timestamp) in rows {
+        match (address_1.parse::<Address>(), address_2.parse::<Address>()) {
+            (Ok(a), Ok(b)) => leaves.push(leaf_hash(a, b, timestamp as u64)),
+            _ => tracing::warn!("Skipping attestation with invalid addresses {} / {}", address_1, address_2),
+        }
+    }
+
+    Ok(MerkleTree::new(leaves))
+}
+
+/// Store a new snapshot at `block_height`, unless the dataset hasn't changed since the last one
+///
+/// The whole tree is stored with the snapshot, so its proofs stay servable after the
+/// attestation table changes.
+pub async fn take_snapshot(pool: &PgPool, block_height: i64) -> Result<Option<MerkleSnapshot>> {
+    let tree = build_tree(pool, block_height).await?;
+    let root = tree.root().to_string();
+    let layers = node_strings(&tree);
+
+    if let Some(latest) = merkle::get_latest_snapshot(pool).await?
+        && latest.root == root
+    {
+        // Same tree as a snapshot taken before nodes were stored, so they can be filled in now
+        if !merkle::has_snapshot_nodes(pool, latest.id).await? {
+            merkle::backfill_snapshot_nodes(pool, latest.id, &layers).await?;
+        }
+        return Ok(None);
+    }
+
+    let snapshot = merkle::insert_snapshot(pool, &root, block_height, &layers).await?;
+    Ok(Some(snapshot))
+}
+
+fn node_strings(tree: &MerkleTree) -> Vec<Vec<String>> {
+    tree.layers()
+        .iter()
+        .map(|layer| layer.iter().map(|node| node.to_string()).collect())
+        .collect()
+}
+
+/// Inclusion proof for `leaf` from the nodes stored with `snapshot`
+///
+/// `None` when the leaf isn't in the snapshot; an error if the snapshot predates stored nodes.
+pub async fn get_proof(pool: &PgPool, snapshot: &MerkleSnapshot, leaf: B256) -> Result<Option<Vec<B256>>> {
+    let Some(index) = merkle::get_leaf_position(pool, snapshot.id, &leaf.to_string()).await? else {
+        return Ok(None);
+    };
+    let Some(positions) = proof_positions(snapshot.leaf_count as usize, index as usize) else {
+        return Ok(None);
+    };
+
+    let nodes = merkle::get_snapshot_nodes(pool, snapshot.id, &positions).await?;
+    if nodes.len() != positions.len() {
+        anyhow::bail!("Merkle snapshot {} is missing tree nodes", snapshot.id);
+    }
+    let proof = nodes.iter()
+        .map(|node| node.parse::<B256>())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(proof))
+}
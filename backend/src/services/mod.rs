@@ -1,5 +1,16 @@
 pub mod contract;
 pub mod alchemy;
+pub mod events;
+pub mod proximity;
+pub mod signatures;
+pub mod signers;
+pub mod merkle;
+pub mod pairs;
+pub mod profile_card;
+pub mod export;
+pub mod account_deletion;
+pub mod webhooks;
+pub mod rpc_proxy;
 
 pub use contract::ContractService;
 pub use alchemy::AlchemyService;
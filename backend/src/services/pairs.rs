@@ -0,0 +1,33 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use crate::{
+    db::{account_deletions, attestations, pairs},
+    models::PairCard,
+};
+
+/// Gather what a pair's collectible shows, or `None` for an unknown token id
+pub async fn get_pair_card(pool: &PgPool, token_id: &str) -> Result<Option<PairCard>> {
+    let Some(pair) = pairs::get_pair(pool, token_id).await? else {
+        return Ok(None);
+    };
+    if account_deletions::any_address_erased(pool, &[&pair.address_1, &pair.address_2]).await? {
+        return Ok(None);
+    }
+    let Some(stats) = pairs::get_pair_stats(pool, &pair.address_1, &pair.address_2).await? else {
+        return Ok(None);
+    };
+
+    let username_1 = attestations::get_username_cache(pool, &pair.address_1).await?.and_then(|cache| cache.username);
+    let username_2 = attestations::get_username_cache(pool, &pair.address_2).await?.and_then(|cache| cache.username);
+
+    Ok(Some(PairCard {
+        token_id: pair.token_id,
+        address_1: pair.address_1,
+        address_2: pair.address_2,
+        username_1,
+        username_2,
+        first_met: stats.first_met,
+        last_met: stats.last_met,
+        strength: stats.strength,
+    }))
+}
@@ -0,0 +1,142 @@
+use anyhow::Result;
+use alloy::primitives::keccak256;
+use sqlx::PgPool;
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+use crate::{
+    constants::*,
+    db::attestations,
+    render,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CardFormat {
+    Svg,
+    Png,
+}
+
+/// A rendered card and the ETag identifying the profile state it shows
+#[derive(Debug, Clone)]
+pub struct RenderedCard {
+    pub etag: String,
+    pub body: Arc<Vec<u8>>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedCard {
+    fingerprint: String,
+    svg: Arc<Vec<u8>>,
+    png: Option<Arc<Vec<u8>>>,
+}
+
+/// Rendered cards shared across requests, keyed by lowercase address
+///
+/// An entry is stale once its fingerprint no longer matches the profile it was made from.
+#[derive(Debug)]
+pub struct CardCache {
+    max_entries: usize,
+    entries: Mutex<HashMap<String, CachedCard>>,
+}
+
+impl Default for CardCache {
+    fn default() -> Self {
+        Self::new(PROFILE_CARD_CACHE_CAPACITY)
+    }
+}
+
+impl CardCache {
+    pub fn new(max_entries: usize) -> Self {
+        Self { max_entries, entries: Mutex::new(HashMap::new()) }
+    }
+
+    fn get(&self, key: &str, fingerprint: &str) -> Option<CachedCard> {
+        let entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        entries.get(key)
+            .filter(|card| card.fingerprint == fingerprint)
+            .cloned()
+    }
+
+    fn insert(&self, key: String, card: CachedCard) {
+        let mut entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            // No recency tracking; evicting an arbitrary entry is enough to bound memory
+            if let Some(evict) = entries.keys().next().cloned() {
+                entries.remove(&evict);
+            }
+        }
+        entries.insert(key, card);
+    }
+}
+
+/// The shared profile link encoded in a card's QR code
+pub fn profile_url(app_url: &str, address: &str) -> String {
+    format!("{}/profile?address={}", app_url, address)
+}
+
+/// Render (or reuse) the profile card for `address`
+///
+/// The profile is always read fresh; rendering and rasterizing are skipped when it
+/// hasn't changed since the cached card was made.
+pub async fn get_profile_card(
+    pool: &PgPool,
+    cache: &CardCache,
+    app_url: &str,
+    address: &str,
+    format: CardFormat,
+) -> Result<RenderedCard> {
+    let profile = attestations::get_user_profile(pool, address, Some(PROFILE_CARD_CONNECTIONS_SCANNED)).await?;
+    let url = profile_url(app_url, address);
+
+    let mut fingerprint_input = serde_json::to_vec(&profile)?;
+    fingerprint_input.extend_from_slice(url.as_bytes());
+    let fingerprint = hex::encode(&keccak256(&fingerprint_input)[..16]);
+    let key = address.to_lowercase();
+
+    let cached = cache.get(&key, &fingerprint);
+
+    let svg = match &cached {
+        Some(card) => card.svg.clone(),
+        None => Arc::new(render::profile_card_svg(&profile, &url)?.into_bytes()),
+    };
+    let png = match (format, cached.and_then(|card| card.png)) {
+        (CardFormat::Png, None) => {
+            let svg = svg.clone();
+            let png = tokio::task::spawn_blocking(move || render::svg_to_png(&String::from_utf8_lossy(&svg))).await??;
+            Some(Arc::new(png))
+        }
+        (_, png) => png,
+    };
+
+    cache.insert(key, CachedCard {
+        fingerprint: fingerprint.clone(),
+        svg: svg.clone(),
+        png: png.clone(),
+    });
+
+    let (etag, body) = match format {
+        CardFormat::Svg => (format!("\"{}-svg\"", fingerprint), svg),
+        CardFormat::Png => (format!("\"{}-png\"", fingerprint), png.ok_or_else(|| anyhow::anyhow!("PNG card was not rendered"))?),
+    };
+    Ok(RenderedCard { etag, body })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(fingerprint: &str) -> CachedCard {
+        CachedCard { fingerprint: fingerprint.to_string(), svg: Arc::new(b"<svg/>".to_vec()), png: None }
+    }
+
+    #[test]
+    fn test_card_cache_is_bounded_and_drops_stale_cards() {
+        let cache = CardCache::new(2);
+        cache.insert("a".to_string(), card("1"));
+        assert!(cache.get("a", "1").is_some());
+        assert!(cache.get("a", "2").is_none(), "a changed profile misses");
+
+        cache.insert("b".to_string(), card("1"));
+        cache.insert("c".to_string(), card("1"));
+        assert_eq!(cache.entries.lock().unwrap().len(), 2);
+        assert!(cache.get("c", "1").is_some());
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::PgPool;
+use std::str::FromStr;
+use uuid::Uuid;
+use crate::{constants::PROXIMITY_PROOF_VALIDITY_SECS, db::proximity, models::ProximityExchange};
+
+/// How much challenge-response proof a pair needs before an attestation is relayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProximityPolicy {
+    /// No proof required; an explicit opt-out for deployments still serving legacy clients
+    Off,
+    /// Either user reported the other's challenge
+    #[default]
+    OneWay,
+    /// Both users reported each other's challenge
+    Reciprocal,
+}
+
+impl FromStr for ProximityPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "off" => Ok(ProximityPolicy::Off),
+            "one_way" | "" => Ok(ProximityPolicy::OneWay),
+            "reciprocal" => Ok(ProximityPolicy::Reciprocal),
+            other => Err(anyhow::anyhow!("Unknown proximity policy: {}", other)),
+        }
+    }
+}
+
+impl ProximityPolicy {
+    /// Pick the responses that satisfy this policy for the pair, or `None` if the proof is missing
+    ///
+    /// `exchanges` should be newest first; the newest response in each direction is used.
+    pub fn select_evidence(&self, exchanges: &[ProximityExchange], user_id_1: i32, user_id_2: i32) -> Option<Vec<Uuid>> {
+        let newest_from = |issuer: i32, responder: i32| exchanges.iter()
+            .find(|e| e.issuer_user_id == issuer && e.responder_user_id == responder)
+            .map(|e| e.response_id);
+
+        let forward = newest_from(user_id_1, user_id_2);
+        let backward = newest_from(user_id_2, user_id_1);
+
+        match self {
+            ProximityPolicy::Off => Some(forward.into_iter().chain(backward).collect()),
+            ProximityPolicy::OneWay => {
+                let ids: Vec<Uuid> = forward.into_iter().chain(backward).collect();
+                (!ids.is_empty()).then_some(ids)
+            }
+            ProximityPolicy::Reciprocal => match (forward, backward) {
+                (Some(f), Some(b)) => Some(vec![f, b]),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Check whether a pair has enough recent proof under `policy`, returning the response IDs to record
+pub async fn check_proximity(
+    pool: &PgPool,
+    policy: ProximityPolicy,
+    user_id_1: i32,
+    user_id_2: i32,
+) -> Result<Option<Vec<Uuid>>> {
+    let since = Utc::now() - chrono::Duration::seconds(PROXIMITY_PROOF_VALIDITY_SECS);
+    let exchanges = proximity::get_recent_exchanges(pool, user_id_1, user_id_2, since).await?;
+    Ok(policy.select_evidence(&exchanges, user_id_1, user_id_2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(issuer_user_id: i32, responder_user_id: i32) -> ProximityExchange {
+        ProximityExchange {
+            response_id: Uuid::new_v4(),
+            challenge_id: Uuid::new_v4(),
+            issuer_user_id,
+            responder_user_id,
+            latency_ms: 800,
+            received_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_policy_selection() {
+        let one_way = vec![exchange(1, 2)];
+        let both = vec![exchange(2, 1), exchange(1, 2), exchange(1, 2)];
+
+        assert_eq!(ProximityPolicy::Off.select_evidence(&[], 1, 2), Some(vec![]));
+        assert_eq!(ProximityPolicy::OneWay.select_evidence(&[], 1, 2), None);
+        assert_eq!(ProximityPolicy::OneWay.select_evidence(&one_way, 2, 1), Some(vec![one_way[0].response_id]));
+        assert_eq!(ProximityPolicy::Reciprocal.select_evidence(&one_way, 1, 2), None);
+        assert_eq!(
+            ProximityPolicy::Reciprocal.select_evidence(&both, 1, 2),
+            Some(vec![both[1].response_id, both[0].response_id])
+        );
+    }
+
+    #[test]
+    fn test_policy_from_str() {
+        assert_eq!("reciprocal".parse::<ProximityPolicy>().unwrap(), ProximityPolicy::Reciprocal);
+        assert_eq!("ONE_WAY".parse::<ProximityPolicy>().unwrap(), ProximityPolicy::OneWay);
+        // Unset enforces proof; turning it off has to be asked for
+        assert_eq!("".parse::<ProximityPolicy>().unwrap(), ProximityPolicy::OneWay);
+        assert_eq!("off".parse::<ProximityPolicy>().unwrap(), ProximityPolicy::Off);
+        assert!("sometimes".parse::<ProximityPolicy>().is_err());
+    }
+}
@@ -0,0 +1,212 @@
+//! Networks, method allowlists and the response cache behind `POST /api/v2/rpc`
+
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use crate::constants::*;
+
+/// A chain the proxy forwards to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcNetwork {
+    /// The `network` query value
+    pub name: &'static str,
+    pub chain_id: u64,
+    /// Alchemy host prefix, e.g. `worldchain-mainnet`
+    alchemy_subdomain: &'static str,
+    /// Used when no Alchemy key is configured
+    public_url: &'static str,
+    methods: &'static [&'static [&'static str]],
+}
+
+pub const NETWORKS: &[RpcNetwork] = &[
+    RpcNetwork {
+        name: "worldchain",
+        chain_id: WORLDCHAIN_MAINNET_CHAIN_ID,
+        alchemy_subdomain: "worldchain-mainnet",
+        public_url: "https://worldchain-mainnet.g.alchemy.com/public",
+        methods: &[RPC_READ_METHODS, RPC_WORLDCHAIN_METHODS],
+    },
+    RpcNetwork {
+        name: "base",
+        chain_id: 8453,
+        alchemy_subdomain: "base-mainnet",
+        public_url: "https://mainnet.base.org",
+        methods: &[RPC_READ_METHODS],
+    },
+    RpcNetwork {
+        name: "ethereum",
+        chain_id: 1,
+        alchemy_subdomain: "eth-mainnet",
+        public_url: "https://eth.llamarpc.com",
+        methods: &[RPC_READ_METHODS],
+    },
+    RpcNetwork {
+        name: "polygon",
+        chain_id: 137,
+        alchemy_subdomain: "polygon-mainnet",
+        public_url: "https://polygon-rpc.com",
+        methods: &[RPC_READ_METHODS],
+    },
+];
+
+impl RpcNetwork {
+    pub fn find(name: &str) -> Option<&'static RpcNetwork> {
+        NETWORKS.iter().find(|network| network.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn url(&self, alchemy_api_key: &str) -> String {
+        if alchemy_api_key.is_empty() {
+            self.public_url.to_string()
+        } else {
+            format!("https://{}.g.alchemy.com/v2/{}", self.alchemy_subdomain, alchemy_api_key)
+        }
+    }
+
+    pub fn allows(&self, method: &str) -> bool {
+        self.methods.iter().any(|methods| methods.contains(&method))
+    }
+}
+
+/// How long a successful result for `method` with `params` may be reused, if at all
+///
+/// Only calls whose answer can't change qualify: constants and state reads pinned to a
+/// block number or hash. Receipts don't, since a freshly mined one can still be
+/// reorged away.
+pub fn cache_ttl(method: &str, params: &Value) -> Option<Duration> {
+    let block_param = match method {
+        "eth_chainId" | "net_version" => return Some(Duration::from_secs(RPC_CACHE_STATIC_TTL_SECS)),
+        "eth_call" | "eth_getBalance" | "eth_getCode" => 1,
+        "eth_getStorageAt" => 2,
+        _ => return None,
+    };
+    params.get(block_param)
+        .filter(|block| is_fixed_block(block))
+        .map(|_| Duration::from_secs(RPC_CACHE_TTL_SECS))
+}
+
+/// A block number or hash (EIP-1898 object included); tags like `latest` move
+fn is_fixed_block(block: &Value) -> bool {
+    match block {
+        Value::String(tag) => tag.starts_with("0x") || tag == "earliest",
+        Value::Object(object) => object.contains_key("blockHash")
+            || object.get("blockNumber").and_then(Value::as_str).is_some_and(|number| number.starts_with("0x")),
+        _ => false,
+    }
+}
+
+/// Short-lived results of idempotent calls, shared across requests
+#[derive(Debug)]
+pub struct RpcCache {
+    max_entries: usize,
+    entries: Mutex<HashMap<String, (Instant, Value)>>,
+}
+
+impl Default for RpcCache {
+    fn default() -> Self {
+        Self::new(RPC_CACHE_MAX_ENTRIES)
+    }
+}
+
+impl RpcCache {
+    pub fn new(max_entries: usize) -> Self {
+        Self { max_entries, entries: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn key(network: &RpcNetwork, method: &str, params: &Value) -> String {
+        format!("{}:{}:{}", network.name, method, params)
+    }
+
+    pub fn get(&self, key: &str) -> Option<Value> {
+        let entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        entries.get(key)
+            .filter(|(expires_at, _)| *expires_at > Instant::now())
+            .map(|(_, result)| result.clone())
+    }
+
+    /// Store `result` unless it's `null`; when full, expired entries are dropped and
+    /// if that frees nothing the result simply isn't cached
+    pub fn insert(&self, key: String, result: Value, ttl: Duration) {
+        if result.is_null() {
+            return;
+        }
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, (expires_at, _)| *expires_at > now);
+            if entries.len() >= self.max_entries {
+                return;
+            }
+        }
+        entries.insert(key, (now + ttl, result));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_network_table() {
+        let worldchain = RpcNetwork::find("Worldchain").unwrap();
+        assert_eq!(worldchain.chain_id, 480);
+        assert_eq!(worldchain.url("key"), "https://worldchain-mainnet.g.alchemy.com/v2/key");
+        assert_eq!(RpcNetwork::find("base").unwrap().url(""), "https://mainnet.base.org");
+        assert!(RpcNetwork::find("solana").is_none());
+    }
+
+    #[test]
+    fn test_method_allowlist() {
+        let worldchain = RpcNetwork::find("worldchain").unwrap();
+        let base = RpcNetwork::find("base").unwrap();
+        assert!(worldchain.allows("eth_call") && base.allows("eth_call"));
+        assert!(worldchain.allows("eth_sendRawTransaction"));
+        assert!(!base.allows("eth_sendRawTransaction"));
+        for method in ["debug_traceTransaction", "trace_block", "eth_sendTransaction", "admin_peers", "eth_getLogs"] {
+            assert!(!worldchain.allows(method), "{} allowed", method);
+        }
+    }
+
+    #[test]
+    fn test_only_fixed_blocks_are_cached() {
+        let call = json!({ "to": "0x0053E5F890d5cE67048C86eCCf6051A92Ab34b4b", "data": "0x" });
+        assert!(cache_ttl("eth_call", &json!([call, "0x12dfd0c"])).is_some());
+        assert!(cache_ttl("eth_call", &json!([call, { "blockHash": "0xabc" }])).is_some());
+        assert!(cache_ttl("eth_call", &json!([call, "latest"])).is_none());
+        assert!(cache_ttl("eth_call", &json!([call])).is_none());
+        assert!(cache_ttl("eth_getStorageAt", &json!(["0x1", "0x0", "0x10"])).is_some());
+        assert_eq!(cache_ttl("eth_chainId", &json!([])), Some(Duration::from_secs(RPC_CACHE_STATIC_TTL_SECS)));
+        assert!(cache_ttl("eth_blockNumber", &json!([])).is_none());
+    }
+
+    #[test]
+    fn test_receipts_are_never_cached() {
+        // Whether still pending or just mined, the answer can still change
+        let tx_hash = json!(["0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060"]);
+        assert!(cache_ttl("eth_getTransactionReceipt", &tx_hash).is_none());
+    }
+
+    #[test]
+    fn test_cache_skips_null_and_stays_bounded() {
+        let cache = RpcCache::new(2);
+        let ttl = Duration::from_secs(60);
+        cache.insert("pending".to_string(), Value::Null, ttl);
+        assert!(cache.get("pending").is_none());
+
+        cache.insert("a".to_string(), json!("0x1e0"), ttl);
+        cache.insert("b".to_string(), json!("0x1"), ttl);
+        cache.insert("c".to_string(), json!("0x2"), ttl);
+        assert_eq!(cache.get("a"), Some(json!("0x1e0")));
+        assert!(cache.get("c").is_none());
+
+        // Expired entries make room for new ones
+        let cache = RpcCache::new(1);
+        cache.insert("expired".to_string(), json!(1), Duration::ZERO);
+        assert!(cache.get("expired").is_none());
+        cache.insert("d".to_string(), json!(2), ttl);
+        assert_eq!(cache.get("d"), Some(json!(2)));
+    }
+}
@@ -0,0 +1,314 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use sqlx::{PgConnection, PgPool};
+use crate::{constants::*, db::signatures, models::NewSignatureIssuance, utils::client_ip::ClientIp};
+
+/// Which issuance quota a request would exceed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuotaExceeded {
+    Requester,
+    Address(String),
+    Pair,
+}
+
+impl QuotaExceeded {
+    pub fn message(&self) -> String {
+        match self {
+            QuotaExceeded::Requester => format!(
+                "Signature quota of {} per day reached",
+                SIGNATURE_QUOTA_PER_REQUESTER_PER_DAY
+            ),
+            QuotaExceeded::Address(address) => format!(
+                "Signature quota of {} per day reached for {}",
+                SIGNATURE_QUOTA_PER_ADDRESS_PER_DAY, address
+            ),
+            QuotaExceeded::Pair => format!(
+                "Signature quota of {} per hour reached for this pair",
+                SIGNATURE_QUOTA_PER_PAIR_PER_HOUR
+            ),
+        }
+    }
+}
+
+/// Who an issuance's quota is charged to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaSubject<'a> {
+    /// A client's request, charged to whoever asked (see [`requester_key`]) as well as
+    /// to the pair and addresses they named, since anyone can name them
+    Requester(&'a str),
+    /// A relayed match, charged to both users; each signed in to request it
+    Relayed,
+}
+
+impl<'a> QuotaSubject<'a> {
+    pub fn of(issuance: &'a NewSignatureIssuance<'_>) -> Self {
+        match issuance.requester.as_deref() {
+            Some(requester) => QuotaSubject::Requester(requester),
+            None => QuotaSubject::Relayed,
+        }
+    }
+}
+
+/// Quota key for a client's signature request: the signed-in wallet, else the client IP
+pub fn requester_key(wallet_address: Option<&str>, client_ip: &ClientIp) -> String {
+    match wallet_address {
+        Some(wallet_address) => format!("wallet:{}", wallet_address.to_lowercase()),
+        None => format!("ip:{}", client_ip),
+    }
+}
+
+/// Check the issuance quotas for signing for a pair
+///
+/// On its own this is only an early refusal; [`record_within_quota`] is what enforces them.
+pub async fn check_quota(
+    conn: &mut PgConnection,
+    subject: QuotaSubject<'_>,
+    address_1: &str,
+    address_2: &str,
+) -> Result<Option<QuotaExceeded>> {
+    let now = Utc::now();
+    let hour_ago = now - Duration::hours(1);
+    let day_ago = now - Duration::days(1);
+
+    match subject {
+        QuotaSubject::Requester(requester) => {
+            let pair_count = signatures::count_requested_pair_issuances(conn, address_1, address_2, hour_ago).await?;
+            if pair_count >= SIGNATURE_QUOTA_PER_PAIR_PER_HOUR {
+                return Ok(Some(QuotaExceeded::Pair));
+            }
+
+            let count = signatures::count_requested_issuances(conn, requester, day_ago).await?;
+            if count >= SIGNATURE_QUOTA_PER_REQUESTER_PER_DAY {
+                return Ok(Some(QuotaExceeded::Requester));
+            }
+
+            // A new wallet or IP gets a fresh requester quota, so the addresses are capped too
+            for address in [address_1, address_2] {
+                let count = signatures::count_requested_issuances_for_address(conn, address, day_ago).await?;
+                if count >= SIGNATURE_QUOTA_PER_ADDRESS_PER_DAY {
+                    return Ok(Some(QuotaExceeded::Address(address.to_string())));
+                }
+            }
+        }
+        QuotaSubject::Relayed => {
+            let pair_count = signatures::count_relayed_pair_issuances(conn, address_1, address_2, hour_ago).await?;
+            if pair_count >= SIGNATURE_QUOTA_PER_PAIR_PER_HOUR {
+                return Ok(Some(QuotaExceeded::Pair));
+            }
+
+            for address in [address_1, address_2] {
+                let count = signatures::count_relayed_issuances_for_address(conn, address, day_ago).await?;
+                if count >= SIGNATURE_QUOTA_PER_ADDRESS_PER_DAY {
+                    return Ok(Some(QuotaExceeded::Address(address.to_string())));
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Record an issuance in the ledger unless it would exceed its quota
+///
+/// The check and the insert share a transaction that holds advisory locks on the quota's
+/// keys, so concurrent requests can't both squeeze under the limit. `Some` means nothing
+/// was recorded and the signature must not be handed out or relayed.
+pub async fn record_within_quota(pool: &PgPool, issuance: &NewSignatureIssuance<'_>) -> Result<Option<QuotaExceeded>> {
+    let subject = QuotaSubject::of(issuance);
+    let (address_1, address_2) = (issuance.on_behalf_of.to_lowercase(), issuance.together_with.to_lowercase());
+    let mut keys = vec![address_1.clone(), address_2.clone()];
+    if let QuotaSubject::Requester(requester) = subject {
+        let pair = if address_1 <= address_2 { (&address_1, &address_2) } else { (&address_2, &address_1) };
+        keys.push(format!("pair:{}:{}", pair.0, pair.1));
+        keys.push(requester.to_string());
+    }
+    // A fixed order, so two issuances sharing a key can't deadlock
+    keys.sort();
+    keys.dedup();
+
+    let mut tx = pool.begin().await?;
+    for key in &keys {
+        signatures::lock_quota(&mut tx, &format!("signature_quota:{}", key)).await?;
+    }
+
+    if let Some(exceeded) = check_quota(&mut tx, subject, &issuance.on_behalf_of, &issuance.together_with).await? {
+        return Ok(Some(exceeded));
+    }
+
+    signatures::record_issuance(&mut tx, issuance).await?;
+    tx.commit().await?;
+    Ok(None)
+}
+
+/// Format an AuthData nonce the way the ledger stores it (0x-prefixed bytes32)
+pub fn format_nonce(nonce: alloy::primitives::U256) -> String {
+    format!("0x{:064x}", nonce)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::testing::{random_wallet, test_pool};
+    use alloy::primitives::U256;
+
+    fn issuance<'a>(requester: Option<&str>, address_1: &str, address_2: &str) -> NewSignatureIssuance<'a> {
+        NewSignatureIssuance {
+            nonce: format_nonce(U256::from_be_bytes(rand::random::<[u8; 32]>())),
+            on_behalf_of: address_1.to_string(),
+            together_with: address_2.to_string(),
+            attestation_timestamp: Utc::now().timestamp(),
+            deadline: Utc::now().timestamp() + 600,
+            signer_address: random_wallet(),
+            source: if requester.is_some() { SIGNATURE_SOURCE_ATTEST } else { SIGNATURE_SOURCE_CONNECTION_CHECKER },
+            requester_ip: None,
+            session_id: None,
+            requester: requester.map(str::to_string),
+        }
+    }
+
+    async fn age_issuances(pool: &PgPool, address: &str, age: Duration) {
+        sqlx::query("UPDATE signature_issuances SET created_at = $2 WHERE LOWER(on_behalf_of) = LOWER($1)")
+            .bind(address)
+            .bind(Utc::now() - age)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_requester_key_prefers_the_wallet() {
+        let ip = ClientIp(Some("203.0.113.7".parse().unwrap()));
+        assert_eq!(requester_key(Some("0xAbC"), &ip), "wallet:0xabc");
+        assert_eq!(requester_key(None, &ip), "ip:203.0.113.7");
+    }
+
+    #[tokio::test]
+    async fn test_pair_quota_is_hourly() {
+        let Some(pool) = test_pool().await else { return };
+        let (me, partner) = (random_wallet(), random_wallet());
+        let requester = format!("ip:{}", random_wallet());
+
+        for _ in 0..SIGNATURE_QUOTA_PER_PAIR_PER_HOUR {
+            assert_eq!(record_within_quota(&pool, &issuance(Some(&requester), &me, &partner)).await.unwrap(), None);
+        }
+        // Either order is the same pair
+        let refused = record_within_quota(&pool, &issuance(Some(&requester), &partner, &me)).await.unwrap();
+        assert_eq!(refused, Some(QuotaExceeded::Pair));
+
+        // The hour rolls over
+        age_issuances(&pool, &me, Duration::minutes(61)).await;
+        assert_eq!(record_within_quota(&pool, &issuance(Some(&requester), &me, &partner)).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_requester_quota_is_daily() {
+        let Some(pool) = test_pool().await else { return };
+        let me = random_wallet();
+        let requester = format!("wallet:{}", me.to_lowercase());
+
+        for _ in 0..SIGNATURE_QUOTA_PER_REQUESTER_PER_DAY {
+            assert_eq!(record_within_quota(&pool, &issuance(Some(&requester), &me, &random_wallet())).await.unwrap(), None);
+        }
+        let refused = record_within_quota(&pool, &issuance(Some(&requester), &me, &random_wallet())).await.unwrap();
+        assert_eq!(refused, Some(QuotaExceeded::Requester));
+
+        age_issuances(&pool, &me, Duration::hours(23)).await;
+        let refused = record_within_quota(&pool, &issuance(Some(&requester), &me, &random_wallet())).await.unwrap();
+        assert_eq!(refused, Some(QuotaExceeded::Requester));
+
+        age_issuances(&pool, &me, Duration::hours(25)).await;
+        assert_eq!(record_within_quota(&pool, &issuance(Some(&requester), &me, &random_wallet())).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_pair_quota_is_shared_between_requesters() {
+        let Some(pool) = test_pool().await else { return };
+        let (me, partner) = (random_wallet(), random_wallet());
+        let (first, second) = (format!("ip:{}", random_wallet()), format!("wallet:{}", random_wallet().to_lowercase()));
+
+        for attempt in 0..SIGNATURE_QUOTA_PER_PAIR_PER_HOUR {
+            let requester = if attempt % 2 == 0 { &first } else { &second };
+            assert_eq!(record_within_quota(&pool, &issuance(Some(requester), &me, &partner)).await.unwrap(), None);
+        }
+        // A fresh IP or wallet doesn't reset the pair's quota
+        for requester in [&first, &second, &format!("ip:{}", random_wallet())] {
+            let refused = record_within_quota(&pool, &issuance(Some(requester), &me, &partner)).await.unwrap();
+            assert_eq!(refused, Some(QuotaExceeded::Pair));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_address_quota_is_shared_between_requesters() {
+        let Some(pool) = test_pool().await else { return };
+        let me = random_wallet();
+
+        for _ in 0..SIGNATURE_QUOTA_PER_ADDRESS_PER_DAY {
+            let requester = format!("ip:{}", random_wallet());
+            assert_eq!(record_within_quota(&pool, &issuance(Some(&requester), &me, &random_wallet())).await.unwrap(), None);
+        }
+        // Nobody can ask for another one naming it, wherever they ask from
+        let requester = format!("ip:{}", random_wallet());
+        let refused = record_within_quota(&pool, &issuance(Some(&requester), &random_wallet(), &me)).await.unwrap();
+        assert_eq!(refused, Some(QuotaExceeded::Address(me.clone())));
+
+        age_issuances(&pool, &me, Duration::hours(25)).await;
+        assert_eq!(record_within_quota(&pool, &issuance(Some(&requester), &random_wallet(), &me)).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requesters_cannot_overshoot_a_pair() {
+        let Some(pool) = test_pool().await else { return };
+        let (me, partner) = (random_wallet(), random_wallet());
+
+        let attempts = (0..SIGNATURE_QUOTA_PER_PAIR_PER_HOUR * 3).map(|_| {
+            let requester = format!("ip:{}", random_wallet());
+            let (pool, issuance) = (pool.clone(), issuance(Some(&requester), &me, &partner));
+            tokio::spawn(async move { record_within_quota(&pool, &issuance).await.unwrap() })
+        }).collect::<Vec<_>>();
+
+        let mut recorded = 0;
+        for attempt in attempts {
+            if attempt.await.unwrap().is_none() {
+                recorded += 1;
+            }
+        }
+        assert_eq!(recorded, SIGNATURE_QUOTA_PER_PAIR_PER_HOUR);
+    }
+
+    #[tokio::test]
+    async fn test_client_requests_never_use_up_relay_quota() {
+        let Some(pool) = test_pool().await else { return };
+        let (me, partner) = (random_wallet(), random_wallet());
+
+        for _ in 0..SIGNATURE_QUOTA_PER_PAIR_PER_HOUR {
+            let requester = format!("ip:{}", random_wallet());
+            record_within_quota(&pool, &issuance(Some(&requester), &me, &partner)).await.unwrap();
+        }
+        assert_eq!(record_within_quota(&pool, &issuance(None, &me, &partner)).await.unwrap(), None);
+
+        for _ in 1..SIGNATURE_QUOTA_PER_PAIR_PER_HOUR {
+            record_within_quota(&pool, &issuance(None, &me, &partner)).await.unwrap();
+        }
+        assert_eq!(record_within_quota(&pool, &issuance(None, &me, &partner)).await.unwrap(), Some(QuotaExceeded::Pair));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_cannot_overshoot() {
+        let Some(pool) = test_pool().await else { return };
+        let (me, partner) = (random_wallet(), random_wallet());
+        let requester = format!("ip:{}", random_wallet());
+
+        let attempts = (0..SIGNATURE_QUOTA_PER_PAIR_PER_HOUR * 3).map(|_| {
+            let (pool, issuance) = (pool.clone(), issuance(Some(&requester), &me, &partner));
+            tokio::spawn(async move { record_within_quota(&pool, &issuance).await.unwrap() })
+        }).collect::<Vec<_>>();
+
+        let mut recorded = 0;
+        for attempt in attempts {
+            if attempt.await.unwrap().is_none() {
+                recorded += 1;
+            }
+        }
+        assert_eq!(recorded, SIGNATURE_QUOTA_PER_PAIR_PER_HOUR);
+    }
+}
@@ -0,0 +1,66 @@
+use anyhow::Result;
+use alloy::primitives::Address;
+use chrono::Utc;
+use sqlx::PgPool;
+use std::{sync::Arc, time::{Duration, Instant}};
+use tokio::sync::Mutex;
+use crate::{
+    constants::SIGNER_DENYLIST_CACHE_SECS,
+    db::signers,
+    utils::signer::{choose_weighted, SignerBackend, SignerSet},
+};
+
+/// Pick an attestation signer for one request
+///
+/// Only keys inside their activation window and not denied on chain are eligible;
+/// among those the choice is weighted.
+pub async fn pick_signer(pool: &PgPool, set: &SignerSet) -> Result<Arc<dyn SignerBackend>> {
+    choose_signer(set, &load_denied_signers(pool).await?)
+}
+
+/// [`pick_signer`] for a long-lived server: the deny list is re-read at most every
+/// `SIGNER_DENYLIST_CACHE_SECS` instead of on every request
+#[derive(Debug)]
+pub struct SignerPicker {
+    set: SignerSet,
+    denied: Mutex<Option<(Instant, Vec<Address>)>>,
+}
+
+impl SignerPicker {
+    pub fn new(set: SignerSet) -> Self {
+        Self { set, denied: Mutex::new(None) }
+    }
+
+    pub async fn pick(&self, pool: &PgPool) -> Result<Arc<dyn SignerBackend>> {
+        let denied = {
+            let mut cached = self.denied.lock().await;
+            match cached.as_ref() {
+                Some((fetched_at, denied)) if fetched_at.elapsed() < Duration::from_secs(SIGNER_DENYLIST_CACHE_SECS) => denied.clone(),
+                _ => {
+                    let denied = load_denied_signers(pool).await?;
+                    *cached = Some((Instant::now(), denied.clone()));
+                    denied
+                }
+            }
+        };
+        choose_signer(&self.set, &denied)
+    }
+}
+
+async fn load_denied_signers(pool: &PgPool) -> Result<Vec<Address>> {
+    Ok(signers::get_denied_signers(pool).await?
+        .iter()
+        .filter_map(|address| address.parse::<Address>().ok())
+        .collect())
+}
+
+fn choose_signer(set: &SignerSet, denied: &[Address]) -> Result<Arc<dyn SignerBackend>> {
+    let active = set.active(Utc::now(), denied)?;
+    let total_weight: u64 = active.iter().map(|(_, weight)| *weight as u64).sum();
+    if total_weight == 0 {
+        return Err(anyhow::anyhow!("No active attestation signer"));
+    }
+
+    choose_weighted(&active, rand::random_range(0..total_weight))
+        .ok_or_else(|| anyhow::anyhow!("No active attestation signer"))
+}
@@ -0,0 +1,450 @@
+use alloy::primitives::Address;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::str::FromStr;
+use thiserror::Error;
+use tokio::task::JoinSet;
+use tokio::time::Duration;
+use uuid::Uuid;
+use crate::{constants::*, db::{account_deletions, webhooks}, models::DueWebhookDelivery};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// What a partner endpoint can subscribe to
+///
+/// `matched` fires when the connection checker pairs two users, `confirmed` when the
+/// watcher records the attestation from chain, `relay_failed` when a match's
+/// attestation couldn't be signed or sent, and `reverted` when its transaction was
+/// mined but reverted. `ping` is only sent by hand to test an endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    Matched,
+    Confirmed,
+    RelayFailed,
+    Reverted,
+    Ping,
+}
+
+impl WebhookEventType {
+    /// Event types an endpoint can filter on
+    pub const SUBSCRIBABLE: [WebhookEventType; 4] = [
+        WebhookEventType::Matched,
+        WebhookEventType::Confirmed,
+        WebhookEventType::RelayFailed,
+        WebhookEventType::Reverted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::Matched => "matched",
+            WebhookEventType::Confirmed => "confirmed",
+            WebhookEventType::RelayFailed => "relay_failed",
+            WebhookEventType::Reverted => "reverted",
+            WebhookEventType::Ping => "ping",
+        }
+    }
+}
+
+impl FromStr for WebhookEventType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::SUBSCRIBABLE
+            .into_iter()
+            .find(|event_type| event_type.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("Unknown webhook event type '{}' (expected matched, confirmed, relay_failed or reverted)", s))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEventData {
+    pub address_1: String,
+    pub address_2: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Body POSTed to partner endpoints
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: WebhookEventType,
+    pub created_at: DateTime<Utc>,
+    pub data: WebhookEventData,
+}
+
+impl WebhookEvent {
+    fn new(event_type: WebhookEventType, data: WebhookEventData) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            event_type,
+            created_at: Utc::now(),
+            data,
+        }
+    }
+
+    pub fn matched(address_1: &str, address_2: &str) -> Self {
+        Self::new(WebhookEventType::Matched, WebhookEventData {
+            address_1: address_1.to_lowercase(),
+            address_2: address_2.to_lowercase(),
+            tx_hash: None,
+            block_number: None,
+            reason: None,
+        })
+    }
+
+    pub fn confirmed(address_1: &str, address_2: &str, tx_hash: &str, block_number: i64) -> Self {
+        Self::new(WebhookEventType::Confirmed, WebhookEventData {
+            address_1: address_1.to_lowercase(),
+            address_2: address_2.to_lowercase(),
+            tx_hash: Some(tx_hash.to_string()),
+            block_number: Some(block_number),
+            reason: None,
+        })
+    }
+
+    pub fn relay_failed(address_1: &str, address_2: &str, reason: &str) -> Self {
+        Self::new(WebhookEventType::RelayFailed, WebhookEventData {
+            address_1: address_1.to_lowercase(),
+            address_2: address_2.to_lowercase(),
+            tx_hash: None,
+            block_number: None,
+            reason: Some(reason.to_string()),
+        })
+    }
+
+    pub fn reverted(address_1: &str, address_2: &str, tx_hash: &str) -> Self {
+        Self::new(WebhookEventType::Reverted, WebhookEventData {
+            address_1: address_1.to_lowercase(),
+            address_2: address_2.to_lowercase(),
+            tx_hash: Some(tx_hash.to_string()),
+            block_number: None,
+            reason: None,
+        })
+    }
+
+    pub fn ping() -> Self {
+        Self::new(WebhookEventType::Ping, WebhookEventData {
+            address_1: Address::ZERO.to_string().to_lowercase(),
+            address_2: Address::ZERO.to_string().to_lowercase(),
+            tx_hash: None,
+            block_number: None,
+            reason: None,
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum WebhookSignatureError {
+    #[error("malformed signature header")]
+    Malformed,
+    #[error("signature timestamp is outside the tolerance window")]
+    Expired,
+    #[error("signature does not match payload")]
+    Mismatch,
+}
+
+#[derive(Debug, Error)]
+pub enum DeliveryError {
+    #[error("endpoint responded with HTTP {0}")]
+    Status(u16),
+    #[error("request failed: {0}")]
+    Request(#[from] reqwest::Error),
+}
+
+impl DeliveryError {
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            DeliveryError::Status(status) => Some(*status),
+            DeliveryError::Request(e) => e.status().map(|status| status.as_u16()),
+        }
+    }
+}
+
+/// Generate a signing secret for a new endpoint
+pub fn generate_secret() -> String {
+    format!("whsec_{}", hex::encode(rand::random::<[u8; 32]>()))
+}
+
+fn payload_mac(secret: &str, timestamp: i64, payload: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+/// Value of the signature header for a payload sent at `timestamp`
+pub fn signature_header(secret: &str, timestamp: i64, payload: &str) -> String {
+    let signature = payload_mac(secret, timestamp, payload).finalize().into_bytes();
+    format!("t={},v1={}", timestamp, hex::encode(signature))
+}
+
+/// Check a signature header the way a receiver should: timestamp within tolerance and
+/// a constant-time match on any `v1` signature
+pub fn verify_signature(secret: &str, header: &str, payload: &str, now: i64) -> Result<(), WebhookSignatureError> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.push(hex::decode(value).map_err(|_| WebhookSignatureError::Malformed)?),
+            _ => {}
+        }
+    }
+
+    let timestamp = timestamp.ok_or(WebhookSignatureError::Malformed)?;
+    if signatures.is_empty() {
+        return Err(WebhookSignatureError::Malformed);
+    }
+    if (now - timestamp).abs() > WEBHOOK_SIGNATURE_TOLERANCE_SECS {
+        return Err(WebhookSignatureError::Expired);
+    }
+
+    let mac = payload_mac(secret, timestamp, payload);
+    if signatures.iter().any(|signature| mac.clone().verify_slice(signature).is_ok()) {
+        Ok(())
+    } else {
+        Err(WebhookSignatureError::Mismatch)
+    }
+}
+
+/// Delay before retrying after `attempts` failed attempts, or `None` once they're used up
+pub fn retry_delay(attempts: i32) -> Option<chrono::Duration> {
+    if attempts >= WEBHOOK_MAX_ATTEMPTS {
+        return None;
+    }
+    let exponent = attempts.saturating_sub(1).clamp(0, 30) as u32;
+    let secs = WEBHOOK_RETRY_BASE_SECS.saturating_mul(2i64.saturating_pow(exponent));
+    Some(chrono::Duration::seconds(secs.min(WEBHOOK_RETRY_MAX_SECS)))
+}
+
+pub fn http_client() -> Result<Client> {
+    Ok(Client::builder()
+        .timeout(Duration::from_secs(WEBHOOK_REQUEST_TIMEOUT_SECS))
+        .build()?)
+}
+
+/// Sign and POST a payload to an endpoint, succeeding on any 2xx response
+pub async fn send_webhook(
+    client: &Client,
+    url: &str,
+    secret: &str,
+    delivery_id: Uuid,
+    event_type: &str,
+    payload: &str,
+) -> Result<u16, DeliveryError> {
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(WEBHOOK_SIGNATURE_HEADER, signature_header(secret, Utc::now().timestamp(), payload))
+        .header(WEBHOOK_EVENT_HEADER, event_type)
+        .header(WEBHOOK_DELIVERY_HEADER, delivery_id.to_string())
+        .body(payload.to_string())
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        Err(DeliveryError::Status(status.as_u16()))
+    }
+}
+
+/// Queue an event for every endpoint subscribed to it, returning how many were queued
+///
+/// Nothing is sent about a pair once either wallet has been erased.
+pub async fn enqueue(pool: &PgPool, event: &WebhookEvent) -> Result<u64> {
+    if account_deletions::any_address_erased(pool, &[&event.data.address_1, &event.data.address_2]).await? {
+        return Ok(0);
+    }
+
+    let payload = serde_json::to_string(event)?;
+    let addresses = [event.data.address_1.clone(), event.data.address_2.clone()];
+
+    webhooks::enqueue_deliveries(pool, event.id, event.event_type.as_str(), &addresses, &payload).await
+}
+
+async fn deliver(pool: &PgPool, client: &Client, delivery: DueWebhookDelivery) -> Result<bool> {
+    let result = send_webhook(
+        client,
+        &delivery.url,
+        &delivery.secret,
+        delivery.id,
+        &delivery.event_type,
+        &delivery.payload,
+    ).await;
+
+    match result {
+        Ok(status) => {
+            webhooks::mark_delivered(pool, delivery.id, status as i32).await?;
+            Ok(true)
+        }
+        Err(e) => {
+            let retry_at = retry_delay(delivery.attempts).map(|delay| Utc::now() + delay);
+            match retry_at {
+                Some(at) => tracing::warn!(
+                    "Webhook delivery {} to {} failed (attempt {}): {}, retrying at {}",
+                    delivery.id, delivery.url, delivery.attempts, e, at
+                ),
+                None => tracing::error!(
+                    "Webhook delivery {} to {} failed after {} attempts: {}",
+                    delivery.id, delivery.url, delivery.attempts, e
+                ),
+            }
+            webhooks::mark_attempt_failed(
+                pool,
+                delivery.id,
+                e.status().map(i32::from),
+                &e.to_string(),
+                retry_at,
+            ).await?;
+            Ok(false)
+        }
+    }
+}
+
+/// Send one batch of due deliveries concurrently, returning how many succeeded
+pub async fn deliver_due(pool: &PgPool, client: &Client) -> Result<usize> {
+    // Lease past the request timeout so an in-flight delivery isn't claimed twice
+    let lease_until = Utc::now() + chrono::Duration::seconds(2 * WEBHOOK_REQUEST_TIMEOUT_SECS as i64);
+    let due = webhooks::claim_due_deliveries(pool, WEBHOOK_DISPATCH_BATCH_SIZE, lease_until).await?;
+
+    let mut tasks = JoinSet::new();
+    for delivery in due {
+        let pool = pool.clone();
+        let client = client.clone();
+        tasks.spawn(async move { deliver(&pool, &client, delivery).await });
+    }
+
+    let mut delivered = 0;
+    while let Some(result) = tasks.join_next().await {
+        match result {
+            Ok(Ok(true)) => delivered += 1,
+            Ok(Ok(false)) => {}
+            Ok(Err(e)) => tracing::error!("Failed to record webhook delivery: {}", e),
+            Err(e) => tracing::error!("Webhook delivery task panicked: {}", e),
+        }
+    }
+
+    Ok(delivered)
+}
+
+/// Spawn a background task that keeps sending due webhook deliveries
+pub fn spawn_dispatcher(pool: PgPool, client: Client) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(WEBHOOK_DISPATCH_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            match deliver_due(&pool, &client).await {
+                Ok(0) => {}
+                Ok(delivered) => tracing::info!("Delivered {} webhooks", delivered),
+                Err(e) => tracing::error!("Webhook dispatcher failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::{HeaderMap, StatusCode}, routing::post, Router};
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn test_signature_roundtrip() {
+        let secret = generate_secret();
+        let payload = serde_json::to_string(&WebhookEvent::matched("0xAA", "0xbb")).unwrap();
+        let now = Utc::now().timestamp();
+        let header = signature_header(&secret, now, &payload);
+
+        assert!(verify_signature(&secret, &header, &payload, now).is_ok());
+        assert!(matches!(verify_signature("whsec_other", &header, &payload, now), Err(WebhookSignatureError::Mismatch)));
+        assert!(matches!(verify_signature(&secret, &header, &payload.replace("0xaa", "0xcc"), now), Err(WebhookSignatureError::Mismatch)));
+        assert!(matches!(
+            verify_signature(&secret, &header, &payload, now + WEBHOOK_SIGNATURE_TOLERANCE_SECS + 1),
+            Err(WebhookSignatureError::Expired)
+        ));
+        assert!(matches!(verify_signature(&secret, "v1=00", &payload, now), Err(WebhookSignatureError::Malformed)));
+    }
+
+    #[test]
+    fn test_relay_failures_are_not_reverts() {
+        for event_type in WebhookEventType::SUBSCRIBABLE {
+            assert_eq!(event_type.as_str().parse::<WebhookEventType>().unwrap(), event_type);
+        }
+
+        let failed = serde_json::to_value(WebhookEvent::relay_failed("0xAA", "0xbb", "Signature quota reached")).unwrap();
+        assert_eq!(failed["type"], "relay_failed");
+        assert!(failed["data"].get("tx_hash").is_none());
+
+        let reverted = serde_json::to_value(WebhookEvent::reverted("0xAA", "0xbb", "0x01")).unwrap();
+        assert_eq!(reverted["type"], "reverted");
+        assert_eq!(reverted["data"]["tx_hash"], "0x01");
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_and_gives_up() {
+        assert_eq!(retry_delay(1), Some(chrono::Duration::seconds(WEBHOOK_RETRY_BASE_SECS)));
+        assert_eq!(retry_delay(2), Some(chrono::Duration::seconds(WEBHOOK_RETRY_BASE_SECS * 2)));
+        assert_eq!(retry_delay(3), Some(chrono::Duration::seconds(WEBHOOK_RETRY_BASE_SECS * 4)));
+        assert!(retry_delay(WEBHOOK_MAX_ATTEMPTS - 1).unwrap() <= chrono::Duration::seconds(WEBHOOK_RETRY_MAX_SECS));
+        assert_eq!(retry_delay(WEBHOOK_MAX_ATTEMPTS), None);
+    }
+
+    #[tokio::test]
+    async fn test_send_webhook_to_local_receiver() {
+        async fn receive(
+            State(tx): State<mpsc::UnboundedSender<(HeaderMap, String)>>,
+            headers: HeaderMap,
+            body: String,
+        ) -> StatusCode {
+            let _ = tx.send((headers, body));
+            StatusCode::NO_CONTENT
+        }
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .route("/broken", post(|| async { StatusCode::INTERNAL_SERVER_ERROR }))
+            .with_state(tx);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = http_client().unwrap();
+        let secret = generate_secret();
+        let event = WebhookEvent::confirmed("0xAA", "0xBB", "0x1234", 42);
+        let payload = serde_json::to_string(&event).unwrap();
+        let delivery_id = Uuid::new_v4();
+
+        let status = send_webhook(&client, &format!("{}/hook", base), &secret, delivery_id, "confirmed", &payload).await.unwrap();
+        assert_eq!(status, 204);
+
+        let (headers, body) = rx.recv().await.unwrap();
+        assert_eq!(body, payload);
+        assert_eq!(headers[WEBHOOK_EVENT_HEADER], "confirmed");
+        assert_eq!(headers[WEBHOOK_DELIVERY_HEADER], delivery_id.to_string().as_str());
+        let signature = headers[WEBHOOK_SIGNATURE_HEADER].to_str().unwrap();
+        assert!(verify_signature(&secret, signature, &body, Utc::now().timestamp()).is_ok());
+
+        let received: WebhookEvent = serde_json::from_str(&body).unwrap();
+        assert_eq!(received.event_type, WebhookEventType::Confirmed);
+        assert_eq!(received.data.block_number, Some(42));
+
+        let err = send_webhook(&client, &format!("{}/broken", base), &secret, delivery_id, "confirmed", &payload).await.unwrap_err();
+        assert_eq!(err.status(), Some(500));
+    }
+}
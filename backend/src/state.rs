@@ -0,0 +1,62 @@
+use alloy::providers::{DynProvider, Provider, ProviderBuilder};
+use anyhow::Result;
+use sqlx::PgPool;
+use std::sync::Arc;
+use crate::{
+    rate_limit::RateLimiter,
+    services::{contract::ContractService, profile_card::CardCache, rpc_proxy::RpcCache, signers::SignerPicker},
+    Config,
+};
+
+/// State shared by every HTTP handler
+///
+/// Built once at startup so clients keep their connection pools and caches warm
+/// across requests instead of being rebuilt per call.
+#[derive(Debug, Clone)]
+pub struct AppState {
+    pub pool: PgPool,
+    pub config: Config,
+    /// Outbound HTTP (RPC proxy, gas price lookups)
+    pub http: reqwest::Client,
+    /// Read-only Worldchain provider (`FORK_RPC_URL`), built once so its connections are reused
+    pub provider: DynProvider,
+    pub contract: Arc<ContractService>,
+    /// Attestation signers, with the on-chain deny list cached briefly
+    pub signers: Arc<SignerPicker>,
+    /// Backs the per-route `RateLimitLayer`s
+    pub rate_limits: RateLimiter,
+    /// Results of idempotent calls made through the RPC proxy
+    pub rpc_cache: Arc<RpcCache>,
+    /// Rendered profile cards, reused until the profile they show changes
+    pub card_cache: Arc<CardCache>,
+}
+
+impl AppState {
+    pub async fn new(pool: PgPool, config: Config) -> Result<Self> {
+        let http = reqwest::Client::new();
+        let provider = ProviderBuilder::new()
+            .connect_http(config.rpc_url.parse()?)
+            .erased();
+        let contract = ContractService::new(
+            config.rpc_url.clone(),
+            config.together_contract_address.clone(),
+            config.alchemy_api_key.clone(),
+        ).await?
+            .with_http_client(http.clone())
+            .with_provider(provider.clone());
+        let signers = SignerPicker::new(config.signers.clone());
+        let rate_limits = RateLimiter::new(config.rate_limit_store, pool.clone());
+
+        Ok(Self {
+            pool,
+            config,
+            http,
+            provider,
+            contract: Arc::new(contract),
+            signers: Arc::new(signers),
+            rate_limits,
+            rpc_cache: Arc::new(RpcCache::default()),
+            card_cache: Arc::new(CardCache::default()),
+        })
+    }
+}
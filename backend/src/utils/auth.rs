@@ -0,0 +1,319 @@
+use alloy::{
+    primitives::{eip191_hash_message, keccak256, Address, Bytes, Signature},
+    providers::Provider,
+    rpc::types::{TransactionInput, TransactionRequest},
+    sol_types::SolCall,
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::fmt;
+use crate::constants::{ERC1271_MAGIC_VALUE, SIWE_CLOCK_SKEW_SECS, SIWE_MAX_AGE_SECS};
+
+alloy::sol! {
+    function isValidSignature(bytes32 hash, bytes signature) external view returns (bytes4 magicValue);
+}
+
+/// Generate a random bearer token handed to the client once
+pub fn generate_session_token() -> String {
+    hex::encode(rand::random::<[u8; 32]>())
+}
+
+/// Generate a SIWE-compatible (alphanumeric) sign-in nonce
+pub fn generate_auth_nonce() -> String {
+    hex::encode(rand::random::<[u8; 16]>())
+}
+
+/// Hash a bearer token for storage so a database leak doesn't leak sessions
+pub fn hash_session_token(token: &str) -> String {
+    keccak256(token.as_bytes()).to_string()
+}
+
+const SIWE_HEADER_SUFFIX: &str = " wants you to sign in with your Ethereum account:";
+
+/// A sign-in message in the EIP-4361 (SIWE) format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiweMessage<'a> {
+    pub domain: &'a str,
+    pub address: &'a str,
+    pub statement: Option<&'a str>,
+    pub uri: &'a str,
+    pub version: &'a str,
+    pub chain_id: u64,
+    pub nonce: &'a str,
+    pub issued_at: DateTime<Utc>,
+    pub expiration_time: Option<DateTime<Utc>>,
+    pub not_before: Option<DateTime<Utc>>,
+}
+
+/// Why a sign-in message was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiweError {
+    Malformed(&'static str),
+    WrongDomain,
+    WrongChain,
+    Stale,
+    Expired,
+    NotYetValid,
+}
+
+impl SiweError {
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::Malformed(reason) => reason,
+            Self::WrongDomain => "Sign-in message is for a different site",
+            Self::WrongChain => "Sign-in message is for a different chain",
+            Self::Stale => "Sign-in message is too old",
+            Self::Expired => "Sign-in message has expired",
+            Self::NotYetValid => "Sign-in message is not valid yet",
+        }
+    }
+}
+
+impl fmt::Display for SiweError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.reason())
+    }
+}
+
+impl std::error::Error for SiweError {}
+
+fn siwe_field<'a>(line: Option<&'a str>, key: &'static str) -> Result<&'a str, SiweError> {
+    line.and_then(|line| line.strip_prefix(key))
+        .and_then(|rest| rest.strip_prefix(": "))
+        .ok_or(SiweError::Malformed(key))
+}
+
+fn siwe_time(value: &str) -> Result<DateTime<Utc>, SiweError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| SiweError::Malformed("Invalid timestamp in sign-in message"))
+}
+
+impl<'a> SiweMessage<'a> {
+    /// Parse `message` line by line; fields must appear in the order EIP-4361 gives them
+    pub fn parse(message: &'a str) -> Result<Self, SiweError> {
+        let malformed = SiweError::Malformed("Malformed sign-in message");
+        let mut lines = message.lines().peekable();
+
+        let domain = lines.next()
+            .and_then(|line| line.strip_suffix(SIWE_HEADER_SUFFIX))
+            .ok_or(malformed)?;
+        // EIP-4361 allows an optional scheme before the domain
+        let domain = domain.split_once("://").map_or(domain, |(_, host)| host);
+        let address = lines.next().ok_or(malformed)?;
+
+        // A blank line, an optional one-line statement, then blank lines until the fields
+        let mut statement = None;
+        while let Some(line) = lines.next_if(|line| !line.starts_with("URI: ")) {
+            if !line.is_empty() {
+                if statement.is_some() {
+                    return Err(malformed);
+                }
+                statement = Some(line);
+            }
+        }
+
+        let uri = siwe_field(lines.next(), "URI")?;
+        let version = siwe_field(lines.next(), "Version")?;
+        let chain_id = siwe_field(lines.next(), "Chain ID")?
+            .parse()
+            .map_err(|_| SiweError::Malformed("Invalid chain ID in sign-in message"))?;
+        let nonce = siwe_field(lines.next(), "Nonce")?;
+        let issued_at = siwe_time(siwe_field(lines.next(), "Issued At")?)?;
+
+        let mut expiration_time = None;
+        let mut not_before = None;
+        if let Some(line) = lines.next_if(|line| line.starts_with("Expiration Time: ")) {
+            expiration_time = Some(siwe_time(siwe_field(Some(line), "Expiration Time")?)?);
+        }
+        if let Some(line) = lines.next_if(|line| line.starts_with("Not Before: ")) {
+            not_before = Some(siwe_time(siwe_field(Some(line), "Not Before")?)?);
+        }
+        lines.next_if(|line| line.starts_with("Request ID: "));
+        if lines.next_if(|line| *line == "Resources:").is_some() {
+            while lines.next_if(|line| line.starts_with("- ")).is_some() {}
+        }
+        if lines.any(|line| !line.is_empty()) {
+            return Err(malformed);
+        }
+
+        Ok(Self {
+            domain,
+            address,
+            statement,
+            uri,
+            version,
+            chain_id,
+            nonce,
+            issued_at,
+            expiration_time,
+            not_before,
+        })
+    }
+
+    /// Check the message was made for this site and chain, and is current at `now`
+    ///
+    /// `uri` must be on `domain` or one of its subdomains (the mini-app is served from
+    /// `miniapp.` while signing in as the app's root domain).
+    pub fn validate(&self, domain: &str, chain_id: u64, now: DateTime<Utc>) -> Result<(), SiweError> {
+        let uri_host = reqwest::Url::parse(self.uri)
+            .ok()
+            .and_then(|uri| uri.host_str().map(str::to_lowercase));
+        let on_domain = |host: &str| {
+            host.eq_ignore_ascii_case(domain)
+                || host.to_lowercase().ends_with(&format!(".{}", domain.to_lowercase()))
+        };
+        if !self.domain.eq_ignore_ascii_case(domain) || !uri_host.is_some_and(|host| on_domain(&host)) {
+            return Err(SiweError::WrongDomain);
+        }
+        if self.version != "1" {
+            return Err(SiweError::Malformed("Unsupported sign-in message version"));
+        }
+        if self.chain_id != chain_id {
+            return Err(SiweError::WrongChain);
+        }
+
+        let skew = chrono::Duration::seconds(SIWE_CLOCK_SKEW_SECS);
+        if self.issued_at > now + skew || now - self.issued_at > chrono::Duration::seconds(SIWE_MAX_AGE_SECS) {
+            return Err(SiweError::Stale);
+        }
+        if self.expiration_time.is_some_and(|expires| expires <= now) {
+            return Err(SiweError::Expired);
+        }
+        if self.not_before.is_some_and(|not_before| not_before > now + skew) {
+            return Err(SiweError::NotYetValid);
+        }
+        Ok(())
+    }
+}
+
+/// Verify a personal_sign signature from either an EOA or an ERC-1271 smart wallet
+///
+/// World App accounts are Safe wallets, so a failed ecrecover falls back to
+/// calling `isValidSignature` on the wallet contract, but only if `address` has code:
+/// a bad signature from a plain account costs one `eth_getCode`, not an `eth_call`.
+pub async fn verify_wallet_signature<P: Provider>(
+    provider: &P,
+    address: Address,
+    message: &str,
+    signature: &str,
+) -> Result<bool> {
+    let sig_bytes = hex::decode(signature.trim_start_matches("0x"))
+        .map_err(|e| anyhow::anyhow!("Invalid signature hex: {}", e))?;
+
+    let recovered = Signature::try_from(sig_bytes.as_slice())
+        .ok()
+        .and_then(|sig| sig.recover_address_from_msg(message).ok());
+    if recovered == Some(address) {
+        return Ok(true);
+    }
+
+    match provider.get_code_at(address).await {
+        Ok(code) if code.is_empty() => return Ok(false),
+        Ok(_) => {}
+        Err(e) => {
+            tracing::debug!("Code lookup failed for {}: {}", address, e);
+            return Ok(false);
+        }
+    }
+
+    let call = isValidSignatureCall {
+        hash: eip191_hash_message(message),
+        signature: sig_bytes.into(),
+    };
+    let tx = TransactionRequest::default()
+        .to(address)
+        .input(TransactionInput::new(Bytes::from(call.abi_encode())));
+
+    match provider.call(tx).await {
+        Ok(result) => Ok(result.len() >= 4 && result[..4] == ERC1271_MAGIC_VALUE),
+        Err(e) => {
+            tracing::debug!("ERC-1271 check failed for {}: {}", address, e);
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{providers::ProviderBuilder, signers::{local::PrivateKeySigner, Signer}};
+
+    const MESSAGE: &str = "togetherapp.app wants you to sign in with your Ethereum account:\n0xAefC770D8515C552C952a30e597d9fbEa99aA756\n\nSign in to Together\n\nURI: https://miniapp.togetherapp.app\nVersion: 1\nChain ID: 480\nNonce: 0123456789abcdef\nIssued At: 2025-09-27T13:31:04Z";
+
+    fn at(time: &str) -> DateTime<Utc> {
+        siwe_time(time).unwrap()
+    }
+
+    #[test]
+    fn test_siwe_fields() {
+        let message = SiweMessage::parse(MESSAGE).unwrap();
+        assert_eq!(message.domain, "togetherapp.app");
+        assert_eq!(message.address, "0xAefC770D8515C552C952a30e597d9fbEa99aA756");
+        assert_eq!(message.statement, Some("Sign in to Together"));
+        assert_eq!(message.nonce, "0123456789abcdef");
+        assert_eq!(message.chain_id, 480);
+        assert_eq!(message.expiration_time, None);
+
+        // MiniKit's shape: optional time bounds and no statement
+        let minikit = "https://togetherapp.app wants you to sign in with your Ethereum account:\n0xAefC770D8515C552C952a30e597d9fbEa99aA756\n\n\nURI: https://togetherapp.app/\nVersion: 1\nChain ID: 480\nNonce: abc12345\nIssued At: 2025-09-27T13:31:04Z\nExpiration Time: 2025-10-04T13:31:04Z\nNot Before: 2025-09-26T13:31:04Z";
+        let message = SiweMessage::parse(minikit).unwrap();
+        assert_eq!(message.domain, "togetherapp.app");
+        assert_eq!(message.statement, None);
+        assert_eq!(message.expiration_time, Some(at("2025-10-04T13:31:04Z")));
+
+        assert!(SiweMessage::parse("0xAefC770D8515C552C952a30e597d9fbEa99aA756").is_err());
+        assert!(SiweMessage::parse(&MESSAGE.replace("Nonce:", "Nonce =")).is_err());
+        assert!(SiweMessage::parse(&format!("{}\nSurprise: field", MESSAGE)).is_err());
+    }
+
+    #[test]
+    fn test_siwe_validation() {
+        let message = SiweMessage::parse(MESSAGE).unwrap();
+        let now = at("2025-09-27T13:33:00Z");
+        assert_eq!(message.validate("togetherapp.app", 480, now), Ok(()));
+        assert_eq!(message.validate("evil.example", 480, now), Err(SiweError::WrongDomain));
+        assert_eq!(message.validate("togetherapp.app", 1, now), Err(SiweError::WrongChain));
+        assert_eq!(message.validate("togetherapp.app", 480, at("2025-09-28T13:31:04Z")), Err(SiweError::Stale));
+        assert_eq!(message.validate("togetherapp.app", 480, at("2025-09-27T12:00:00Z")), Err(SiweError::Stale));
+
+        // The URI must be on the signed-in domain too
+        let moved = MESSAGE.replace("URI: https://miniapp.togetherapp.app", "URI: https://togetherapp.app.evil.example");
+        let moved = SiweMessage::parse(&moved).unwrap();
+        assert_eq!(moved.validate("togetherapp.app", 480, now), Err(SiweError::WrongDomain));
+
+        let expiring = format!("{}\nExpiration Time: 2025-09-27T13:32:00Z", MESSAGE);
+        let expiring = SiweMessage::parse(&expiring).unwrap();
+        assert_eq!(expiring.validate("togetherapp.app", 480, now), Err(SiweError::Expired));
+    }
+
+    #[test]
+    fn test_session_token_hash_is_stable() {
+        let token = generate_session_token();
+        assert_eq!(token.len(), 64);
+        assert_eq!(hash_session_token(&token), hash_session_token(&token));
+        assert_ne!(hash_session_token(&token), hash_session_token(&generate_session_token()));
+    }
+
+    #[tokio::test]
+    async fn test_verify_eoa_signature() {
+        let signer = PrivateKeySigner::random();
+        let signature = signer.sign_message(MESSAGE.as_bytes()).await.unwrap();
+        let signature = format!("0x{}", hex::encode(signature.as_bytes()));
+
+        // EOA signatures verify locally, so the RPC URL is never touched
+        let provider = ProviderBuilder::new().connect_http("http://127.0.0.1:1".parse().unwrap());
+        let valid = verify_wallet_signature(&provider, signer.address(), MESSAGE, &signature)
+            .await
+            .unwrap();
+        assert!(valid);
+
+        // Someone else's signature is refused without an `isValidSignature` call
+        let other = PrivateKeySigner::random().sign_message(MESSAGE.as_bytes()).await.unwrap();
+        let other = format!("0x{}", hex::encode(other.as_bytes()));
+        let valid = verify_wallet_signature(&provider, signer.address(), MESSAGE, &other)
+            .await
+            .unwrap();
+        assert!(!valid);
+    }
+}
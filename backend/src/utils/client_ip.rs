@@ -0,0 +1,236 @@
+//! Client IP resolution that only believes forwarding headers from trusted proxies
+//!
+//! The address is resolved once per request by [`resolve_client_ip`] and read back
+//! through the [`ClientIp`] extractor, so rate limits and logs always agree on it.
+
+use anyhow::Result;
+use axum::{
+    extract::{ConnectInfo, FromRequestParts, Request, State},
+    http::{request::Parts, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    convert::Infallible,
+    fmt,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::Arc,
+};
+use crate::constants::{CLOUDFLARE_PROXY_RANGES, PRIVATE_PROXY_RANGES, RAILWAY_PROXY_RANGES};
+
+/// An IP network such as `10.0.0.0/8`; a bare address is a single host
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                prefix_matches(u32::from(network).into(), u32::from(ip).into(), self.prefix, 32)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_matches(u128::from(network), u128::from(ip), self.prefix, 128)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(network: u128, ip: u128, prefix: u8, bits: u8) -> bool {
+    if prefix == 0 {
+        return true;
+    }
+    let shift = bits - prefix;
+    network >> shift == ip >> shift
+}
+
+impl FromStr for Cidr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (address, prefix) = match s.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (s, None),
+        };
+        let network: IpAddr = address.trim().parse()
+            .map_err(|_| anyhow::anyhow!("Invalid proxy address: {}", s))?;
+        let network = network.to_canonical();
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.trim().parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= max)
+                .ok_or_else(|| anyhow::anyhow!("Invalid prefix length in {}", s))?,
+            None => max,
+        };
+        Ok(Self { network, prefix })
+    }
+}
+
+/// Proxies allowed to tell us who the client is, from `TRUSTED_PROXIES`
+///
+/// A comma-separated list of CIDRs, addresses and the presets `cloudflare`, `railway`
+/// and `private`. Empty (the default) trusts nobody: forwarding headers are ignored
+/// and the socket's peer address is the client.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Arc<Vec<Cidr>>);
+
+impl FromStr for TrustedProxies {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut ranges = Vec::new();
+        for entry in s.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let preset = match entry.to_lowercase().as_str() {
+                "cloudflare" => Some(CLOUDFLARE_PROXY_RANGES),
+                "railway" => Some(RAILWAY_PROXY_RANGES),
+                "private" => Some(PRIVATE_PROXY_RANGES),
+                _ => None,
+            };
+            match preset {
+                Some(preset) => ranges.extend(preset.iter().map(|range| range.parse::<Cidr>()).collect::<Result<Vec<_>>>()?),
+                None => ranges.push(entry.parse()?),
+            }
+        }
+        Ok(Self(Arc::new(ranges)))
+    }
+}
+
+impl TrustedProxies {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.0.iter().any(|range| range.contains(ip))
+    }
+
+    /// The client behind `peer`, the address that opened the connection
+    ///
+    /// Walks `X-Forwarded-For` from the right while each hop is a trusted proxy; the
+    /// first untrusted hop is the client. Hops to its left were written by the client
+    /// itself and are never believed. Other forwarding headers are ignored.
+    pub fn resolve(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> Option<IpAddr> {
+        let peer = peer?.to_canonical();
+        if !self.contains(peer) {
+            return Some(peer);
+        }
+
+        let forwarded: Vec<&str> = headers.get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect();
+
+        let mut client = peer;
+        for hop in forwarded.into_iter().rev() {
+            // Garbage from a trusted proxy means we can't see further; stop at the last good hop
+            let Ok(ip) = hop.parse::<IpAddr>() else { break };
+            client = ip.to_canonical();
+            if !self.contains(client) {
+                break;
+            }
+        }
+        Some(client)
+    }
+}
+
+/// The caller's address as resolved by [`resolve_client_ip`]; `None` without connection info
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl fmt::Display for ClientIp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(ip) => write!(f, "{}", ip),
+            None => write!(f, "unknown"),
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(ClientIp::from_parts(parts))
+    }
+}
+
+impl ClientIp {
+    pub fn from_parts(parts: &Parts) -> Self {
+        parts.extensions.get::<ClientIp>().copied().unwrap_or(ClientIp(None))
+    }
+}
+
+/// Middleware: resolve the client IP once and store it for [`ClientIp`]
+///
+/// The peer address comes from `ConnectInfo`, so the server must be started with
+/// `into_make_service_with_connect_info::<SocketAddr>()`.
+pub async fn resolve_client_ip(State(trusted): State<TrustedProxies>, mut request: Request, next: Next) -> Response {
+    let peer = request.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let client_ip = ClientIp(trusted.resolve(request.headers(), peer));
+    request.extensions_mut().insert(client_ip);
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn forwarded_for(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_cidr_contains() {
+        let range: Cidr = "10.0.0.0/8".parse().unwrap();
+        assert!(range.contains(ip("10.20.30.40")));
+        assert!(range.contains(ip("::ffff:10.0.0.1")));
+        assert!(!range.contains(ip("11.0.0.1")));
+        assert!("2606:4700::/32".parse::<Cidr>().unwrap().contains(ip("2606:4700:10::1")));
+        assert!("0.0.0.0/0".parse::<Cidr>().unwrap().contains(ip("8.8.8.8")));
+        assert!("1.2.3.4".parse::<Cidr>().unwrap().contains(ip("1.2.3.4")));
+        assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+        assert!("nope/8".parse::<Cidr>().is_err());
+    }
+
+    #[test]
+    fn test_untrusted_peer_headers_are_ignored() {
+        let trusted: TrustedProxies = "".parse().unwrap();
+        let headers = forwarded_for("1.1.1.1");
+        assert_eq!(trusted.resolve(&headers, Some(ip("203.0.113.9"))), Some(ip("203.0.113.9")));
+        assert_eq!(trusted.resolve(&headers, None), None);
+    }
+
+    #[test]
+    fn test_forwarded_for_is_walked_from_the_right() {
+        let trusted: TrustedProxies = "railway, cloudflare".parse().unwrap();
+        let peer = Some(ip("100.64.0.2"));
+
+        // Client-written hops to the left of the real client are skipped
+        let headers = forwarded_for("6.6.6.6, 203.0.113.9, 172.70.1.1");
+        assert_eq!(trusted.resolve(&headers, peer), Some(ip("203.0.113.9")));
+
+        // Every hop trusted: the leftmost is as far as we can see
+        assert_eq!(trusted.resolve(&forwarded_for("172.70.1.1"), peer), Some(ip("172.70.1.1")));
+        assert_eq!(trusted.resolve(&HeaderMap::new(), peer), peer);
+        assert_eq!(trusted.resolve(&forwarded_for("garbage, 172.70.1.1"), peer), Some(ip("172.70.1.1")));
+    }
+
+    #[test]
+    fn test_trusted_proxies_from_str() {
+        assert!("private".parse::<TrustedProxies>().unwrap().contains(ip("192.168.1.1")));
+        assert!("10.1.0.0/16, ::1".parse::<TrustedProxies>().unwrap().contains(ip("::1")));
+        assert!("10.1.0.0/16, bogus".parse::<TrustedProxies>().is_err());
+    }
+}
@@ -1,39 +1,77 @@
 use anyhow::Result;
 use std::env;
-use crate::constants::DEFAULT_SERVER_PORT;
+use crate::constants::{DEFAULT_PUBLIC_APP_URL, DEFAULT_SERVER_PORT, DEFAULT_SIWE_DOMAIN};
+use crate::rate_limit::StoreKind;
+use crate::utils::client_ip::TrustedProxies;
+use crate::services::proximity::ProximityPolicy;
+use crate::utils::signer::{SignerSet, SignerSource};
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub database_url: String,
     pub port: u16,
+    pub public_base_url: String,
+    pub public_app_url: String,
+    /// Domain sign-in messages must name
+    pub siwe_domain: String,
     pub rpc_url: String,
     pub together_contract_address: String,
     pub alchemy_api_key: String,
-    pub private_key_signer: String,
-    pub private_key_deployer: String,
+    pub signers: SignerSet,
+    pub deployer: SignerSource,
+    pub proximity_policy: ProximityPolicy,
+    pub rate_limit_store: StoreKind,
+    pub trusted_proxies: TrustedProxies,
 }
 
 impl Config {
     pub fn from_env() -> Result<Self> {
         dotenvy::dotenv().ok(); // Load .env file if present
         
+        let port = env::var("PORT")
+            .unwrap_or_else(|_| DEFAULT_SERVER_PORT.to_string())
+            .parse()
+            .unwrap_or(DEFAULT_SERVER_PORT);
+        
         Ok(Self {
             database_url: env::var("DATABASE_URL")
                 .map_err(|_| anyhow::anyhow!("DATABASE_URL must be set"))?,
-            port: env::var("PORT")
-                .unwrap_or_else(|_| DEFAULT_SERVER_PORT.to_string())
-                .parse()
-                .unwrap_or(DEFAULT_SERVER_PORT),
+            port,
+            // Absolute URLs in collectible metadata point here
+            public_base_url: env::var("PUBLIC_BASE_URL")
+                .ok()
+                .filter(|url| !url.is_empty())
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| format!("http://localhost:{}", port)),
+            // Shared profile links (and their QR codes) point here
+            public_app_url: env::var("PUBLIC_APP_URL")
+                .ok()
+                .filter(|url| !url.is_empty())
+                .map(|url| url.trim_end_matches('/').to_string())
+                .unwrap_or_else(|| DEFAULT_PUBLIC_APP_URL.to_string()),
+            siwe_domain: env::var("SIWE_DOMAIN")
+                .ok()
+                .filter(|domain| !domain.is_empty())
+                .unwrap_or_else(|| DEFAULT_SIWE_DOMAIN.to_string()),
             rpc_url: env::var("FORK_RPC_URL")
                 .map_err(|_| anyhow::anyhow!("FORK_RPC_URL must be set"))?,
             together_contract_address: env::var("TOGETHER_CONTRACT_ADDRESS")
                 .map_err(|_| anyhow::anyhow!("TOGETHER_CONTRACT_ADDRESS must be set"))?,
             alchemy_api_key: env::var("ALCHEMY_API_KEY")
                 .map_err(|_| anyhow::anyhow!("ALCHEMY_API_KEY must be set"))?,
-            private_key_signer: env::var("PRIVATE_KEY_SIGNER")
-                .map_err(|_| anyhow::anyhow!("PRIVATE_KEY_SIGNER must be set"))?,
-            private_key_deployer: env::var("PRIVATE_KEY_DEPLOYER")
-                .map_err(|_| anyhow::anyhow!("PRIVATE_KEY_DEPLOYER must be set"))?,
+            signers: SignerSet::from_env()?,
+            deployer: SignerSource::from_env("DEPLOYER", "PRIVATE_KEY_DEPLOYER")?,
+            proximity_policy: env::var("PROXIMITY_POLICY")
+                .unwrap_or_default()
+                .parse()?,
+            // `postgres` when running more than one replica
+            rate_limit_store: env::var("RATE_LIMIT_STORE")
+                .unwrap_or_default()
+                .parse()?,
+            // Only these may set X-Forwarded-For; e.g. `railway,cloudflare`
+            trusted_proxies: env::var("TRUSTED_PROXIES")
+                .unwrap_or_default()
+                .parse()?,
         })
     }
 }
@@ -1,12 +1,13 @@
 use alloy::{
     primitives::{Address, U256},
-    signers::{local::PrivateKeySigner, Signer},
     sol_types::{Eip712Domain, SolStruct},
 };
 use anyhow::Result;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use crate::constants::*;
+use crate::utils::signer::SignerBackend;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TogetherSignatureData {
@@ -31,14 +32,12 @@ alloy::sol! {
 }
 
 pub struct Eip712Signer {
-    signer: PrivateKeySigner,
+    signer: Arc<dyn SignerBackend>,
     domain: Eip712Domain,
 }
 
 impl Eip712Signer {
-    pub fn new(private_key: &str, chain_id: u64) -> Result<Self> {
-        let signer = private_key.parse::<PrivateKeySigner>()?;
-        
+    pub fn new(signer: Arc<dyn SignerBackend>, chain_id: u64) -> Self {
         let domain = Eip712Domain {
             name: Some(TOGETHER_DOMAIN_NAME.to_string().into()),
             version: Some(TOGETHER_DOMAIN_VERSION.to_string().into()),
@@ -47,7 +46,12 @@ impl Eip712Signer {
             salt: None,
         };
 
-        Ok(Self { signer, domain })
+        Self { signer, domain }
+    }
+
+    /// Address of the key producing signatures
+    pub fn address(&self) -> Address {
+        self.signer.address()
     }
 
     pub async fn sign_together_permit(
@@ -136,6 +140,7 @@ impl Eip712Signer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::signer::InMemorySigner;
     use std::env;
 
     #[test]
@@ -169,8 +174,9 @@ mod tests {
             .expect("Invalid contract address format");
 
         // Create signer
-        let signer = Eip712Signer::new(&private_key, WORLDCHAIN_MAINNET_CHAIN_ID)
-            .expect("Failed to create EIP712 signer");
+        let backend = InMemorySigner::from_private_key(&private_key)
+            .expect("Failed to load private key");
+        let signer = Eip712Signer::new(Arc::new(backend), WORLDCHAIN_MAINNET_CHAIN_ID);
 
         // Real test data for end-to-end test with Plotchy wallet
         let plotchy_wallet: Address = "0xAefC770D8515C552C952a30e597d9fbEa99aA756".parse().unwrap();
@@ -180,7 +186,7 @@ mod tests {
         let deadline = 1850000000; // Wed Aug 16 2028
 
         println!("\n=== EIP-712 Signature Test Data ===");
-        println!("Signer Address: {}", signer.signer.address());
+        println!("Signer Address: {}", signer.address());
         println!("Contract Address: {}", contract_address);
         println!("Chain ID: {}", WORLDCHAIN_MAINNET_CHAIN_ID);
         println!("Domain Name: {}", TOGETHER_DOMAIN_NAME);
@@ -1,6 +1,11 @@
 pub mod config;
 pub mod logging;
 pub mod eip712;
+pub mod auth;
+pub mod pairing_code;
+pub mod signer;
+pub mod pair_token;
+pub mod client_ip;
 
 pub use config::Config;
 pub use logging::init_logging;
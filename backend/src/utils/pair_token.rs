@@ -0,0 +1,30 @@
+use alloy::{
+    primitives::{Address, U256, keccak256},
+    sol_types::SolValue,
+};
+
+/// Deterministic collectible token id for a pair: `uint256(keccak256(abi.encode(low, high)))`
+///
+/// The lower address goes first, so the id doesn't depend on who attested.
+pub fn pair_token_id(a: Address, b: Address) -> U256 {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    U256::from_be_bytes(keccak256((low, high).abi_encode_params()).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pair_token_id_is_order_independent() {
+        let a: Address = "0x59888BE579194C701F16a9425f57ECce3906AF4b".parse().unwrap();
+        let b: Address = "0xAefC770D8515C552C952a30e597d9fbEa99aA756".parse().unwrap();
+        assert_eq!(pair_token_id(a, b), pair_token_id(b, a));
+        assert_ne!(pair_token_id(a, b), pair_token_id(a, Address::ZERO));
+
+        let mut encoded = [0u8; 64];
+        encoded[12..32].copy_from_slice(a.as_slice());
+        encoded[44..64].copy_from_slice(b.as_slice());
+        assert_eq!(pair_token_id(b, a), U256::from_be_bytes(keccak256(encoded).0));
+    }
+}
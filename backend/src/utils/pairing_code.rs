@@ -0,0 +1,79 @@
+use alloy::primitives::keccak256;
+use chrono::{DateTime, TimeZone, Utc};
+use crate::constants::*;
+
+/// Generate a new per-user pairing secret (hex encoded)
+pub fn generate_pairing_secret() -> String {
+    hex::encode(rand::random::<[u8; 32]>())
+}
+
+/// Index of the rotation window containing `now`
+pub fn window_index(now: DateTime<Utc>) -> i64 {
+    now.timestamp().div_euclid(PAIRING_CODE_ROTATION_SECS)
+}
+
+/// When a code for `window` stops resolving, including the grace period
+pub fn window_expires_at(window: i64) -> DateTime<Utc> {
+    let end = (window + 1) * PAIRING_CODE_ROTATION_SECS + PAIRING_CODE_GRACE_SECS;
+    Utc.timestamp_opt(end, 0).single().unwrap_or_else(Utc::now)
+}
+
+/// Derive the numeric pairing code for a secret and rotation window
+///
+/// TOTP-style: keccak256(secret || window) is a sound MAC (no length extension),
+/// dynamically truncated to `PAIRING_CODE_DIGITS` decimal digits.
+pub fn derive_pairing_code(secret: &str, window: i64) -> String {
+    let mut input = secret.as_bytes().to_vec();
+    input.extend_from_slice(&window.to_be_bytes());
+    let digest = keccak256(&input);
+
+    let offset = (digest[31] & 0x0f) as usize;
+    let truncated = u32::from_be_bytes([digest[offset] & 0x7f, digest[offset + 1], digest[offset + 2], digest[offset + 3]]);
+    let code = truncated % 10u32.pow(PAIRING_CODE_DIGITS);
+
+    format!("{:0width$}", code, width = PAIRING_CODE_DIGITS as usize)
+}
+
+/// Normalize user input (strip spaces and dashes) and check it looks like a code
+pub fn normalize_pairing_code(input: &str) -> Option<String> {
+    let code: String = input.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+    if code.len() == PAIRING_CODE_DIGITS as usize && code.chars().all(|c| c.is_ascii_digit()) {
+        Some(code)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pairing_code_is_deterministic_per_window() {
+        let secret = generate_pairing_secret();
+        let code = derive_pairing_code(&secret, 1000);
+        assert_eq!(code.len(), PAIRING_CODE_DIGITS as usize);
+        assert!(code.chars().all(|c| c.is_ascii_digit()));
+        assert_eq!(code, derive_pairing_code(&secret, 1000));
+
+        // Different windows should (almost always) give different codes
+        let distinct = (0..20).map(|w| derive_pairing_code(&secret, w)).collect::<std::collections::HashSet<_>>();
+        assert!(distinct.len() > 15);
+    }
+
+    #[test]
+    fn test_window_expiry_includes_grace() {
+        let now = Utc.timestamp_opt(PAIRING_CODE_ROTATION_SECS * 10 + 5, 0).unwrap();
+        let window = window_index(now);
+        assert_eq!(window, 10);
+        assert_eq!(window_expires_at(window).timestamp(), PAIRING_CODE_ROTATION_SECS * 11 + PAIRING_CODE_GRACE_SECS);
+    }
+
+    #[test]
+    fn test_normalize_pairing_code() {
+        assert_eq!(normalize_pairing_code("123-456"), Some("123456".to_string()));
+        assert_eq!(normalize_pairing_code(" 123 456 "), Some("123456".to_string()));
+        assert_eq!(normalize_pairing_code("12345"), None);
+        assert_eq!(normalize_pairing_code("12345a"), None);
+    }
+}
@@ -0,0 +1,472 @@
+use alloy::{
+    consensus::SignableTransaction,
+    network::{EthereumWallet, TxSigner},
+    primitives::{Address, B256, Signature},
+    signers::{local::PrivateKeySigner, Signer},
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::{env, fmt, path::PathBuf, sync::Arc};
+
+/// Something that can produce secp256k1 signatures over 32-byte hashes for one address
+///
+/// Used both for EIP-712 attestation signatures and for signing relayed transactions,
+/// so production keys don't have to live in environment variables.
+#[async_trait]
+pub trait SignerBackend: Send + Sync + fmt::Debug {
+    fn address(&self) -> Address;
+
+    async fn sign_hash(&self, hash: &B256) -> Result<Signature>;
+}
+
+/// A raw private key held in memory
+pub struct InMemorySigner {
+    signer: PrivateKeySigner,
+}
+
+impl InMemorySigner {
+    pub fn from_private_key(private_key: &str) -> Result<Self> {
+        let signer = private_key.parse::<PrivateKeySigner>()?;
+        Ok(Self { signer })
+    }
+}
+
+impl fmt::Debug for InMemorySigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InMemorySigner").field("address", &self.signer.address()).finish()
+    }
+}
+
+#[async_trait]
+impl SignerBackend for InMemorySigner {
+    fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    async fn sign_hash(&self, hash: &B256) -> Result<Signature> {
+        Ok(self.signer.sign_hash(hash).await?)
+    }
+}
+
+/// A key decrypted from an encrypted JSON (Web3 secret storage) keystore file
+pub struct KeystoreSigner {
+    path: PathBuf,
+    signer: PrivateKeySigner,
+}
+
+impl KeystoreSigner {
+    pub fn open(path: impl Into<PathBuf>, password: &str) -> Result<Self> {
+        let path = path.into();
+        let signer = PrivateKeySigner::decrypt_keystore(&path, password)
+            .map_err(|e| anyhow::anyhow!("Failed to decrypt keystore {}: {}", path.display(), e))?;
+        Ok(Self { path, signer })
+    }
+}
+
+impl fmt::Debug for KeystoreSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeystoreSigner")
+            .field("path", &self.path)
+            .field("address", &self.signer.address())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl SignerBackend for KeystoreSigner {
+    fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    async fn sign_hash(&self, hash: &B256) -> Result<Signature> {
+        Ok(self.signer.sign_hash(hash).await?)
+    }
+}
+
+// Wire format of the remote signer: POST {url}/sign
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoteSignRequest {
+    pub address: Address,
+    pub hash: B256,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoteSignResponse {
+    pub signature: String,
+}
+
+/// A signing service reached over HTTP
+///
+/// The expected address is pinned in config, and every returned signature is checked
+/// to recover to it so a misbehaving signer can't hand us someone else's signatures.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    client: reqwest::Client,
+    url: String,
+    address: Address,
+    auth_token: Option<String>,
+}
+
+impl RemoteSigner {
+    pub fn new(url: impl Into<String>, address: Address, auth_token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into().trim_end_matches('/').to_string(),
+            address,
+            auth_token,
+        }
+    }
+}
+
+#[async_trait]
+impl SignerBackend for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_hash(&self, hash: &B256) -> Result<Signature> {
+        let mut request = self.client
+            .post(format!("{}/sign", self.url))
+            .json(&RemoteSignRequest { address: self.address, hash: *hash })
+            .timeout(std::time::Duration::from_secs(10));
+        if let Some(token) = &self.auth_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response: RemoteSignResponse = request.send().await?.error_for_status()?.json().await?;
+        let signature: Signature = response.signature.parse()
+            .map_err(|e| anyhow::anyhow!("Remote signer returned an invalid signature: {}", e))?;
+
+        let recovered = signature.recover_address_from_prehash(hash)?;
+        if recovered != self.address {
+            return Err(anyhow::anyhow!(
+                "Remote signer returned a signature for {} instead of {}",
+                recovered,
+                self.address
+            ));
+        }
+
+        Ok(signature)
+    }
+}
+
+// Adapter so a backend can sign transactions through an alloy wallet
+#[derive(Debug, Clone)]
+struct BackendTxSigner(Arc<dyn SignerBackend>);
+
+#[async_trait]
+impl TxSigner<Signature> for BackendTxSigner {
+    fn address(&self) -> Address {
+        self.0.address()
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<Signature>,
+    ) -> alloy::signers::Result<Signature> {
+        self.0.sign_hash(&tx.signature_hash()).await
+            .map_err(|e| alloy::signers::Error::other(e.to_string()))
+    }
+}
+
+/// Wrap a backend as an `EthereumWallet` for use with `ProviderBuilder::wallet`
+pub fn ethereum_wallet(backend: Arc<dyn SignerBackend>) -> EthereumWallet {
+    EthereumWallet::new(BackendTxSigner(backend))
+}
+
+#[derive(Clone)]
+enum SignerKind {
+    PrivateKey(String),
+    Keystore { path: PathBuf, password_file: Option<PathBuf> },
+    Remote { url: String, address: Address, auth_token: Option<String> },
+}
+
+/// Where a signer comes from, read from `{PREFIX}_*` environment variables
+///
+/// `{PREFIX}_BACKEND` selects `key` (default, the legacy raw hex key variable),
+/// `keystore` (`{PREFIX}_KEYSTORE_PATH`, password from `{PREFIX}_KEYSTORE_PASSWORD_FILE`
+/// or an interactive prompt) or `remote` (`{PREFIX}_REMOTE_URL`, `{PREFIX}_REMOTE_ADDRESS`,
+/// optional `{PREFIX}_REMOTE_TOKEN`). The backend is only loaded on first use, so binaries
+/// that never sign don't prompt for passwords.
+#[derive(Clone)]
+pub struct SignerSource {
+    name: String,
+    kind: SignerKind,
+    loaded: Arc<OnceCell<Arc<dyn SignerBackend>>>,
+}
+
+impl SignerSource {
+    pub fn from_env(prefix: &str, private_key_var: &str) -> Result<Self> {
+        let var = |suffix: &str| env::var(format!("{}_{}", prefix, suffix)).ok().filter(|v| !v.is_empty());
+        let require = |suffix: &str| var(suffix)
+            .ok_or_else(|| anyhow::anyhow!("{}_{} must be set", prefix, suffix));
+
+        let kind = match var("BACKEND").as_deref().unwrap_or("key") {
+            "key" => SignerKind::PrivateKey(
+                env::var(private_key_var).map_err(|_| anyhow::anyhow!("{} must be set", private_key_var))?,
+            ),
+            "keystore" => SignerKind::Keystore {
+                path: require("KEYSTORE_PATH")?.into(),
+                password_file: var("KEYSTORE_PASSWORD_FILE").map(PathBuf::from),
+            },
+            "remote" => SignerKind::Remote {
+                url: require("REMOTE_URL")?,
+                address: require("REMOTE_ADDRESS")?.parse()
+                    .map_err(|_| anyhow::anyhow!("{}_REMOTE_ADDRESS is not a valid address", prefix))?,
+                auth_token: var("REMOTE_TOKEN"),
+            },
+            other => return Err(anyhow::anyhow!(
+                "Invalid {}_BACKEND '{}', expected key, keystore or remote",
+                prefix,
+                other
+            )),
+        };
+
+        Ok(Self { name: prefix.to_string(), kind, loaded: Arc::new(OnceCell::new()) })
+    }
+
+    /// Load the backend on first call (decrypting or prompting as needed) and reuse it after
+    pub fn backend(&self) -> Result<Arc<dyn SignerBackend>> {
+        self.loaded.get_or_try_init(|| self.load()).cloned()
+    }
+
+    fn load(&self) -> Result<Arc<dyn SignerBackend>> {
+        let backend: Arc<dyn SignerBackend> = match &self.kind {
+            SignerKind::PrivateKey(key) => Arc::new(InMemorySigner::from_private_key(key)?),
+            SignerKind::Keystore { path, password_file } => {
+                let password = match password_file {
+                    Some(file) => std::fs::read_to_string(file)
+                        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", file.display(), e))?
+                        .trim_end_matches(['\r', '\n'])
+                        .to_string(),
+                    None => rpassword::prompt_password(format!("Password for {} keystore {}: ", self.name, path.display()))?,
+                };
+                Arc::new(KeystoreSigner::open(path, &password)?)
+            }
+            SignerKind::Remote { url, address, auth_token } => {
+                Arc::new(RemoteSigner::new(url.clone(), *address, auth_token.clone()))
+            }
+        };
+
+        tracing::info!("Loaded {} signer {:?}", self.name, backend);
+        Ok(backend)
+    }
+}
+
+impl fmt::Debug for SignerSource {
+    // Never print the raw key
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let backend = match &self.kind {
+            SignerKind::PrivateKey(_) => "key",
+            SignerKind::Keystore { .. } => "keystore",
+            SignerKind::Remote { .. } => "remote",
+        };
+        f.debug_struct("SignerSource")
+            .field("name", &self.name)
+            .field("backend", &backend)
+            .field("loaded", &self.loaded.get())
+            .finish()
+    }
+}
+
+/// One attestation key in the rotation set
+#[derive(Debug, Clone)]
+pub struct SignerKey {
+    pub source: SignerSource,
+    pub weight: u32,
+    pub active_from: Option<DateTime<Utc>>,
+    pub active_until: Option<DateTime<Utc>>,
+}
+
+impl SignerKey {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.weight > 0
+            && self.active_from.is_none_or(|from| now >= from)
+            && self.active_until.is_none_or(|until| now < until)
+    }
+}
+
+/// The attestation signers the backend may choose from
+///
+/// `SIGNER_KEYS` lists key names (e.g. `current,next`); each name `N` is a signer source
+/// with prefix `SIGNER_N` (raw key in `SIGNER_N_PRIVATE_KEY`) plus optional
+/// `SIGNER_N_WEIGHT` (default 1), `SIGNER_N_ACTIVE_FROM` and `SIGNER_N_ACTIVE_UNTIL`
+/// (RFC 3339). Without `SIGNER_KEYS` the single `SIGNER` source is used.
+///
+/// To rotate, add the new key with an `ACTIVE_FROM`, give the old one an `ACTIVE_UNTIL`,
+/// and only call `denySigner` on it once signatures it issued have passed their deadline.
+#[derive(Debug, Clone)]
+pub struct SignerSet {
+    keys: Vec<SignerKey>,
+}
+
+impl SignerSet {
+    pub fn new(keys: Vec<SignerKey>) -> Self {
+        Self { keys }
+    }
+
+    pub fn from_env() -> Result<Self> {
+        let names = env::var("SIGNER_KEYS").unwrap_or_default();
+        let names: Vec<&str> = names.split(',').map(str::trim).filter(|name| !name.is_empty()).collect();
+        if names.is_empty() {
+            return Ok(Self::new(vec![SignerKey {
+                source: SignerSource::from_env("SIGNER", "PRIVATE_KEY_SIGNER")?,
+                weight: 1,
+                active_from: None,
+                active_until: None,
+            }]));
+        }
+
+        let mut keys = Vec::with_capacity(names.len());
+        for name in names {
+            let prefix = format!("SIGNER_{}", name.to_uppercase());
+            let var = |suffix: &str| env::var(format!("{}_{}", prefix, suffix)).ok().filter(|v| !v.is_empty());
+            let timestamp = |suffix: &str| -> Result<Option<DateTime<Utc>>> {
+                var(suffix)
+                    .map(|value| DateTime::parse_from_rfc3339(&value)
+                        .map(|at| at.with_timezone(&Utc))
+                        .map_err(|_| anyhow::anyhow!("{}_{} must be an RFC 3339 timestamp", prefix, suffix)))
+                    .transpose()
+            };
+
+            keys.push(SignerKey {
+                source: SignerSource::from_env(&prefix, &format!("{}_PRIVATE_KEY", prefix))?,
+                weight: var("WEIGHT")
+                    .map(|weight| weight.parse())
+                    .transpose()
+                    .map_err(|_| anyhow::anyhow!("{}_WEIGHT must be a non-negative integer", prefix))?
+                    .unwrap_or(1),
+                active_from: timestamp("ACTIVE_FROM")?,
+                active_until: timestamp("ACTIVE_UNTIL")?,
+            });
+        }
+
+        Ok(Self::new(keys))
+    }
+
+    /// Load every key up front, so keystore prompts happen at startup
+    pub fn load_all(&self) -> Result<Vec<Arc<dyn SignerBackend>>> {
+        self.keys.iter().map(|key| key.source.backend()).collect()
+    }
+
+    /// Keys that may sign at `now`, skipping any whose address has been denied on chain
+    pub fn active(&self, now: DateTime<Utc>, denied: &[Address]) -> Result<Vec<(Arc<dyn SignerBackend>, u32)>> {
+        let mut active = Vec::new();
+        for key in self.keys.iter().filter(|key| key.is_active(now)) {
+            let backend = key.source.backend()?;
+            if !denied.contains(&backend.address()) {
+                active.push((backend, key.weight));
+            }
+        }
+        Ok(active)
+    }
+}
+
+/// Weighted choice, with `roll` uniformly drawn from `0..total weight`
+pub fn choose_weighted<T: Clone>(candidates: &[(T, u32)], roll: u64) -> Option<T> {
+    let mut remaining = roll;
+    for (candidate, weight) in candidates {
+        if remaining < *weight as u64 {
+            return Some(candidate.clone());
+        }
+        remaining -= *weight as u64;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::k256::elliptic_curve::rand_core::OsRng;
+    use axum::{extract::State, routing::post, Json, Router};
+
+    // Local stand-in for the remote signing service
+    async fn spawn_remote_signer(signer: PrivateKeySigner) -> String {
+        async fn sign(
+            State(signer): State<Arc<PrivateKeySigner>>,
+            Json(req): Json<RemoteSignRequest>,
+        ) -> Json<RemoteSignResponse> {
+            let signature = signer.sign_hash(&req.hash).await.unwrap();
+            Json(RemoteSignResponse { signature: signature.to_string() })
+        }
+
+        let app = Router::new().route("/sign", post(sign)).with_state(Arc::new(signer));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_round_trip() {
+        let key = PrivateKeySigner::random();
+        let url = spawn_remote_signer(key.clone()).await;
+
+        let remote = RemoteSigner::new(url, key.address(), None);
+        let hash = alloy::primitives::keccak256(b"together");
+        let signature = remote.sign_hash(&hash).await.unwrap();
+        assert_eq!(signature.recover_address_from_prehash(&hash).unwrap(), key.address());
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_rejects_wrong_key() {
+        let url = spawn_remote_signer(PrivateKeySigner::random()).await;
+
+        let remote = RemoteSigner::new(url, PrivateKeySigner::random().address(), None);
+        let hash = alloy::primitives::keccak256(b"together");
+        assert!(remote.sign_hash(&hash).await.is_err());
+    }
+
+    #[test]
+    fn test_signer_set_respects_windows_and_denials() {
+        let key = |weight, active_from, active_until| {
+            let signer = PrivateKeySigner::random();
+            let source = SignerSource {
+                name: "SIGNER_TEST".to_string(),
+                kind: SignerKind::PrivateKey(hex::encode(signer.to_bytes())),
+                loaded: Arc::new(OnceCell::new()),
+            };
+            (SignerKey { source, weight, active_from, active_until }, signer.address())
+        };
+
+        let now = Utc::now();
+        let hour = chrono::Duration::hours(1);
+        let (current, current_address) = key(1, None, Some(now + hour));
+        let (retired, _) = key(1, None, Some(now - hour));
+        let (next, next_address) = key(3, Some(now - hour), None);
+        let (future, _) = key(1, Some(now + hour), None);
+        let set = SignerSet::new(vec![current, retired, next, future]);
+
+        let addresses = |active: Vec<(Arc<dyn SignerBackend>, u32)>| {
+            active.into_iter().map(|(backend, weight)| (backend.address(), weight)).collect::<Vec<_>>()
+        };
+        assert_eq!(addresses(set.active(now, &[]).unwrap()), vec![(current_address, 1), (next_address, 3)]);
+        assert_eq!(addresses(set.active(now, &[current_address]).unwrap()), vec![(next_address, 3)]);
+    }
+
+    #[test]
+    fn test_choose_weighted() {
+        let candidates = [("a", 1), ("b", 0), ("c", 3)];
+        assert_eq!(choose_weighted(&candidates, 0), Some("a"));
+        assert_eq!(choose_weighted(&candidates, 1), Some("c"));
+        assert_eq!(choose_weighted(&candidates, 3), Some("c"));
+        assert_eq!(choose_weighted(&candidates, 4), None);
+        assert_eq!(choose_weighted::<&str>(&[], 0), None);
+    }
+
+    #[tokio::test]
+    async fn test_keystore_signer_matches_key() {
+        let dir = std::env::temp_dir().join(format!("together-keystore-{}", hex::encode(rand::random::<[u8; 8]>())));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (key, file_name) = PrivateKeySigner::new_keystore(&dir, &mut OsRng, "hunter2", None).unwrap();
+
+        let keystore = KeystoreSigner::open(dir.join(file_name), "hunter2").unwrap();
+        assert_eq!(keystore.address(), key.address());
+        assert!(KeystoreSigner::open(dir.join("missing.json"), "hunter2").is_err());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,95 @@
+//! Server-Sent Events from `/api/user/{user_id}/events`
+
+use reqwest::Response;
+use crate::{types::PairingEvent, ClientError, Result};
+
+/// One message from the event stream
+#[derive(Debug, Clone)]
+pub enum StreamEvent {
+    Pairing(PairingEvent),
+    /// The server dropped this many events; refetch pending/optimistic connections
+    Lagged(u64),
+}
+
+/// A user's pairing event stream, read with [`EventStream::next`]
+#[derive(Debug)]
+pub struct EventStream {
+    response: Response,
+    buffer: String,
+}
+
+impl EventStream {
+    pub(crate) fn new(response: Response) -> Self {
+        Self { response, buffer: String::new() }
+    }
+
+    /// Wait for the next event, or `None` once the server closes the stream
+    pub async fn next(&mut self) -> Option<Result<StreamEvent>> {
+        loop {
+            if let Some(end) = self.buffer.find("\n\n") {
+                let block: String = self.buffer.drain(..end + 2).collect();
+                match parse_block(&block) {
+                    Some(event) => return Some(event),
+                    // Keep-alive comments carry no event
+                    None => continue,
+                }
+            }
+
+            match self.response.chunk().await {
+                Ok(Some(chunk)) => {
+                    let text = String::from_utf8_lossy(&chunk).replace("\r\n", "\n");
+                    self.buffer.push_str(&text);
+                }
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+}
+
+fn parse_block(block: &str) -> Option<Result<StreamEvent>> {
+    let mut name = None;
+    let mut data = Vec::new();
+    for line in block.lines() {
+        if let Some(value) = line.strip_prefix("event:") {
+            name = Some(value.trim());
+        } else if let Some(value) = line.strip_prefix("data:") {
+            data.push(value.strip_prefix(' ').unwrap_or(value));
+        }
+    }
+    if data.is_empty() {
+        return None;
+    }
+    let data = data.join("\n");
+
+    let event = match name {
+        Some("lagged") => data.trim().parse()
+            .map(StreamEvent::Lagged)
+            .map_err(|_| ClientError::Stream(format!("invalid lagged count '{}'", data))),
+        _ => serde_json::from_str(&data)
+            .map(StreamEvent::Pairing)
+            .map_err(|e| ClientError::Stream(e.to_string())),
+    };
+    Some(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_block() {
+        assert!(parse_block(":\n\n").is_none());
+
+        let matched = "event: matched\ndata: {\"type\":\"matched\",\"optimistic_id\":\"67e55044-10b1-426f-9247-bb680e5fe0c8\",\"user_id_1\":1,\"user_id_2\":2}\n\n";
+        match parse_block(matched) {
+            Some(Ok(StreamEvent::Pairing(PairingEvent::Matched { user_id_1, user_id_2, .. }))) => {
+                assert_eq!((user_id_1, user_id_2), (1, 2));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        assert!(matches!(parse_block("event: lagged\ndata: 3\n\n"), Some(Ok(StreamEvent::Lagged(3)))));
+        assert!(matches!(parse_block("event: matched\ndata: nope\n\n"), Some(Err(ClientError::Stream(_)))));
+    }
+}
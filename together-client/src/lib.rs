@@ -0,0 +1,353 @@
+//! Typed async client for the Together backend API
+//!
+//! Every `/api/v2` route in `together::router::create_router` has a method here, using the
+//! server's own request and response types (see [`types`]).
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), together_client::ClientError> {
+//! let client = together_client::TogetherClient::new("https://api.togetherapp.app");
+//! let profile = client.get_profile("0xAefC770D8515C552C952a30e597d9fbEa99aA756", &Default::default()).await?;
+//! println!("{} connections", profile.total_connections);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod events;
+pub mod types;
+
+use reqwest::{Method, RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use uuid::Uuid;
+
+pub use events::{EventStream, StreamEvent};
+use types::*;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    /// The server answered with a non-2xx status; `message` is its `error` field, or the raw body
+    /// when something other than the API answered (a proxy, say), in which case `code` is `None`.
+    #[error("server returned HTTP {status}: {message}")]
+    Api {
+        status: u16,
+        message: String,
+        code: Option<ErrorCode>,
+        request_id: Option<String>,
+    },
+    #[error("malformed event stream: {0}")]
+    Stream(String),
+}
+
+impl ClientError {
+    /// HTTP status of an API error
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ClientError::Api { status, .. } => Some(*status),
+            ClientError::Http(e) => e.status().map(|status| status.as_u16()),
+            ClientError::Stream(_) => None,
+        }
+    }
+
+    /// Stable error code of an API error, when the endpoint sent one
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            ClientError::Api { code, .. } => *code,
+            _ => None,
+        }
+    }
+}
+
+pub type Result<T, E = ClientError> = std::result::Result<T, E>;
+
+#[derive(Debug, Clone)]
+pub struct TogetherClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl TogetherClient {
+    /// Client for the server at `base_url`, e.g. `http://localhost:3000`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(reqwest::Client::new(), base_url)
+    }
+
+    /// Use a preconfigured `reqwest::Client` (timeouts, proxies, ...)
+    pub fn with_http_client(http: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: None,
+        }
+    }
+
+    /// Send `Authorization: Bearer <token>` on every request
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn set_token(&mut self, token: Option<String>) {
+        self.token = token;
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self.http.request(method, format!("{}{}", self.base_url, path));
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send(request: RequestBuilder) -> Result<Response> {
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        let status = status.as_u16();
+        if let Ok(error) = serde_json::from_str::<ApiErrorBody>(&body) {
+            return Err(ClientError::Api {
+                status,
+                message: error.error,
+                code: Some(error.code),
+                request_id: error.request_id,
+            });
+        }
+        Err(ClientError::Api { status, message: body, code: None, request_id: None })
+    }
+
+    async fn json<T: DeserializeOwned>(request: RequestBuilder) -> Result<T> {
+        Ok(Self::send(request).await?.json().await?)
+    }
+
+    async fn text(request: RequestBuilder) -> Result<String> {
+        Ok(Self::send(request).await?.text().await?)
+    }
+
+    async fn bytes(request: RequestBuilder) -> Result<Vec<u8>> {
+        Ok(Self::send(request).await?.bytes().await?.to_vec())
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        Self::json(self.request(Method::GET, path)).await
+    }
+
+    async fn post<B: Serialize + ?Sized, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T> {
+        Self::json(self.request(Method::POST, path).json(body)).await
+    }
+
+    // =========================================================================
+    // Health
+    // =========================================================================
+
+    pub async fn health(&self) -> Result<String> {
+        Self::text(self.request(Method::GET, "/health")).await
+    }
+
+    // =========================================================================
+    // Auth
+    // =========================================================================
+
+    /// Nonce to embed in the SIWE message the wallet signs
+    pub async fn create_auth_nonce(&self, wallet_address: &str) -> Result<AuthNonceResponse> {
+        self.post("/api/v2/auth/nonces", &AuthNonceRequest {
+            wallet_address: wallet_address.to_string(),
+        }).await
+    }
+
+    /// Exchange a signed SIWE message for a session; pass the token to [`Self::with_token`]
+    pub async fn create_session(&self, message: &str, signature: &str) -> Result<SessionResponse> {
+        self.post("/api/v2/auth/sessions", &CreateSessionRequest {
+            message: message.to_string(),
+            signature: signature.to_string(),
+        }).await
+    }
+
+    /// Sign out the current token
+    pub async fn delete_session(&self) -> Result<()> {
+        Self::send(self.request(Method::DELETE, "/api/v2/auth/sessions/current")).await?;
+        Ok(())
+    }
+
+    /// A single-use ticket for opening the event stream without a bearer header
+    pub async fn create_stream_ticket(&self) -> Result<StreamTicketResponse> {
+        Self::json(self.request(Method::POST, "/api/v2/auth/stream-tickets")).await
+    }
+
+    // =========================================================================
+    // Profiles and embeds
+    // =========================================================================
+
+    pub async fn get_profile(&self, address: &str, query: &ProfileQuery) -> Result<UserProfile> {
+        Self::json(self.request(Method::GET, &format!("/api/v2/profiles/{}", address)).query(query)).await
+    }
+
+    pub async fn get_profile_card_svg(&self, address: &str) -> Result<String> {
+        Self::text(self.request(Method::GET, &format!("/api/v2/profiles/{}/card.svg", address))).await
+    }
+
+    pub async fn get_profile_card_png(&self, address: &str) -> Result<Vec<u8>> {
+        Self::bytes(self.request(Method::GET, &format!("/api/v2/profiles/{}/card.png", address))).await
+    }
+
+    pub async fn get_profile_og_image(&self, address: &str) -> Result<Vec<u8>> {
+        Self::bytes(self.request(Method::GET, &format!("/api/v2/profiles/{}/og-image", address))).await
+    }
+
+    pub async fn get_badge(&self, address: &str, query: &BadgeQuery) -> Result<String> {
+        Self::text(self.request(Method::GET, &format!("/api/v2/profiles/{}/badge.svg", address)).query(query)).await
+    }
+
+    /// The pair's attestation, or `None` if they've never been together
+    pub async fn check_together(&self, address_1: &str, address_2: &str) -> Result<Option<TogetherAttestation>> {
+        self.get(&format!("/api/v2/profiles/{}/together/{}", address_1, address_2)).await
+    }
+
+    // =========================================================================
+    // Users and pairing
+    // =========================================================================
+
+    pub async fn get_or_create_user(&self, address: &str) -> Result<UserResponse> {
+        self.post("/api/v2/users", &CreateUserRequest { wallet_address: address.to_string() }).await
+    }
+
+    pub async fn get_pairing_code(&self, user_id: i32) -> Result<PairingCodeResponse> {
+        self.get(&format!("/api/v2/users/{}/pairing-code", user_id)).await
+    }
+
+    pub async fn create_proximity_challenge(&self, user_id: i32) -> Result<ProximityChallengeResponse> {
+        Self::json(self.request(Method::POST, &format!("/api/v2/users/{}/proximity-challenges", user_id))).await
+    }
+
+    pub async fn report_proximity_challenge(&self, user_id: i32, nonce: &str) -> Result<ReportProximityResponse> {
+        self.post(&format!("/api/v2/users/{}/proximity-responses", user_id), &ReportProximityRequest {
+            nonce: nonce.to_string(),
+        }).await
+    }
+
+    pub async fn create_pending_connection(
+        &self,
+        from_user_id: i32,
+        request: &CreatePendingConnectionRequest,
+    ) -> Result<PendingConnectionResponse> {
+        self.post(&format!("/api/v2/users/{}/pending-connections", from_user_id), request).await
+    }
+
+    pub async fn get_user_pending_connections(&self, user_id: i32) -> Result<UserPendingConnectionsResponse> {
+        self.get(&format!("/api/v2/users/{}/pending-connections", user_id)).await
+    }
+
+    pub async fn cancel_pending_connection(&self, user_id: i32, connection_id: Uuid, reason: ResolutionReason) -> Result<()> {
+        let path = format!("/api/v2/users/{}/pending-connections/{}", user_id, connection_id);
+        Self::send(self.request(Method::DELETE, &path).query(&ResolvePendingConnectionQuery { reason })).await?;
+        Ok(())
+    }
+
+    pub async fn decline_pending_connection(&self, user_id: i32, connection_id: Uuid, reason: ResolutionReason) -> Result<()> {
+        let path = format!("/api/v2/users/{}/pending-connections/{}/decline", user_id, connection_id);
+        Self::send(self.request(Method::POST, &path).query(&ResolvePendingConnectionQuery { reason })).await?;
+        Ok(())
+    }
+
+    pub async fn get_user_optimistic_connections(&self, user_id: i32) -> Result<UserOptimisticConnectionsResponse> {
+        self.get(&format!("/api/v2/users/{}/optimistic-connections", user_id)).await
+    }
+
+    /// Follow the user's pairing lifecycle events as they happen
+    pub async fn stream_user_events(&self, user_id: i32) -> Result<EventStream> {
+        let response = Self::send(self.request(Method::GET, &format!("/api/v2/users/{}/events", user_id))).await?;
+        Ok(EventStream::new(response))
+    }
+
+    /// The user's personal data export, as a ZIP archive
+    pub async fn export_user_data(&self, user_id: i32) -> Result<Vec<u8>> {
+        Self::bytes(self.request(Method::GET, &format!("/api/v2/users/{}/export", user_id))).await
+    }
+
+    pub async fn get_account_deletion(&self, user_id: i32) -> Result<AccountDeletionResponse> {
+        self.get(&format!("/api/v2/users/{}/deletion", user_id)).await
+    }
+
+    pub async fn request_account_deletion(&self, user_id: i32) -> Result<AccountDeletionResponse> {
+        Self::json(self.request(Method::POST, &format!("/api/v2/users/{}/deletion", user_id))).await
+    }
+
+    pub async fn cancel_account_deletion(&self, user_id: i32) -> Result<AccountDeletionResponse> {
+        Self::json(self.request(Method::DELETE, &format!("/api/v2/users/{}/deletion", user_id))).await
+    }
+
+    // =========================================================================
+    // Attestations and proofs
+    // =========================================================================
+
+    pub async fn attest_together(&self, request: &AttestTogetherRequest) -> Result<AttestTogetherResponse> {
+        self.post("/api/v2/signatures", request).await
+    }
+
+    pub async fn get_signature_status(&self, nonce: &str) -> Result<SignatureStatusResponse> {
+        self.get(&format!("/api/v2/signatures/{}", nonce)).await
+    }
+
+    pub async fn get_proof_certificate(&self, address_a: &str, address_b: &str) -> Result<ProofCertificate> {
+        self.get(&format!("/api/v2/proofs/{}/{}", address_a, address_b)).await
+    }
+
+    pub async fn get_latest_merkle_snapshot(&self) -> Result<MerkleSnapshotResponse> {
+        self.get("/api/v2/merkle/snapshots/latest").await
+    }
+
+    pub async fn get_merkle_proof(
+        &self,
+        address_a: &str,
+        address_b: &str,
+        timestamp: i64,
+        query: &MerkleProofQuery,
+    ) -> Result<MerkleProofResponse> {
+        let path = format!("/api/v2/merkle/proofs/{}/{}/{}", address_a, address_b, timestamp);
+        Self::json(self.request(Method::GET, &path).query(query)).await
+    }
+
+    pub async fn get_credentials(&self, address: &str, query: &CredentialsQuery) -> Result<Vec<VerifiableCredential>> {
+        Self::json(self.request(Method::GET, &format!("/api/v2/profiles/{}/credentials", address)).query(query)).await
+    }
+
+    pub async fn get_eas_attestations(&self, address: &str, query: &CredentialsQuery) -> Result<Vec<OffchainAttestation>> {
+        Self::json(self.request(Method::GET, &format!("/api/v2/profiles/{}/eas-attestations", address)).query(query)).await
+    }
+
+    // =========================================================================
+    // Pair collectibles
+    // =========================================================================
+
+    /// ERC-721 metadata for a pair, by decimal or 0x-prefixed token id
+    pub async fn get_pair_metadata(&self, token_id: &str) -> Result<PairMetadata> {
+        self.get(&format!("/api/v2/pairs/{}", token_id)).await
+    }
+
+    pub async fn get_pair_image(&self, token_id: &str) -> Result<String> {
+        Self::text(self.request(Method::GET, &format!("/api/v2/pairs/{}/image.svg", token_id))).await
+    }
+
+    // =========================================================================
+    // RPC proxy
+    // =========================================================================
+
+    /// Forward a JSON-RPC request through the server's Worldchain proxy
+    pub async fn proxy_rpc(&self, body: &Value, query: &RpcQuery) -> Result<Value> {
+        Self::json(self.request(Method::POST, "/api/v2/rpc").query(query).json(body)).await
+    }
+}
@@ -0,0 +1,29 @@
+//! Request and response types, shared with the server so the two can't drift apart
+
+pub use together_types::{
+    // Errors
+    error::{ApiErrorBody, ErrorCode},
+    // Auth
+    auth::{AuthNonceRequest, AuthNonceResponse, CreateSessionRequest, SessionResponse, StreamTicketResponse},
+    // Users, profiles and connections
+    together::{
+        UserResponse, ProfileQuery, CreateUserRequest, PairingCodeResponse,
+        CreatePendingConnectionRequest, ResolvePendingConnectionQuery, PendingConnectionResponse,
+        UserPendingConnectionsResponse, OptimisticConnectionResponse, UserOptimisticConnectionsResponse,
+        AttestTogetherRequest, AttestTogetherResponse,
+    },
+    proximity::{ProximityChallengeResponse, ReportProximityRequest, ReportProximityResponse},
+    account::AccountDeletionResponse,
+    // Attestations and proofs
+    signatures::SignatureStatusResponse,
+    merkle::{MerkleSnapshotResponse, MerkleProofQuery, MerkleProofResponse},
+    credentials::CredentialsQuery,
+    models::{UserProfile, ConnectionInfo, TogetherAttestation, ResolutionReason},
+    proof::{ProofCertificate, OffchainAttestation, VerifiableCredential},
+    // Collectibles and embeds
+    pairs::{PairMetadata, MetadataAttribute},
+    badge::{BadgeQuery, BadgeMetric, BadgeStyle},
+    rpc::RpcQuery,
+    // Event stream
+    events::PairingEvent,
+};
@@ -0,0 +1,12 @@
+//! Account deletion
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AccountDeletionResponse {
+    pub status: String,
+    pub requested_at: String,
+    pub scheduled_for: String,
+    pub cancelled_at: Option<String>,
+}
@@ -0,0 +1,36 @@
+//! Sign-in and session bodies
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuthNonceRequest {
+    pub wallet_address: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuthNonceResponse {
+    pub nonce: String,
+    pub expires_at: String,
+}
+
+// Wallet-auth result from the mini-app: the SIWE message and its signature
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateSessionRequest {
+    pub message: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SessionResponse {
+    pub token: String,
+    pub user_id: i32,
+    pub wallet_address: String,
+    pub expires_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StreamTicketResponse {
+    pub ticket: String,
+    pub expires_at: String,
+}
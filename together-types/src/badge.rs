@@ -0,0 +1,32 @@
+//! Embeddable count badges
+
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum BadgeStyle {
+    #[default]
+    Flat,
+    FlatSquare,
+    ForTheBadge,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BadgeMetric {
+    #[default]
+    Total,
+    Partners,
+    Month,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BadgeQuery {
+    #[serde(default)]
+    pub style: BadgeStyle,
+    pub label: Option<String>,
+    #[serde(default)]
+    pub metric: BadgeMetric,
+}
@@ -0,0 +1,12 @@
+//! Paging for credential and EAS exports
+
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CredentialsQuery {
+    pub offset: Option<i64>,
+    /// Attestations to export, at most 100 (default 50)
+    pub limit: Option<i64>,
+}
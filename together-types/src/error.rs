@@ -0,0 +1,42 @@
+//! Error bodies with stable, machine-readable codes
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Stable identifier for each kind of failure; clients should branch on this, not the message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    InvalidAddress,
+    InvalidRequest,
+    InvalidPairingCode,
+    Unauthorized,
+    Forbidden,
+    ProximityProofRequired,
+    UserNotFound,
+    PairingCodeNotFound,
+    PendingConnectionNotFound,
+    NotFound,
+    PairingCodeAmbiguous,
+    PendingConnectionExists,
+    AlreadyExists,
+    AccountErased,
+    SelfConnection,
+    PendingLimitReached,
+    OptimisticLimitReached,
+    DeclineCooldown,
+    SignatureQuotaExceeded,
+    RateLimited,
+    SignerUnavailable,
+    Internal,
+}
+
+/// Body of every API error response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApiErrorBody {
+    pub error: String,
+    pub code: ErrorCode,
+    /// Also sent as the `x-request-id` header; quote it when reporting a problem
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
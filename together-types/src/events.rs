@@ -0,0 +1,86 @@
+//! Pairing lifecycle events, as sent over the event stream
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Lifecycle of a pairing, from the first pending request to on-chain confirmation
+///
+/// Events are published with `pg_notify` by whichever process observes them (server,
+/// connection checker, attestation watcher) and re-broadcast in-process by every
+/// server that runs the event listener.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PairingEvent {
+    PendingCreated {
+        pending_id: Uuid,
+        from_user_id: i32,
+        to_user_id: i32,
+        expires_at: DateTime<Utc>,
+    },
+    Cancelled {
+        pending_id: Uuid,
+        from_user_id: i32,
+        to_user_id: i32,
+    },
+    Declined {
+        pending_id: Uuid,
+        from_user_id: i32,
+        to_user_id: i32,
+    },
+    Matched {
+        optimistic_id: Uuid,
+        user_id_1: i32,
+        user_id_2: i32,
+    },
+    TxSent {
+        user_id_1: i32,
+        user_id_2: i32,
+        tx_hash: String,
+    },
+    Confirmed {
+        user_id_1: i32,
+        user_id_2: i32,
+        tx_hash: String,
+        block_number: i64,
+    },
+    Failed {
+        user_id_1: i32,
+        user_id_2: i32,
+        reason: String,
+    },
+}
+
+impl PairingEvent {
+    /// SSE event name, matching the serialized `type` tag
+    pub fn name(&self) -> &'static str {
+        match self {
+            PairingEvent::PendingCreated { .. } => "pending_created",
+            PairingEvent::Cancelled { .. } => "cancelled",
+            PairingEvent::Declined { .. } => "declined",
+            PairingEvent::Matched { .. } => "matched",
+            PairingEvent::TxSent { .. } => "tx_sent",
+            PairingEvent::Confirmed { .. } => "confirmed",
+            PairingEvent::Failed { .. } => "failed",
+        }
+    }
+
+    /// The two users this event should be delivered to
+    pub fn user_ids(&self) -> (i32, i32) {
+        match self {
+            PairingEvent::PendingCreated { from_user_id, to_user_id, .. }
+            | PairingEvent::Cancelled { from_user_id, to_user_id, .. }
+            | PairingEvent::Declined { from_user_id, to_user_id, .. } => (*from_user_id, *to_user_id),
+            PairingEvent::Matched { user_id_1, user_id_2, .. }
+            | PairingEvent::TxSent { user_id_1, user_id_2, .. }
+            | PairingEvent::Confirmed { user_id_1, user_id_2, .. }
+            | PairingEvent::Failed { user_id_1, user_id_2, .. } => (*user_id_1, *user_id_2),
+        }
+    }
+
+    pub fn involves(&self, user_id: i32) -> bool {
+        let (a, b) = self.user_ids();
+        a == user_id || b == user_id
+    }
+}
@@ -0,0 +1,19 @@
+//! Request and response types shared by the Together server and `together-client`
+//!
+//! Kept apart from the server so the client builds without it. Modules follow the
+//! server's handlers; enable the `sqlx` feature for `FromRow` on the row-shaped types.
+
+pub mod error;
+pub mod auth;
+pub mod together;
+pub mod proximity;
+pub mod account;
+pub mod signatures;
+pub mod merkle;
+pub mod credentials;
+pub mod pairs;
+pub mod badge;
+pub mod rpc;
+pub mod models;
+pub mod proof;
+pub mod events;
@@ -0,0 +1,29 @@
+//! Merkle snapshots and inclusion proofs
+
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MerkleProofQuery {
+    pub snapshot_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MerkleSnapshotResponse {
+    pub snapshot_id: i64,
+    pub root: String,
+    pub leaf_count: i64,
+    pub block_height: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MerkleProofResponse {
+    pub snapshot: MerkleSnapshotResponse,
+    pub address_1: String,
+    pub address_2: String,
+    pub timestamp: i64,
+    pub leaf: String,
+    pub proof: Vec<String>,
+}
@@ -0,0 +1,65 @@
+//! Attestations and profiles as stored and served
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct TogetherAttestation {
+    pub id: Uuid,
+    pub address_1: String,
+    pub address_2: String,
+    pub attestation_timestamp: i64,
+    pub tx_hash: Option<String>,
+    pub block_number: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub block_hash: Option<String>,
+    pub log_index: Option<i64>,
+}
+
+// DTOs for API responses
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserProfile {
+    pub address: String,
+    pub username: Option<String>,
+    pub profile_picture_url: Option<String>,
+    pub total_connections: i64,
+    pub recent_connections: Vec<ConnectionInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct ConnectionInfo {
+    pub partner_address: String,
+    pub attestation_timestamp: i64,
+    pub tx_hash: Option<String>,
+    pub partner_username: Option<String>,
+    pub connection_strength: Option<i64>, // Number of times connected with this partner
+    pub has_optimistic: Option<bool>, // Whether there are unprocessed optimistic connections
+}
+
+/// Why a pending connection was cancelled or declined (stored for analytics)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionReason {
+    WrongUser,
+    NotTogether,
+    Duplicate,
+    Spam,
+    #[default]
+    Other,
+}
+
+impl ResolutionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResolutionReason::WrongUser => "wrong_user",
+            ResolutionReason::NotTogether => "not_together",
+            ResolutionReason::Duplicate => "duplicate",
+            ResolutionReason::Spam => "spam",
+            ResolutionReason::Other => "other",
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! Pair collectible metadata
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// ERC-721 metadata JSON (OpenSea flavour) for a pair
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PairMetadata {
+    pub name: String,
+    pub description: String,
+    pub image: String,
+    pub attributes: Vec<MetadataAttribute>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MetadataAttribute {
+    pub trait_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_type: Option<String>,
+    pub value: serde_json::Value,
+}
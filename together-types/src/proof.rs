@@ -0,0 +1,160 @@
+//! Signed proofs: certificates, offchain EAS attestations and verifiable credentials
+
+use alloy_primitives::{Address, B256, Bytes};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A backend-signed statement that an on-chain Together event happened
+///
+/// Everything needed to check it is inside: the event's position on chain and an
+/// EIP-712 signature (domain `PROOF_DOMAIN_NAME`/`PROOF_DOMAIN_VERSION` on Worldchain,
+/// no verifying contract) over all of it by one of the attestation signers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ProofCertificate {
+    pub version: u8,
+    #[schema(value_type = String)]
+    pub address_1: Address,
+    #[schema(value_type = String)]
+    pub address_2: Address,
+    pub timestamp: u64,
+    #[schema(value_type = String)]
+    pub tx_hash: B256,
+    pub block_number: u64,
+    #[schema(value_type = String)]
+    pub block_hash: B256,
+    pub log_index: u64,
+    #[schema(value_type = String)]
+    pub contract_address: Address,
+    pub chain_id: u64,
+    pub issued_at: u64,
+    #[schema(value_type = String)]
+    pub signer: Address,
+    pub signature: String,
+}
+
+/// An offchain EAS attestation in the JSON shape the EAS SDK and explorers import
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct OffchainAttestation {
+    pub sig: OffchainAttestationSig,
+    #[schema(value_type = String)]
+    pub signer: Address,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OffchainAttestationSig {
+    pub domain: EasDomain,
+    pub primary_type: String,
+    pub types: serde_json::Value,
+    pub message: AttestMessage,
+    #[schema(value_type = String)]
+    pub uid: B256,
+    pub signature: SignatureParts,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EasDomain {
+    pub name: String,
+    pub version: String,
+    #[serde(with = "u64_string")]
+    #[schema(value_type = String)]
+    pub chain_id: u64,
+    #[schema(value_type = String)]
+    pub verifying_contract: Address,
+}
+
+/// `Attest` as the SDK serializes it, with uint64s as decimal strings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestMessage {
+    pub version: u16,
+    #[schema(value_type = String)]
+    pub schema: B256,
+    #[schema(value_type = String)]
+    pub recipient: Address,
+    #[serde(with = "u64_string")]
+    #[schema(value_type = String)]
+    pub time: u64,
+    #[serde(with = "u64_string")]
+    #[schema(value_type = String)]
+    pub expiration_time: u64,
+    pub revocable: bool,
+    #[serde(rename = "refUID")]
+    #[schema(value_type = String)]
+    pub ref_uid: B256,
+    #[schema(value_type = String)]
+    pub data: Bytes,
+    #[schema(value_type = String)]
+    pub salt: B256,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SignatureParts {
+    pub v: u8,
+    #[schema(value_type = String)]
+    pub r: B256,
+    #[schema(value_type = String)]
+    pub s: B256,
+}
+
+mod u64_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A "these two wallets met" credential in W3C VC data model 1.1 form
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiableCredential {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    #[serde(rename = "type")]
+    pub credential_type: Vec<String>,
+    pub id: String,
+    pub issuer: String,
+    pub issuance_date: String,
+    pub credential_subject: CredentialSubject,
+    pub evidence: CredentialEvidence,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof: Option<CredentialProof>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialSubject {
+    pub id: String,
+    pub together_with: String,
+    pub met_at: String,
+}
+
+/// Where the claim comes from: the Together event on chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialEvidence {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub evidence_type: Vec<String>,
+    pub transaction_hash: String,
+    pub block_number: String,
+    pub contract_address: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialProof {
+    #[serde(rename = "type")]
+    pub proof_type: String,
+    pub created: String,
+    pub proof_purpose: String,
+    pub verification_method: String,
+    pub proof_value: String,
+    pub eip712: serde_json::Value,
+}
@@ -0,0 +1,23 @@
+//! Proximity challenges
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ProximityChallengeResponse {
+    pub challenge_id: String,
+    pub nonce: String,
+    pub expires_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReportProximityRequest {
+    pub nonce: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReportProximityResponse {
+    pub challenge_id: String,
+    pub issuer_user_id: i32,
+    pub latency_ms: i64,
+}
@@ -0,0 +1,12 @@
+//! The JSON-RPC proxy
+
+use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
+
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RpcQuery {
+    /// `worldchain` (default), `base`, `ethereum` or `polygon`
+    #[serde(default)]
+    pub network: Option<String>,
+}
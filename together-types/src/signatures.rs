@@ -0,0 +1,19 @@
+//! Issued attestation signatures
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SignatureStatusResponse {
+    pub nonce: String,
+    pub on_behalf_of: String,
+    pub together_with: String,
+    pub attestation_timestamp: i64,
+    pub deadline: i64,
+    pub source: String,
+    pub issued_at: String,
+    pub consumed: bool,
+    pub consumed_at: Option<String>,
+    pub consumed_tx_hash: Option<String>,
+    pub expired: bool,
+}
@@ -0,0 +1,106 @@
+//! Users, profiles, pairing codes, pending and optimistic connections, and attestation signatures
+
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use crate::models::ResolutionReason;
+
+// Request to create an attestation signature
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AttestTogetherRequest {
+    pub my_address: String,
+    pub partner_address: String, 
+    pub timestamp: i64,
+    pub password: String,
+    pub my_username: Option<String>,
+    pub partner_username: Option<String>,
+    pub my_profile_picture_url: Option<String>,
+    pub partner_profile_picture_url: Option<String>,
+}
+
+// Response with signature for on-chain attestation
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AttestTogetherResponse {
+    pub signature: String,
+    pub nonce: String,
+    pub deadline: u64,
+}
+
+// Query parameters for profile endpoint
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProfileQuery {
+    #[serde(default)]
+    pub limit: Option<i64>,
+    pub username: Option<String>,
+    pub profile_picture_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateUserRequest {
+    pub wallet_address: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserResponse {
+    pub id: i32,
+    pub wallet_address: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreatePendingConnectionRequest {
+    /// The recipient's current rotating pairing code
+    pub pairing_code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PairingCodeResponse {
+    pub code: String,
+    pub expires_at: String,
+    pub rotation_seconds: i64,
+}
+
+// Query parameters for cancelling or declining a pending connection
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ResolvePendingConnectionQuery {
+    #[serde(default)]
+    pub reason: ResolutionReason,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PendingConnectionResponse {
+    pub id: String,
+    pub from_user_id: i32,
+    pub to_user_id: i32,
+    pub from_user_address: Option<String>,
+    pub to_user_address: Option<String>,
+    pub from_username: Option<String>,
+    pub to_username: Option<String>,
+    pub created_at: String,
+    pub expires_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserPendingConnectionsResponse {
+    pub outgoing: Vec<PendingConnectionResponse>,
+    pub incoming: Vec<PendingConnectionResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OptimisticConnectionResponse {
+    pub id: String,
+    pub user_id_1: i32,
+    pub user_id_2: i32,
+    pub user_1_address: Option<String>,
+    pub user_2_address: Option<String>,
+    pub user_1_username: Option<String>,
+    pub user_2_username: Option<String>,
+    pub processed: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UserOptimisticConnectionsResponse {
+    pub connections: Vec<OptimisticConnectionResponse>,
+}